@@ -1,7 +1,11 @@
 use rquickjs::{
-    class::Trace, Array, FromJs, IntoJs, IteratorJs, String as JsString, Type, Value as JsValue,
+    class::Trace, qjs, Array, FromJs, IntoJs, IteratorJs, String as JsString, Type,
+    Value as JsValue,
+};
+use vaerdi::{
+    limits::{LimitError, LimitTracker, Limits},
+    List, Map, Value,
 };
-use vaerdi::{Map, Value};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Val(pub vaerdi::Value);
@@ -29,45 +33,125 @@ macro_rules! un {
     };
 }
 
+/// Convert `value` within `limits`, for values from scripts that are not trusted.
+/// An exceeded limit is an `Error::Io` of kind `InvalidData` holding the `LimitError`.
+pub fn from_js_with_limits<'js>(
+    ctx: &rquickjs::prelude::Ctx<'js>,
+    value: rquickjs::Value<'js>,
+    limits: Limits,
+) -> rquickjs::Result<Value> {
+    let mut tracker = LimitTracker::new(limits);
+    from_js(ctx, value, &mut tracker).map_err(|err| match err {
+        Error::Limit(err) => {
+            rquickjs::Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+        }
+        Error::Js(err) => err,
+    })
+}
+
+enum Error {
+    Limit(LimitError),
+    Js(rquickjs::Error),
+}
+
+impl From<LimitError> for Error {
+    fn from(value: LimitError) -> Self {
+        Error::Limit(value)
+    }
+}
+
+impl From<rquickjs::Error> for Error {
+    fn from(value: rquickjs::Error) -> Self {
+        Error::Js(value)
+    }
+}
+
 fn from_js<'js>(
-    _ctx: &rquickjs::prelude::Ctx<'js>,
+    ctx: &rquickjs::prelude::Ctx<'js>,
     value: rquickjs::Value<'js>,
-) -> rquickjs::Result<Val> {
-    match value.type_of() {
-        Type::Bool => Ok(Val(Value::Bool(value.as_bool().unwrap()))),
-        Type::String => Ok(Val(Value::String(
-            un!(value.try_into_string())?.to_string()?.into(),
-        ))),
-        Type::Int => Ok(Val(Value::Number(value.as_int().unwrap().into()))),
-        Type::Float => Ok(Val(Value::Number(value.as_float().unwrap().into()))),
-        Type::Null | Type::Undefined => Ok(Val(Value::Null)),
+    tracker: &mut LimitTracker,
+) -> Result<Value, Error> {
+    tracker.value()?;
+    let val = match value.type_of() {
+        Type::Bool => Value::Bool(value.as_bool().unwrap()),
+        Type::String => {
+            let s = un!(value.try_into_string())?;
+            let s = to_string(ctx, &s, tracker)?;
+            Value::String(s.into())
+        }
+        Type::Int => Value::Number(value.as_int().unwrap().into()),
+        Type::Float => Value::Number(value.as_float().unwrap().into()),
+        Type::Null | Type::Undefined => Value::Null,
         Type::Array => {
             let array = un!(value.try_into_array())?;
-            Ok(Val(Value::List(
-                array
-                    .iter::<Val>()
-                    .map(|m| m.map(|m| m.0))
-                    .collect::<Result<_, _>>()?,
-            )))
+            tracker.enter()?;
+            if !array.is_empty() {
+                tracker.item(array.len() - 1)?;
+            }
+            let mut list = List::with_capacity(array.len());
+            for (idx, item) in array.iter::<JsValue>().enumerate() {
+                let item = from_js(ctx, item?, tracker).map_err(|err| match err {
+                    Error::Limit(err) => Error::Limit(err.with_index(idx)),
+                    err => err,
+                })?;
+                list.push(item);
+            }
+            tracker.leave();
+            Value::List(list.into())
         }
         Type::Object => {
             let object = un!(value.try_into_object())?;
+            tracker.enter()?;
 
             let mut map = Map::default();
-            for k in object.keys::<String>() {
+            for (idx, k) in object.keys::<String>().enumerate() {
                 let k = k?;
-                let v = object.get::<_, Val>(&k)?;
-                map.insert(k, v.0);
+                tracker.item(idx)?;
+                tracker.text(k.len())?;
+                let v =
+                    from_js(ctx, object.get::<_, JsValue>(&k)?, tracker).map_err(
+                        |err| match err {
+                            Error::Limit(err) => Error::Limit(err.with_field(k.as_str())),
+                            err => err,
+                        },
+                    )?;
+                map.insert(k, v);
             }
-
-            Ok(Val(Value::Map(map)))
+            tracker.leave();
+            Value::Map(map)
         }
         Type::Exception => {
             let exption = un!(value.try_into_exception())?;
-            Ok(Val(Value::String(exption.to_string().into())))
+            Value::String(exption.to_string().into())
         }
-        _ => Err(rquickjs::Error::new_from_js("value", "value")),
-    }
+        _ => return Err(rquickjs::Error::new_from_js("value", "value").into()),
+    };
+
+    Ok(val)
+}
+
+/// Checks the length of `s` against the limits before it is copied out
+fn to_string(
+    ctx: &rquickjs::prelude::Ctx<'_>,
+    s: &JsString<'_>,
+    tracker: &mut LimitTracker,
+) -> Result<String, Error> {
+    // The UTF-8 form is never shorter than the UTF-16 code units of `length`
+    // SAFETY: `s` is a live value of `ctx` and the result is owned by the `JsValue`
+    let len = unsafe {
+        let raw = qjs::JS_GetPropertyStr(
+            ctx.as_raw().as_ptr(),
+            s.as_value().as_raw(),
+            c"length".as_ptr(),
+        );
+        JsValue::from_raw(ctx.clone(), raw)
+    };
+    let len = un!(len.as_int().ok_or(len))?;
+    tracker.text_at_least(len as usize)?;
+
+    let s = s.to_string()?;
+    tracker.text(s.len())?;
+    Ok(s)
 }
 
 impl<'js> FromJs<'js> for Val {
@@ -75,7 +159,7 @@ impl<'js> FromJs<'js> for Val {
         ctx: &rquickjs::prelude::Ctx<'js>,
         value: rquickjs::Value<'js>,
     ) -> rquickjs::Result<Self> {
-        from_js(ctx, value)
+        from_js_with_limits(ctx, value, Limits::default()).map(Val)
     }
 }
 
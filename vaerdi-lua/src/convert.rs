@@ -1,31 +1,85 @@
-use vaerdi::{List, Map, Value};
+use vaerdi::{
+    limits::{LimitError, LimitTracker, Limits},
+    List, Map, Value,
+};
 
 pub fn from_lua(value: mlua::Value<'_>) -> mlua::Result<vaerdi::Value> {
+    from_lua_with_limits(value, Limits::default())
+}
+
+/// Convert `value` within `limits`, tables may come from untrusted scripts
+/// and even reference themselves. An exceeded limit is an external error
+/// holding the `LimitError`.
+pub fn from_lua_with_limits(value: mlua::Value<'_>, limits: Limits) -> mlua::Result<vaerdi::Value> {
+    let mut tracker = LimitTracker::new(limits);
+    from_lua_limited(value, &mut tracker).map_err(|err| match err {
+        Error::Limit(err) => mlua::Error::external(err),
+        Error::Lua(err) => err,
+    })
+}
+
+enum Error {
+    Limit(LimitError),
+    Lua(mlua::Error),
+}
+
+impl From<LimitError> for Error {
+    fn from(value: LimitError) -> Self {
+        Error::Limit(value)
+    }
+}
+
+impl From<mlua::Error> for Error {
+    fn from(value: mlua::Error) -> Self {
+        Error::Lua(value)
+    }
+}
+
+fn from_lua_limited(value: mlua::Value<'_>, tracker: &mut LimitTracker) -> Result<Value, Error> {
+    tracker.value()?;
     let val = match value {
         mlua::Value::Boolean(b) => Value::Bool(b),
         mlua::Value::Integer(i) => Value::Number(i.into()),
         mlua::Value::Number(i) => Value::Number(i.into()),
-        mlua::Value::String(s) => Value::String(s.to_str()?.into()),
+        mlua::Value::String(s) => {
+            let s = s.to_str()?;
+            tracker.text(s.len())?;
+            Value::String(s.into())
+        }
         mlua::Value::Table(table) => {
+            tracker.enter()?;
             let len = table.raw_len();
-            if len > 0 {
-                let mut list = List::with_capacity(table.len()? as usize);
-                for item in table.sequence_values::<mlua::Value>() {
-                    let item = item?;
-                    list.push(from_lua(item)?);
+            let val = if len > 0 {
+                tracker.item(len - 1)?;
+                let mut list = List::with_capacity(len);
+                for (idx, item) in table.sequence_values::<mlua::Value>().enumerate() {
+                    let item = from_lua_limited(item?, tracker).map_err(|err| match err {
+                        Error::Limit(err) => Error::Limit(err.with_index(idx)),
+                        err => err,
+                    })?;
+                    list.push(item);
                 }
                 Value::List(list.into())
             } else {
-                let mut map = Map::with_capacity(table.len()? as usize);
-                for pair in table.pairs::<mlua::String, mlua::Value>() {
+                let mut map = Map::default();
+                for (idx, pair) in table.pairs::<mlua::String, mlua::Value>().enumerate() {
                     let (k, v) = pair?;
-                    map.insert(k.to_str()?, from_lua(v)?);
+                    let k = k.to_str()?;
+                    tracker.item(idx)?;
+                    tracker.text(k.len())?;
+                    let v = from_lua_limited(v, tracker).map_err(|err| match err {
+                        Error::Limit(err) => Error::Limit(err.with_field(k)),
+                        err => err,
+                    })?;
+                    map.insert(k, v);
                 }
                 Value::Map(map)
-            }
+            };
+            tracker.leave();
+            val
         }
         mlua::Value::Nil => Value::Null,
-        _ => return Err(mlua::Error::external("unsupported lua type")),
+        _ => return Err(mlua::Error::external("unsupported lua type").into()),
     };
 
     Ok(val)
@@ -0,0 +1,348 @@
+use syn::{meta::ParseNestedMeta, Attribute, Expr, ExprPath, LitStr, Path};
+
+/// Case conventions accepted by `#[vaerdi(rename_all = "...")]`
+#[derive(Clone, Copy)]
+pub enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(lit: &LitStr) -> syn::Result<RenameRule> {
+        let rule = match lit.value().as_str() {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "unknown rename rule, expected one of: lowercase, UPPERCASE, PascalCase, camelCase, snake_case, SCREAMING_SNAKE_CASE, kebab-case, SCREAMING-KEBAB-CASE",
+                ))
+            }
+        };
+        Ok(rule)
+    }
+
+    /// Rename a field, which is expected to be snake_case
+    pub fn apply_to_field(&self, field: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_string(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal => {
+                let mut out = String::with_capacity(field.len());
+                let mut upper = true;
+                for c in field.chars() {
+                    if c == '_' {
+                        upper = true;
+                    } else if upper {
+                        out.extend(c.to_uppercase());
+                        upper = false;
+                    } else {
+                        out.push(c);
+                    }
+                }
+                out
+            }
+            RenameRule::Camel => lower_first(&RenameRule::Pascal.apply_to_field(field)),
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+
+    /// Rename a variant, which is expected to be PascalCase
+    pub fn apply_to_variant(&self, variant: &str) -> String {
+        match self {
+            RenameRule::Pascal => variant.to_string(),
+            RenameRule::Lower => variant.to_ascii_lowercase(),
+            RenameRule::Upper => variant.to_ascii_uppercase(),
+            RenameRule::Camel => lower_first(variant),
+            RenameRule::Snake => {
+                let mut out = String::with_capacity(variant.len() + 4);
+                for (idx, c) in variant.char_indices() {
+                    if idx > 0 && c.is_uppercase() {
+                        out.push('_');
+                    }
+                    out.extend(c.to_lowercase());
+                }
+                out
+            }
+            RenameRule::ScreamingSnake => RenameRule::Snake
+                .apply_to_variant(variant)
+                .to_ascii_uppercase(),
+            RenameRule::Kebab => RenameRule::Snake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+            RenameRule::ScreamingKebab => RenameRule::ScreamingSnake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+        }
+    }
+}
+
+fn lower_first(input: &str) -> String {
+    let mut chars = input.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn parse_vaerdi_attrs(
+    attrs: &[Attribute],
+    mut logic: impl FnMut(ParseNestedMeta) -> syn::Result<()>,
+) -> syn::Result<()> {
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("vaerdi")) {
+        attr.parse_nested_meta(&mut logic)?;
+    }
+    Ok(())
+}
+
+fn parse_path<T: syn::parse::Parse>(meta: &ParseNestedMeta) -> syn::Result<T> {
+    let lit: LitStr = meta.value()?.parse()?;
+    lit.parse()
+}
+
+/// How the variants of an enum with data are represented
+pub enum Repr {
+    /// `{"<tag>": "Variant", "<content>": ...}`
+    Adjacent { tag: String, content: String },
+    /// `{"<tag>": "Variant", ...fields}`
+    Internal { tag: String },
+    /// `{"Variant": ...}`
+    External,
+    /// The content without any tag
+    Untagged,
+}
+
+impl Default for Repr {
+    fn default() -> Self {
+        Repr::Adjacent {
+            tag: "type".to_string(),
+            content: "content".to_string(),
+        }
+    }
+}
+
+/// Options given with `#[vaerdi(...)]` on a struct or an enum
+#[derive(Default)]
+pub struct ContainerAttrs {
+    /// Renames the fields of a struct or the variants of an enum
+    pub rename_all: Option<RenameRule>,
+    /// Fail `FromValue` on fields that are not part of the struct
+    pub deny_unknown_fields: bool,
+    /// Representation of an enum, `None` if not given
+    pub repr: Option<Repr>,
+}
+
+impl ContainerAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<ContainerAttrs> {
+        let mut out = ContainerAttrs::default();
+
+        let mut tag = None;
+        let mut content = None;
+        let mut untagged = None;
+        let mut external = None;
+
+        parse_vaerdi_attrs(attrs, |meta| {
+            if meta.path.is_ident("rename_all") {
+                out.rename_all = Some(RenameRule::parse(&meta.value()?.parse()?)?);
+            } else if meta.path.is_ident("deny_unknown_fields") {
+                out.deny_unknown_fields = true;
+            } else if meta.path.is_ident("tag") {
+                tag = Some(optional_name(&meta, "type")?);
+            } else if meta.path.is_ident("content") {
+                content = Some(optional_name(&meta, "content")?);
+            } else if meta.path.is_ident("untagged") {
+                untagged = Some(meta.path.clone());
+            } else if meta.path.is_ident("external") {
+                external = Some(meta.path.clone());
+            } else {
+                return Err(meta.error("unknown vaerdi container attribute"));
+            }
+            Ok(())
+        })?;
+
+        out.repr = match (tag, content, untagged, external) {
+            (None, None, None, None) => None,
+            (Some(tag), None, None, None) => Some(Repr::Internal { tag }),
+            (tag, Some(content), None, None) => Some(Repr::Adjacent {
+                tag: tag.unwrap_or_else(|| "type".to_string()),
+                content,
+            }),
+            (None, None, Some(_), None) => Some(Repr::Untagged),
+            (None, None, None, Some(_)) => Some(Repr::External),
+            (_, _, Some(path), _) | (_, _, _, Some(path)) => {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    "tag, content, untagged and external select conflicting enum representations",
+                ))
+            }
+        };
+
+        Ok(out)
+    }
+}
+
+/// `name = "..."` or just `name` which means `default`
+fn optional_name(meta: &ParseNestedMeta, default: &str) -> syn::Result<String> {
+    if meta.input.peek(syn::Token![=]) {
+        let lit: LitStr = meta.value()?.parse()?;
+        Ok(lit.value())
+    } else {
+        Ok(default.to_string())
+    }
+}
+
+/// Options given with `#[vaerdi(...)]` on an enum variant
+#[derive(Default)]
+pub struct VariantAttrs {
+    pub rename: Option<LitStr>,
+    /// Renames the fields of a struct variant
+    pub rename_all: Option<RenameRule>,
+}
+
+impl VariantAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<VariantAttrs> {
+        let mut out = VariantAttrs::default();
+
+        parse_vaerdi_attrs(attrs, |meta| {
+            if meta.path.is_ident("rename") {
+                out.rename = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("rename_all") {
+                out.rename_all = Some(RenameRule::parse(&meta.value()?.parse()?)?);
+            } else {
+                return Err(meta.error("unknown vaerdi variant attribute"));
+            }
+            Ok(())
+        })?;
+
+        Ok(out)
+    }
+}
+
+/// Options given with `#[vaerdi(...)]` on a field
+#[derive(Default)]
+pub struct FieldAttrs {
+    /// Named target in the converter registry the value is converted to
+    /// before it is handed to `FromValue`
+    pub convert: Option<LitStr>,
+    pub rename: Option<LitStr>,
+    /// Extra names accepted by `FromValue`
+    pub aliases: Vec<LitStr>,
+    /// `Some(None)` uses `Default::default()`, `Some(Some(path))` calls `path()`
+    pub default: Option<Option<ExprPath>>,
+    pub skip: bool,
+    pub skip_serializing_if: Option<ExprPath>,
+    /// Inline the fields of a map or struct. `IntoValue` panics if the
+    /// field converts to anything but a map or null
+    pub flatten: bool,
+    /// Module with `into_value`, `from_value` and `from_value_ref` functions
+    pub with: Option<Path>,
+    pub validate: Validators,
+}
+
+impl FieldAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<FieldAttrs> {
+        let mut out = FieldAttrs::default();
+
+        parse_vaerdi_attrs(attrs, |meta| {
+            if meta.path.is_ident("convert") {
+                out.convert = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("rename") {
+                out.rename = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("alias") {
+                out.aliases.push(meta.value()?.parse()?);
+            } else if meta.path.is_ident("default") {
+                out.default = if meta.input.peek(syn::Token![=]) {
+                    Some(Some(parse_path(&meta)?))
+                } else {
+                    Some(None)
+                };
+            } else if meta.path.is_ident("skip") {
+                out.skip = true;
+            } else if meta.path.is_ident("skip_serializing_if") {
+                out.skip_serializing_if = Some(parse_path(&meta)?);
+            } else if meta.path.is_ident("flatten") {
+                out.flatten = true;
+            } else if meta.path.is_ident("with") {
+                out.with = Some(parse_path(&meta)?);
+            } else if meta.path.is_ident("validate") {
+                meta.parse_nested_meta(|meta| out.validate.parse(meta))?;
+            } else {
+                return Err(meta.error("unknown vaerdi field attribute"));
+            }
+            Ok(())
+        })?;
+
+        Ok(out)
+    }
+}
+
+/// Rules given with `#[vaerdi(validate(...))]` on a field
+#[derive(Default)]
+pub struct Validators {
+    pub min: Option<Expr>,
+    pub max: Option<Expr>,
+    pub len_min: Option<Expr>,
+    pub len_max: Option<Expr>,
+    pub pattern: Option<LitStr>,
+    pub email: bool,
+    /// Functions called with a reference to the field
+    pub custom: Vec<ExprPath>,
+    /// Run the `Validate` implementation of the field
+    pub nested: bool,
+}
+
+impl Validators {
+    fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("min") {
+            self.min = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("max") {
+            self.max = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("len") {
+            meta.parse_nested_meta(|meta| {
+                if meta.path.is_ident("min") {
+                    self.len_min = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("max") {
+                    self.len_max = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("expected min or max"));
+                }
+                Ok(())
+            })?;
+        } else if meta.path.is_ident("pattern") {
+            self.pattern = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("email") {
+            self.email = true;
+        } else if meta.path.is_ident("custom") {
+            self.custom.push(parse_path(&meta)?);
+        } else if meta.path.is_ident("nested") {
+            self.nested = true;
+        } else {
+            return Err(meta.error("unknown validation rule"));
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.is_none()
+            && self.max.is_none()
+            && self.len_min.is_none()
+            && self.len_max.is_none()
+            && self.pattern.is_none()
+            && !self.email
+            && self.custom.is_empty()
+            && !self.nested
+    }
+}
@@ -0,0 +1,23 @@
+use proc_macro2::TokenStream;
+use syn::{parse_quote, Generics};
+
+/// Require `bound` of every type parameter in `generics`
+pub fn with_bound(generics: &Generics, bound: TokenStream) -> Generics {
+    let mut generics = generics.clone();
+
+    let params = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect::<Vec<_>>();
+
+    if params.is_empty() {
+        return generics;
+    }
+
+    let where_clause = generics.make_where_clause();
+    for param in params {
+        where_clause.predicates.push(parse_quote!(#param: #bound));
+    }
+
+    generics
+}
@@ -0,0 +1,732 @@
+use crate::{
+    attr::{ContainerAttrs, Repr, VariantAttrs},
+    bound::with_bound,
+    fields::{
+        collect_fields, construct_fields, field_schema, field_validate, insert_fields, may_be_map,
+        named_fields, named_schema, named_validate, tuple_field_from_value, tuple_field_into_value,
+        tuple_fields, tuple_from_value, tuple_into_value, tuple_schema, tuple_validate, NamedField,
+        Receiver, Source, TupleField,
+    },
+};
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::{ext::IdentExt, parse_quote, Attribute, DataEnum, Fields, Generics, Variant};
+
+enum Shape<'a> {
+    Unit,
+    NewType(Box<TupleField<'a>>),
+    Tuple(Vec<TupleField<'a>>),
+    Struct(Vec<NamedField<'a>>),
+}
+
+struct EnumVariant<'a> {
+    ident: &'a Ident,
+    /// The name of the variant on the wire
+    name: String,
+    shape: Shape<'a>,
+}
+
+fn variants<'a>(
+    item: &'a DataEnum,
+    container: &ContainerAttrs,
+) -> syn::Result<Vec<EnumVariant<'a>>> {
+    item.variants
+        .iter()
+        .map(|variant| {
+            let attrs = VariantAttrs::parse(&variant.attrs)?;
+
+            let name = match (&attrs.rename, container.rename_all) {
+                (Some(rename), _) => rename.value(),
+                (None, Some(rule)) => rule.apply_to_variant(&variant.ident.unraw().to_string()),
+                (None, None) => variant.ident.unraw().to_string(),
+            };
+
+            let shape = match &variant.fields {
+                Fields::Unit => Shape::Unit,
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    Shape::NewType(Box::new(tuple_fields(&fields.unnamed)?.remove(0)))
+                }
+                Fields::Unnamed(fields) => Shape::Tuple(tuple_fields(&fields.unnamed)?),
+                Fields::Named(fields) => {
+                    Shape::Struct(named_fields(&fields.named, attrs.rename_all)?)
+                }
+            };
+
+            if let Some(Repr::Internal { .. }) = &container.repr {
+                check_internal(variant, &shape)?;
+            }
+
+            Ok(EnumVariant {
+                ident: &variant.ident,
+                name,
+                shape,
+            })
+        })
+        .collect()
+}
+
+/// The key an internally tagged newtype is stored under when its value is not a map
+const INTERNAL_CONTENT: &str = "content";
+
+/// With an internal tag the content of a variant has to be a map to hold the tag.
+/// Tuple variants never are, and neither are newtypes of the types checked here.
+/// Other newtypes that turn out not to be maps are kept under `INTERNAL_CONTENT`
+fn check_internal(variant: &Variant, shape: &Shape) -> syn::Result<()> {
+    match shape {
+        Shape::Tuple(_) => Err(syn::Error::new_spanned(
+            variant,
+            "an internally tagged enum cannot contain tuple variants",
+        )),
+        Shape::NewType(field) if !may_be_map(&field.field.ty) => Err(syn::Error::new_spanned(
+            &field.field.ty,
+            "an internally tagged enum can only contain newtype variants holding a map or struct",
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn is_numbers(item: &DataEnum) -> bool {
+    item.variants
+        .iter()
+        .all(|m| m.discriminant.is_some() && matches!(m.fields, Fields::Unit))
+}
+
+fn is_labels(item: &DataEnum) -> bool {
+    item.variants
+        .iter()
+        .all(|m| m.discriminant.is_none() && matches!(m.fields, Fields::Unit))
+}
+
+pub fn derive_enum_into_value(
+    enum_name: Ident,
+    generics: Generics,
+    attrs: &[Attribute],
+    item: DataEnum,
+) -> syn::Result<TokenStream> {
+    let container = ContainerAttrs::parse(attrs)?;
+    let variants = variants(&item, &container)?;
+
+    let vaerdi_name = format_ident!("vaerdi");
+
+    let (owned, borrowed) = if container.repr.is_none() && is_numbers(&item) {
+        let arms = variants
+            .iter()
+            .map(|m| {
+                let ident = m.ident;
+                quote!(#enum_name::#ident => #vaerdi_name::Value::Number((#enum_name::#ident as i64).into()))
+            })
+            .collect::<Vec<_>>();
+        (arms.clone(), arms)
+    } else if container.repr.is_none() && is_labels(&item) {
+        let arms = variants
+            .iter()
+            .map(|m| {
+                let ident = m.ident;
+                let name = &m.name;
+                quote!(#enum_name::#ident => #vaerdi_name::Value::String(#name.into()))
+            })
+            .collect::<Vec<_>>();
+        (arms.clone(), arms)
+    } else {
+        let repr = container.repr.unwrap_or_default();
+        let arms = |receiver| {
+            variants
+                .iter()
+                .map(|m| variant_into_value(&vaerdi_name, &enum_name, &repr, receiver, m))
+                .collect::<Vec<_>>()
+        };
+        (arms(Receiver::Owned), arms(Receiver::Borrowed))
+    };
+
+    let owned_generics = with_bound(
+        &generics,
+        quote!(::core::convert::Into<#vaerdi_name::Value>),
+    );
+    let (imp, ty, wh) = owned_generics.split_for_impl();
+
+    let mut borrowed_generics = with_bound(&generics, quote!(#vaerdi_name::convert::IntoValue));
+    borrowed_generics.params.insert(0, parse_quote!('__vaerdi));
+    let (ref_imp, _, ref_wh) = borrowed_generics.split_for_impl();
+
+    Ok(quote!(
+        impl #imp From<#enum_name #ty> for #vaerdi_name::Value #wh {
+            fn from(from: #enum_name #ty) -> #vaerdi_name::Value {
+                match from {
+                    #(#owned),*
+                }
+            }
+        }
+
+        impl #ref_imp From<&'__vaerdi #enum_name #ty> for #vaerdi_name::Value #ref_wh {
+            fn from(from: &'__vaerdi #enum_name #ty) -> #vaerdi_name::Value {
+                match from {
+                    #(#borrowed),*
+                }
+            }
+        }
+    ))
+}
+
+/// A match arm converting `variant`, the bindings of the fields are
+/// references when `receiver` is borrowed
+fn variant_into_value(
+    vaerdi_name: &Ident,
+    enum_name: &Ident,
+    repr: &Repr,
+    receiver: Receiver,
+    variant: &EnumVariant,
+) -> TokenStream {
+    let ident = variant.ident;
+    let name = &variant.name;
+
+    let (pattern, content) = match &variant.shape {
+        Shape::Unit => (quote!(#enum_name::#ident), None),
+        Shape::NewType(field) => {
+            let value = tuple_field_into_value(vaerdi_name, field, receiver, quote!(__value));
+            (
+                quote!(#enum_name::#ident(__value)),
+                Some(quote!(::core::convert::Into::<#vaerdi_name::Value>::into(#value))),
+            )
+        }
+        Shape::Tuple(fields) => {
+            let bindings = fields.iter().map(|field| field.binding());
+            (
+                quote!(#enum_name::#ident(#(#bindings),*)),
+                Some(tuple_into_value(vaerdi_name, fields, receiver, |field| {
+                    let binding = field.binding();
+                    quote!(#binding)
+                })),
+            )
+        }
+        Shape::Struct(fields) => {
+            let bindings = fields.iter().map(|field| {
+                let ident = field.ident;
+                if field.attrs.skip {
+                    quote!(#ident: _)
+                } else {
+                    quote!(#ident)
+                }
+            });
+            let insert = insert_fields(
+                vaerdi_name,
+                fields,
+                &format_ident!("__content"),
+                receiver,
+                |field| {
+                    let ident = field.ident;
+                    quote!(#ident)
+                },
+            );
+            (
+                quote!(#enum_name::#ident { #(#bindings),* }),
+                Some(quote!({
+                    let mut __content = #vaerdi_name::Map::default();
+                    #insert
+                    #vaerdi_name::Value::Map(__content)
+                })),
+            )
+        }
+    };
+
+    let body = match (repr, content) {
+        (Repr::Adjacent { tag, .. } | Repr::Internal { tag }, None) => quote!({
+            let mut __map = #vaerdi_name::Map::default();
+            __map.insert(#tag, #name);
+            #vaerdi_name::Value::Map(__map)
+        }),
+        (Repr::Adjacent { tag, content: key }, Some(content)) => quote!({
+            let mut __map = #vaerdi_name::Map::default();
+            __map.insert(#tag, #name);
+            __map.insert(#key, #content);
+            #vaerdi_name::Value::Map(__map)
+        }),
+        // The tag is added to the content. A newtype whose value turns out not
+        // to be a map is kept under `INTERNAL_CONTENT` next to the tag
+        (Repr::Internal { tag }, Some(content)) => quote!({
+            match #content {
+                #vaerdi_name::Value::Map(mut __map) => {
+                    __map.insert(#tag, #name);
+                    #vaerdi_name::Value::Map(__map)
+                }
+                __content => {
+                    let mut __map = #vaerdi_name::Map::with_capacity(2);
+                    __map.insert(#tag, #name);
+                    __map.insert(#INTERNAL_CONTENT, __content);
+                    #vaerdi_name::Value::Map(__map)
+                }
+            }
+        }),
+        (Repr::External, None) => quote!(#vaerdi_name::Value::String(#name.into())),
+        (Repr::External, Some(content)) => quote!({
+            let mut __map = #vaerdi_name::Map::with_capacity(1);
+            __map.insert(#name, #content);
+            #vaerdi_name::Value::Map(__map)
+        }),
+        (Repr::Untagged, None) => quote!(#vaerdi_name::Value::Null),
+        (Repr::Untagged, Some(content)) => content,
+    };
+
+    quote!(#pattern => #body)
+}
+
+pub fn derive_enum_from_value(
+    enum_name: Ident,
+    generics: Generics,
+    attrs: &[Attribute],
+    item: DataEnum,
+) -> syn::Result<TokenStream> {
+    let container = ContainerAttrs::parse(attrs)?;
+    let variants = variants(&item, &container)?;
+
+    let vaerdi_name = format_ident!("vaerdi");
+
+    let body = if container.repr.is_none() && is_numbers(&item) {
+        let checks = variants.iter().map(|m| {
+            let ident = m.ident;
+            quote!(
+                if number == #enum_name::#ident as i64 {
+                    return Ok(#enum_name::#ident);
+                }
+            )
+        });
+
+        quote!(
+            let number = <i64 as #vaerdi_name::convert::FromValue>::from_value(from)?;
+
+            #(#checks)*
+
+            Err(#vaerdi_name::ConvertError::unknown_variant(number))
+        )
+    } else if container.repr.is_none() && is_labels(&item) {
+        let arms = variants.iter().map(|m| {
+            let ident = m.ident;
+            let name = &m.name;
+            quote!(#name => Ok(#enum_name::#ident))
+        });
+
+        quote!(
+            let Some(name) = from.as_string() else {
+                return Err(#vaerdi_name::ConvertError::invalid_type(#vaerdi_name::Type::String, from.get_type()));
+            };
+
+            match &**name {
+                #(#arms,)*
+                v => Err(#vaerdi_name::ConvertError::unknown_variant(v)),
+            }
+        )
+    } else {
+        let deny_unknown_fields = container.deny_unknown_fields;
+        match container.repr.unwrap_or_default() {
+            Repr::Adjacent { tag, content } => {
+                let arms = variants
+                    .iter()
+                    .map(|m| {
+                        let name = &m.name;
+                        let build = match m.shape {
+                            Shape::Unit => variant_from_value(&vaerdi_name, &enum_name, m, deny_unknown_fields, quote!(), None)?,
+                            _ => {
+                                let from_value = variant_from_value(
+                                    &vaerdi_name,
+                                    &enum_name,
+                                    m,
+                                    deny_unknown_fields,
+                                    quote!(__content),
+                                    Some(&content),
+                                )?;
+                                quote!({
+                                    let Some(__content) = __map.remove(#content) else {
+                                        return Err(#vaerdi_name::ConvertError::missing_field(#content))
+                                    };
+                                    #from_value
+                                })
+                            }
+                        };
+                        Ok(quote!(#name => #build))
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?;
+
+                quote!(
+                    let Some(__map) = from.as_map_mut() else {
+                        return Err(#vaerdi_name::ConvertError::invalid_type(#vaerdi_name::Type::Map, from.get_type()))
+                    };
+
+                    let __tag = match __map.get(#tag) {
+                        Some(#vaerdi_name::Value::String(tag)) => tag.clone(),
+                        Some(tag) => return Err(#vaerdi_name::ConvertError::invalid_type(#vaerdi_name::Type::String, tag.get_type()).with_field(#tag)),
+                        None => return Err(#vaerdi_name::ConvertError::missing_field(#tag)),
+                    };
+
+                    match &*__tag {
+                        #(#arms,)*
+                        v => Err(#vaerdi_name::ConvertError::unknown_variant(v)),
+                    }
+                )
+            }
+            Repr::Internal { tag } => {
+                let arms = variants
+                    .iter()
+                    .map(|m| {
+                        let name = &m.name;
+                        let build = variant_from_value(
+                            &vaerdi_name,
+                            &enum_name,
+                            m,
+                            deny_unknown_fields,
+                            quote!(from),
+                            None,
+                        )?;
+
+                        if !matches!(m.shape, Shape::NewType(_)) {
+                            return Ok(quote!(#name => #build));
+                        }
+
+                        // A map holding nothing but `INTERNAL_CONTENT` is most likely
+                        // a newtype that is not a map, otherwise the rest of the map
+                        // is the newtype's value
+                        let unwrapped = variant_from_value(
+                            &vaerdi_name,
+                            &enum_name,
+                            m,
+                            deny_unknown_fields,
+                            quote!(__content),
+                            None,
+                        )?;
+                        Ok(quote!(#name => {
+                            let __content = match __map.len() {
+                                1 => __map.get(#INTERNAL_CONTENT).cloned(),
+                                _ => None,
+                            };
+
+                            match __content {
+                                Some(__content) => match #unwrapped {
+                                    Ok(ret) => Ok(ret),
+                                    Err(err) => (#build).map_err(|_| err),
+                                },
+                                None => #build,
+                            }
+                        }))
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?;
+
+                quote!(
+                    let Some(__map) = from.as_map_mut() else {
+                        return Err(#vaerdi_name::ConvertError::invalid_type(#vaerdi_name::Type::Map, from.get_type()))
+                    };
+
+                    let __tag = match __map.remove(#tag) {
+                        Some(#vaerdi_name::Value::String(tag)) => tag,
+                        Some(tag) => return Err(#vaerdi_name::ConvertError::invalid_type(#vaerdi_name::Type::String, tag.get_type()).with_field(#tag)),
+                        None => return Err(#vaerdi_name::ConvertError::missing_field(#tag)),
+                    };
+
+                    match &*__tag {
+                        #(#arms,)*
+                        v => Err(#vaerdi_name::ConvertError::unknown_variant(v)),
+                    }
+                )
+            }
+            Repr::External => {
+                let units = variants
+                    .iter()
+                    .filter(|m| matches!(m.shape, Shape::Unit))
+                    .map(|m| {
+                        let ident = m.ident;
+                        let name = &m.name;
+                        quote!(#name => Ok(#enum_name::#ident))
+                    });
+
+                let arms = variants
+                    .iter()
+                    .map(|m| {
+                        let name = &m.name;
+                        let build = variant_from_value(
+                            &vaerdi_name,
+                            &enum_name,
+                            m,
+                            deny_unknown_fields,
+                            quote!(__content),
+                            Some(name),
+                        )?;
+                        Ok(quote!(#name => #build))
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?;
+
+                quote!(
+                    match from {
+                        #vaerdi_name::Value::String(name) => match &*name {
+                            #(#units,)*
+                            v => Err(#vaerdi_name::ConvertError::unknown_variant(v)),
+                        },
+                        #vaerdi_name::Value::Map(map) if map.len() == 1 => {
+                            let (__tag, __content) = map.into_iter().next().unwrap();
+                            match &*__tag {
+                                #(#arms,)*
+                                v => Err(#vaerdi_name::ConvertError::unknown_variant(v)),
+                            }
+                        }
+                        from => Err(#vaerdi_name::ConvertError::invalid_type(
+                            #vaerdi_name::Type::String | #vaerdi_name::Type::Map,
+                            from.get_type(),
+                        )),
+                    }
+                )
+            }
+            Repr::Untagged => {
+                let attempts = variants
+                    .iter()
+                    .map(|m| {
+                        let build = match m.shape {
+                            Shape::Unit => {
+                                let ident = m.ident;
+                                quote!(if from.is_null() {
+                                    Ok(#enum_name::#ident)
+                                } else {
+                                    Err(#vaerdi_name::ConvertError::unknown("expected null"))
+                                })
+                            }
+                            _ => variant_from_value(
+                                &vaerdi_name,
+                                &enum_name,
+                                m,
+                                deny_unknown_fields,
+                                quote!(from.clone()),
+                                None,
+                            )?,
+                        };
+                        Ok(quote!(
+                            if let Ok(ret) = #build {
+                                return Ok(ret);
+                            }
+                        ))
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?;
+
+                let message =
+                    format!("value did not match any variant of untagged enum {enum_name}");
+
+                quote!(
+                    #(#attempts)*
+
+                    Err(#vaerdi_name::ConvertError::unknown(#message))
+                )
+            }
+        }
+    };
+
+    let generics = with_bound(&generics, quote!(#vaerdi_name::convert::FromValue));
+    let (imp, ty, wh) = generics.split_for_impl();
+
+    Ok(quote!(
+        impl #imp #vaerdi_name::convert::FromValue for #enum_name #ty #wh {
+            type Error = #vaerdi_name::ConvertError;
+            #[allow(unused_mut)]
+            fn from_value(mut from: #vaerdi_name::Value) -> Result<#enum_name #ty, Self::Error> {
+                #body
+            }
+        }
+    ))
+}
+
+/// An expression building `variant` from the owned value `content`.
+/// The expression does not return early and evaluates to a `Result`,
+/// errors are located at `field` if given.
+fn variant_from_value(
+    vaerdi_name: &Ident,
+    enum_name: &Ident,
+    variant: &EnumVariant,
+    deny_unknown_fields: bool,
+    content: TokenStream,
+    field: Option<&str>,
+) -> syn::Result<TokenStream> {
+    let ident = variant.ident;
+    let with_field = field.map(|field| quote!(.with_field(#field)));
+
+    let ret = match &variant.shape {
+        Shape::Unit => quote!(Ok(#enum_name::#ident)),
+        Shape::NewType(field) => {
+            let from_value = tuple_field_from_value(vaerdi_name, field, Source::Owned, content)?;
+            quote!(
+                (#from_value)
+                    .map(#enum_name::#ident)
+                    .map_err(|err| err #with_field)
+            )
+        }
+        Shape::Tuple(fields) => {
+            let from_value = tuple_from_value(
+                vaerdi_name,
+                fields,
+                Source::Owned,
+                content,
+                quote!(#enum_name::#ident),
+            )?;
+            quote!(
+                (#from_value).map_err(|err: #vaerdi_name::ConvertError| err #with_field)
+            )
+        }
+        Shape::Struct(fields) => {
+            let collect = collect_fields(vaerdi_name, fields, Source::Owned, deny_unknown_fields)?;
+            let construct = construct_fields(fields);
+
+            quote!({
+                let mut __content = #content;
+                match __content.as_map_mut() {
+                    Some(__map) => {
+                        let mut __errors = #vaerdi_name::convert::ErrorCollector::new();
+
+                        #collect
+
+                        match __errors.finish() {
+                            Ok(()) => Ok(#enum_name::#ident {
+                                #construct
+                            }),
+                            Err(err) => Err(err #with_field),
+                        }
+                    }
+                    None => Err(#vaerdi_name::ConvertError::invalid_type(#vaerdi_name::Type::Map, __content.get_type())#with_field),
+                }
+            })
+        }
+    };
+
+    Ok(ret)
+}
+
+pub fn derive_enum_schema(
+    enum_name: Ident,
+    generics: Generics,
+    attrs: &[Attribute],
+    item: DataEnum,
+) -> syn::Result<TokenStream> {
+    let container = ContainerAttrs::parse(attrs)?;
+    let variants = variants(&item, &container)?;
+
+    let vaerdi_name = format_ident!("vaerdi");
+    let schema = quote!(#vaerdi_name::schema);
+
+    let (repr, variants) = if container.repr.is_none() && is_numbers(&item) {
+        let variants = variants.iter().map(|m| {
+            let ident = m.ident;
+            let name = &m.name;
+            quote!(.variant(#name, Some(#enum_name::#ident as i64), None))
+        });
+        (
+            quote!(#schema::EnumRepr::Number),
+            variants.collect::<Vec<_>>(),
+        )
+    } else if container.repr.is_none() && is_labels(&item) {
+        let variants = variants.iter().map(|m| {
+            let name = &m.name;
+            quote!(.variant(#name, None, None))
+        });
+        (
+            quote!(#schema::EnumRepr::Label),
+            variants.collect::<Vec<_>>(),
+        )
+    } else {
+        let repr = match container.repr.unwrap_or_default() {
+            Repr::Adjacent { tag, content } => quote!(#schema::EnumRepr::Adjacent {
+                tag: #tag.into(),
+                content: #content.into(),
+            }),
+            Repr::Internal { tag } => quote!(#schema::EnumRepr::Internal { tag: #tag.into() }),
+            Repr::External => quote!(#schema::EnumRepr::External),
+            Repr::Untagged => quote!(#schema::EnumRepr::Untagged),
+        };
+
+        let variants = variants.iter().map(|m| {
+            let name = &m.name;
+            let content = match &m.shape {
+                Shape::Unit => quote!(None),
+                Shape::NewType(field) => {
+                    let schema = field_schema(&vaerdi_name, &field.field.ty, &field.attrs);
+                    quote!(Some(#schema))
+                }
+                Shape::Tuple(fields) => {
+                    let schema = tuple_schema(&vaerdi_name, fields);
+                    quote!(Some(#schema))
+                }
+                Shape::Struct(fields) => {
+                    let schema = named_schema(&vaerdi_name, name, fields);
+                    quote!(Some(#schema.into()))
+                }
+            };
+            quote!(.variant(#name, None, #content))
+        });
+
+        (repr, variants.collect::<Vec<_>>())
+    };
+
+    let name = enum_name.to_string();
+
+    let generics = with_bound(&generics, quote!(#schema::ValueSchema));
+    let (imp, ty, wh) = generics.split_for_impl();
+
+    Ok(quote!(
+        impl #imp #schema::ValueSchema for #enum_name #ty #wh {
+            fn schema() -> #schema::TypeSchema {
+                #schema::EnumSchema::new(#name, #repr) #(#variants)* .into()
+            }
+        }
+
+        #vaerdi_name::__typed_impl!([#imp] #enum_name #ty, [#wh]);
+    ))
+}
+
+pub fn derive_enum_validate(
+    enum_name: Ident,
+    generics: Generics,
+    attrs: &[Attribute],
+    item: DataEnum,
+) -> syn::Result<TokenStream> {
+    let container = ContainerAttrs::parse(attrs)?;
+    let variants = variants(&item, &container)?;
+
+    let vaerdi_name = format_ident!("vaerdi");
+
+    let arms = variants.iter().map(|variant| {
+        let ident = variant.ident;
+        match &variant.shape {
+            Shape::Unit => quote!(#enum_name::#ident => {}),
+            Shape::NewType(field) => {
+                let binding = field.binding();
+                let check = field_validate(&vaerdi_name, &field.attrs, quote!(#binding));
+                quote!(#enum_name::#ident(#binding) => { #check })
+            }
+            Shape::Tuple(fields) => {
+                let bindings = fields.iter().map(|field| field.binding());
+                let checks = tuple_validate(&vaerdi_name, fields, |field| {
+                    let binding = field.binding();
+                    quote!(#binding)
+                });
+                quote!(#enum_name::#ident(#(#bindings),*) => { #checks })
+            }
+            Shape::Struct(fields) => {
+                let bindings = fields.iter().map(|field| {
+                    let name = field.ident;
+                    let binding = field.binding();
+                    quote!(#name: #binding)
+                });
+                let checks = named_validate(&vaerdi_name, fields, |field| {
+                    let binding = field.binding();
+                    quote!(#binding)
+                });
+                quote!(#enum_name::#ident { #(#bindings),* } => { #checks })
+            }
+        }
+    });
+
+    let (imp, ty, wh) = generics.split_for_impl();
+
+    Ok(quote!(
+        impl #imp #vaerdi_name::validate::Validate for #enum_name #ty #wh {
+            #[allow(unused_variables)]
+            fn validate(&self) -> Result<(), #vaerdi_name::ConvertError> {
+                let mut __errors = #vaerdi_name::convert::ErrorCollector::new();
+                match self {
+                    #(#arms)*
+                }
+                __errors.finish()
+            }
+        }
+    ))
+}
@@ -0,0 +1,786 @@
+use crate::attr::{FieldAttrs, RenameRule, Validators};
+use proc_macro2::Ident;
+use quote::{format_ident, quote};
+use syn::{ext::IdentExt, spanned::Spanned, Field, Lifetime};
+
+/// A named field together with its attributes and the key it is stored under
+pub struct NamedField<'a> {
+    pub field: &'a Field,
+    pub ident: &'a Ident,
+    pub attrs: FieldAttrs,
+    pub key: String,
+}
+
+impl<'a> NamedField<'a> {
+    pub fn binding(&self) -> Ident {
+        format_ident!("__field_{}", self.ident.unraw())
+    }
+
+    /// Every key the field is read from, the primary key first
+    pub fn keys(&self) -> impl Iterator<Item = String> + '_ {
+        core::iter::once(self.key.clone()).chain(self.attrs.aliases.iter().map(|m| m.value()))
+    }
+
+    /// Value used when the field is skipped or missing
+    pub fn default_value(&self) -> Option<proc_macro2::TokenStream> {
+        match &self.attrs.default {
+            Some(Some(path)) => Some(quote!(#path())),
+            Some(None) => Some(quote!(::core::default::Default::default())),
+            None if self.attrs.skip => Some(quote!(::core::default::Default::default())),
+            None if is_option(&self.field.ty) => Some(quote!(::core::option::Option::None)),
+            None => None,
+        }
+    }
+}
+
+pub fn named_fields<'a>(
+    fields: impl IntoIterator<Item = &'a Field>,
+    rename_all: Option<RenameRule>,
+) -> syn::Result<Vec<NamedField<'a>>> {
+    fields
+        .into_iter()
+        .map(|field| {
+            let Some(ident) = field.ident.as_ref() else {
+                return Err(syn::Error::new(field.span(), "expected a named field"));
+            };
+
+            let attrs = FieldAttrs::parse(&field.attrs)?;
+
+            if attrs.flatten && attrs.with.is_none() && !may_be_map(&field.ty) {
+                return Err(syn::Error::new_spanned(
+                    &field.ty,
+                    "a flattened field must hold a map or struct",
+                ));
+            }
+
+            let key = match (&attrs.rename, rename_all) {
+                (Some(rename), _) => rename.value(),
+                (None, Some(rule)) => rule.apply_to_field(&ident.unraw().to_string()),
+                (None, None) => ident.unraw().to_string(),
+            };
+
+            Ok(NamedField {
+                field,
+                ident,
+                attrs,
+                key,
+            })
+        })
+        .collect()
+}
+
+fn is_option(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) if path.qself.is_none() => path
+            .path
+            .segments
+            .last()
+            .map(|m| m.ident == "Option")
+            .unwrap_or_default(),
+        _ => false,
+    }
+}
+
+/// False for the types that never convert to a map, true for everything else
+pub fn may_be_map(ty: &syn::Type) -> bool {
+    const NOT_MAPS: &[&str] = &[
+        "bool",
+        "char",
+        "str",
+        "String",
+        "i8",
+        "i16",
+        "i32",
+        "i64",
+        "i128",
+        "isize",
+        "u8",
+        "u16",
+        "u32",
+        "u64",
+        "u128",
+        "usize",
+        "f32",
+        "f64",
+        "Number",
+        "Bytes",
+        "List",
+        "Vec",
+        "VecDeque",
+        "HashSet",
+        "BTreeSet",
+        "Uuid",
+        "NaiveDate",
+        "NaiveTime",
+        "NaiveDateTime",
+        "DateTime",
+    ];
+
+    match ty {
+        syn::Type::Path(path) => match path.path.segments.last() {
+            Some(segment) => !NOT_MAPS.iter().any(|name| segment.ident == name),
+            None => true,
+        },
+        syn::Type::Reference(reference) => may_be_map(&reference.elem),
+        syn::Type::Paren(paren) => may_be_map(&paren.elem),
+        syn::Type::Group(group) => may_be_map(&group.elem),
+        syn::Type::Tuple(_) | syn::Type::Array(_) | syn::Type::Slice(_) => false,
+        _ => true,
+    }
+}
+
+/// How the `IntoValue` derive reaches the fields of the value
+#[derive(Clone, Copy)]
+pub enum Receiver {
+    /// `access` moves the field out of the value
+    Owned,
+    /// `access` is a reference to the field
+    Borrowed,
+}
+
+impl Receiver {
+    /// A reference to the field read with `access`
+    fn by_ref(self, access: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        match self {
+            Receiver::Owned => quote!(&#access),
+            Receiver::Borrowed => access.clone(),
+        }
+    }
+
+    /// The value of the field read with `access`, converted by `with` if given.
+    /// A `with` module takes the field by value so a borrowed field is cloned,
+    /// otherwise a borrowed field converts with `IntoValue`, which is what the
+    /// borrowed impls bound their type parameters with.
+    fn value(
+        self,
+        vaerdi_name: &Ident,
+        with: Option<&syn::Path>,
+        access: &proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        match (with, self) {
+            (Some(with), Receiver::Owned) => quote!(#with::into_value(#access)),
+            (Some(with), Receiver::Borrowed) => {
+                quote!(#with::into_value(::core::clone::Clone::clone(#access)))
+            }
+            (None, Receiver::Owned) => access.clone(),
+            (None, Receiver::Borrowed) => {
+                quote!(#vaerdi_name::convert::IntoValue::to_value(#access))
+            }
+        }
+    }
+}
+
+/// Insert every field into the map `map`, reading the field with `access`
+pub fn insert_fields(
+    vaerdi_name: &Ident,
+    fields: &[NamedField],
+    map: &Ident,
+    receiver: Receiver,
+    access: impl Fn(&NamedField) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let fields = fields
+        .iter()
+        .filter(|field| !field.attrs.skip)
+        .map(|field| {
+            let key = &field.key;
+            let access = access(field);
+            let value = receiver.value(vaerdi_name, field.attrs.with.as_ref(), &access);
+
+            let insert = if field.attrs.flatten {
+                quote!(
+                    match ::core::convert::Into::<#vaerdi_name::Value>::into(#value) {
+                        #vaerdi_name::Value::Map(inner) => #map.extend(inner),
+                        #vaerdi_name::Value::Null => {}
+                        value => ::core::panic!(
+                            "the flattened field `{}` must convert to a map, found {}",
+                            #key,
+                            value.get_type()
+                        ),
+                    }
+                )
+            } else {
+                quote!(
+                    #map.insert(#key, #value);
+                )
+            };
+
+            match &field.attrs.skip_serializing_if {
+                Some(predicate) => {
+                    let access = receiver.by_ref(&access);
+                    quote!(
+                        if !#predicate(#access) {
+                            #insert
+                        }
+                    )
+                }
+                None => insert,
+            }
+        });
+
+    quote!(#(#fields)*)
+}
+
+/// How `collect_fields` reads the fields out of `__map`
+#[derive(Clone, Copy)]
+pub enum Source<'a> {
+    /// `__map` is a `&mut Map` and fields are moved out with `FromValue`
+    Owned,
+    /// `__map` is a `&'a Map` and fields are borrowed with `FromValueRef<'a>`
+    Borrowed(&'a Lifetime),
+}
+
+/// Convert `value` to the type `ty`
+pub fn field_from_value(
+    vaerdi_name: &Ident,
+    ty: &syn::Type,
+    attrs: &FieldAttrs,
+    source: Source<'_>,
+    value: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let ret = match source {
+        Source::Owned => {
+            let from_value = match &attrs.with {
+                Some(with) => quote!(#with::from_value(value)),
+                None => quote!(<#ty as #vaerdi_name::convert::FromValue>::from_value(value)),
+            };
+
+            match &attrs.convert {
+                Some(target) => quote!(
+                    #vaerdi_name::convert::registry::convert_to(#value, #target).and_then(|value| {
+                        #from_value.map_err(Into::<#vaerdi_name::ConvertError>::into)
+                    })
+                ),
+                None => quote!({
+                    let value = #value;
+                    #from_value.map_err(Into::<#vaerdi_name::ConvertError>::into)
+                }),
+            }
+        }
+        Source::Borrowed(lifetime) => {
+            if let Some(target) = &attrs.convert {
+                return Err(syn::Error::new(
+                    target.span(),
+                    "convert needs an owned value and is not supported by FromValueRef",
+                ));
+            }
+
+            let from_value = match &attrs.with {
+                Some(with) => quote!(#with::from_value_ref(value)),
+                None => quote!(
+                    <#ty as #vaerdi_name::convert::FromValueRef<#lifetime>>::from_value_ref(value)
+                ),
+            };
+
+            quote!({
+                let value = #value;
+                #from_value.map_err(Into::<#vaerdi_name::ConvertError>::into)
+            })
+        }
+    };
+
+    Ok(ret)
+}
+
+/// Read every field out of `__map`, recording failures in `__errors`
+pub fn collect_fields(
+    vaerdi_name: &Ident,
+    fields: &[NamedField],
+    source: Source<'_>,
+    deny_unknown_fields: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let flattened = fields
+        .iter()
+        .filter(|field| field.attrs.flatten && !field.attrs.skip)
+        .collect::<Vec<_>>();
+
+    if deny_unknown_fields {
+        if let Some(field) = flattened.first() {
+            return Err(syn::Error::new(
+                field.field.span(),
+                "flatten cannot be combined with deny_unknown_fields",
+            ));
+        }
+    }
+
+    let mut out = Vec::with_capacity(fields.len());
+
+    for field in fields {
+        let binding = field.binding();
+        let ty = &field.field.ty;
+
+        if field.attrs.skip {
+            let default = field.default_value();
+            out.push(quote!(
+                let #binding: Option<#ty> = Some(#default);
+            ));
+            continue;
+        }
+
+        if field.attrs.flatten {
+            continue;
+        }
+
+        let key = &field.key;
+        let mut lookup = field.keys().map(|key| match source {
+            Source::Owned => quote!(__map.remove(#key)),
+            Source::Borrowed(_) => quote!(__map.get(#key)),
+        });
+        let first = lookup.next();
+
+        let value = match source {
+            Source::Owned => quote!(value),
+            Source::Borrowed(_) => quote!(value.as_ref()),
+        };
+        let from_value =
+            field_from_value(vaerdi_name, &field.field.ty, &field.attrs, source, value)?;
+
+        let missing = match field.default_value() {
+            Some(default) => quote!(Ok(#default)),
+            None => quote!(Err(#vaerdi_name::ConvertError::missing_field(#key))),
+        };
+
+        let from_value = validated(
+            vaerdi_name,
+            &field.attrs.validate,
+            quote!(match #first #(.or_else(|| #lookup))* {
+                Some(value) => #from_value,
+                None => #missing,
+            }),
+        );
+
+        out.push(quote!(
+            let #binding = __errors.field(#key, #from_value);
+        ));
+    }
+
+    // Flattened fields are built from whatever the other fields left behind
+    for (idx, field) in flattened.iter().enumerate() {
+        let binding = field.binding();
+        let rest = match source {
+            Source::Owned if idx + 1 == flattened.len() => {
+                quote!(#vaerdi_name::Value::Map(::core::mem::take(__map)))
+            }
+            Source::Owned => quote!(#vaerdi_name::Value::Map(__map.clone())),
+            Source::Borrowed(_) => quote!(#vaerdi_name::ValueRef::Map(__map)),
+        };
+        let from_value = validated(
+            vaerdi_name,
+            &field.attrs.validate,
+            field_from_value(vaerdi_name, &field.field.ty, &field.attrs, source, rest)?,
+        );
+
+        out.push(quote!(
+            let #binding = match #from_value {
+                Ok(value) => Some(value),
+                Err(err) => {
+                    __errors.push(err);
+                    None
+                }
+            };
+        ));
+    }
+
+    if deny_unknown_fields {
+        out.push(match source {
+            Source::Owned => quote!(
+                for (key, _) in __map.iter() {
+                    __errors.push(#vaerdi_name::ConvertError::unknown_field(key));
+                }
+            ),
+            Source::Borrowed(_) => {
+                let keys = fields
+                    .iter()
+                    .filter(|field| !field.attrs.skip)
+                    .flat_map(|field| field.keys())
+                    .collect::<Vec<_>>();
+                quote!(
+                    for (key, _) in __map.iter() {
+                        if ![#(#keys),*].contains(&&**key) {
+                            __errors.push(#vaerdi_name::ConvertError::unknown_field(key));
+                        }
+                    }
+                )
+            }
+        });
+    }
+
+    Ok(quote!(#(#out)*))
+}
+
+/// The struct fields from the bindings made by `collect_fields`
+pub fn construct_fields(fields: &[NamedField]) -> proc_macro2::TokenStream {
+    let fields = fields.iter().map(|field| {
+        let name = field.ident;
+        let binding = field.binding();
+        quote!(#name: #binding.unwrap())
+    });
+
+    quote!(#(#fields),*)
+}
+
+/// A field of a tuple struct or a tuple variant
+pub struct TupleField<'a> {
+    pub field: &'a Field,
+    pub attrs: FieldAttrs,
+    pub index: usize,
+}
+
+impl<'a> TupleField<'a> {
+    pub fn binding(&self) -> Ident {
+        format_ident!("__field_{}", self.index)
+    }
+}
+
+pub fn tuple_fields<'a>(
+    fields: impl IntoIterator<Item = &'a Field>,
+) -> syn::Result<Vec<TupleField<'a>>> {
+    fields
+        .into_iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let attrs = FieldAttrs::parse(&field.attrs)?;
+
+            if attrs.rename.is_some()
+                || !attrs.aliases.is_empty()
+                || attrs.default.is_some()
+                || attrs.skip
+                || attrs.skip_serializing_if.is_some()
+                || attrs.flatten
+            {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "only with and convert are supported on tuple fields",
+                ));
+            }
+
+            Ok(TupleField {
+                field,
+                attrs,
+                index,
+            })
+        })
+        .collect()
+}
+
+/// The value of a single tuple field, read with `access`
+pub fn tuple_field_into_value(
+    vaerdi_name: &Ident,
+    field: &TupleField,
+    receiver: Receiver,
+    access: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    receiver.value(vaerdi_name, field.attrs.with.as_ref(), &access)
+}
+
+/// Convert `value` to the type of the tuple field and check its rules
+pub fn tuple_field_from_value(
+    vaerdi_name: &Ident,
+    field: &TupleField,
+    source: Source<'_>,
+    value: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let from_value = field_from_value(vaerdi_name, &field.field.ty, &field.attrs, source, value)?;
+    Ok(validated(vaerdi_name, &field.attrs.validate, from_value))
+}
+
+/// A list of every field, reading the fields with `access`
+pub fn tuple_into_value(
+    vaerdi_name: &Ident,
+    fields: &[TupleField],
+    receiver: Receiver,
+    access: impl Fn(&TupleField) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let len = fields.len();
+    let push = fields.iter().map(|field| {
+        let value = tuple_field_into_value(vaerdi_name, field, receiver, access(field));
+        quote!(__list.push(#value);)
+    });
+
+    quote!({
+        let mut __list = #vaerdi_name::List::with_capacity(#len);
+        #(#push)*
+        #vaerdi_name::Value::List(__list)
+    })
+}
+
+/// An expression building `constructor(fields..)` from a list in `value`.
+/// The expression does not return early and evaluates to a `Result`.
+pub fn tuple_from_value(
+    vaerdi_name: &Ident,
+    fields: &[TupleField],
+    source: Source<'_>,
+    value: proc_macro2::TokenStream,
+    constructor: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let len = fields.len();
+
+    let (list, element) = match source {
+        Source::Owned => (
+            quote!(#vaerdi_name::Value::List(__list)),
+            quote!(__iter.next().unwrap()),
+        ),
+        Source::Borrowed(_) => (
+            quote!(#vaerdi_name::ValueRef::List(__list)),
+            quote!(__iter.next().unwrap().as_ref()),
+        ),
+    };
+
+    let build = if fields.is_empty() {
+        quote!(Ok(#constructor()))
+    } else {
+        let iter = match source {
+            Source::Owned => quote!(__list.into_iter()),
+            Source::Borrowed(_) => quote!(__list.iter()),
+        };
+
+        let collect = fields
+            .iter()
+            .map(|field| {
+                let binding = field.binding();
+                let index = field.index;
+                let from_value =
+                    tuple_field_from_value(vaerdi_name, field, source, element.clone())?;
+                Ok(quote!(
+                    let #binding = __errors.index(#index, #from_value);
+                ))
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        let construct = fields.iter().map(|field| {
+            let binding = field.binding();
+            quote!(#binding.unwrap())
+        });
+
+        quote!({
+            let mut __iter = #iter;
+            let mut __errors = #vaerdi_name::convert::ErrorCollector::new();
+
+            #(#collect)*
+
+            match __errors.finish() {
+                Ok(()) => Ok(#constructor(#(#construct),*)),
+                Err(err) => Err(err),
+            }
+        })
+    };
+
+    Ok(quote!(
+        match #value {
+            #list if __list.len() == #len => #build,
+            #list => Err(#vaerdi_name::ConvertError::invalid_length(#len, __list.len())),
+            value => Err(#vaerdi_name::ConvertError::invalid_type(#vaerdi_name::Type::List, value.get_type())),
+        }
+    ))
+}
+
+/// The schema of a field of type `ty`
+pub fn field_schema(
+    vaerdi_name: &Ident,
+    ty: &syn::Type,
+    attrs: &FieldAttrs,
+) -> proc_macro2::TokenStream {
+    match &attrs.with {
+        // The module decides the shape of the value
+        Some(_) => quote!(#vaerdi_name::schema::TypeSchema::Any),
+        None => quote!(<#ty as #vaerdi_name::schema::ValueSchema>::schema()),
+    }
+}
+
+/// A `StructSchema` named `name` with every field
+pub fn named_schema(
+    vaerdi_name: &Ident,
+    name: &str,
+    fields: &[NamedField],
+) -> proc_macro2::TokenStream {
+    let fields = fields
+        .iter()
+        .filter(|field| !field.attrs.skip)
+        .map(|field| {
+            let schema = field_schema(vaerdi_name, &field.field.ty, &field.attrs);
+            if field.attrs.flatten {
+                quote!(.flatten(#schema))
+            } else {
+                let key = &field.key;
+                let required = field.default_value().is_none();
+                quote!(.field(#key, #schema, #required))
+            }
+        });
+
+    quote!(#vaerdi_name::schema::StructSchema::new(#name) #(#fields)*)
+}
+
+/// A tuple schema with every field
+pub fn tuple_schema(vaerdi_name: &Ident, fields: &[TupleField]) -> proc_macro2::TokenStream {
+    let items = fields
+        .iter()
+        .map(|field| field_schema(vaerdi_name, &field.field.ty, &field.attrs));
+    quote!(#vaerdi_name::schema::TypeSchema::tuple([#(#items),*]))
+}
+
+/// Whether `expr` is an integer literal without a suffix that fits an
+/// `i128`, which can bound a field of any numeric type
+fn is_int_literal(expr: &syn::Expr) -> bool {
+    let lit = match expr {
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => &**expr,
+        expr => expr,
+    };
+
+    match lit {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(int),
+            ..
+        }) => int.suffix().is_empty() && int.base10_parse::<i128>().is_ok(),
+        _ => false,
+    }
+}
+
+/// An expression checking every rule of `validators` against `value`, a
+/// reference to the field. Evaluates to a `Result<(), ConvertError>` with
+/// every failing rule.
+fn validation(
+    vaerdi_name: &Ident,
+    validators: &Validators,
+    value: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let validate = quote!(#vaerdi_name::validate);
+    let mut checks = Vec::new();
+
+    if let Some(min) = &validators.min {
+        checks.push(if is_int_literal(min) {
+            quote!(#validate::min_int(value, #min))
+        } else {
+            quote!(#validate::min(value, #min))
+        });
+    }
+
+    if let Some(max) = &validators.max {
+        checks.push(if is_int_literal(max) {
+            quote!(#validate::max_int(value, #max))
+        } else {
+            quote!(#validate::max(value, #max))
+        });
+    }
+
+    if validators.len_min.is_some() || validators.len_max.is_some() {
+        let min = match &validators.len_min {
+            Some(min) => quote!(Some(#min)),
+            None => quote!(None),
+        };
+        let max = match &validators.len_max {
+            Some(max) => quote!(Some(#max)),
+            None => quote!(None),
+        };
+        checks.push(quote!(#validate::length(value, #min, #max)));
+    }
+
+    if let Some(pattern) = &validators.pattern {
+        checks.push(quote!(#vaerdi_name::__validate_pattern!(value, #pattern)));
+    }
+
+    if validators.email {
+        checks.push(quote!(#validate::email(value)));
+    }
+
+    for custom in &validators.custom {
+        checks.push(quote!(
+            #custom(value).map_err(Into::<#vaerdi_name::ConvertError>::into)
+        ));
+    }
+
+    if validators.nested {
+        checks.push(quote!(#validate::Validate::validate(value)));
+    }
+
+    quote!({
+        let value = #value;
+        let mut __checks = #vaerdi_name::convert::ErrorCollector::new();
+        #(
+            if let Err(err) = #checks {
+                __checks.push(err);
+            }
+        )*
+        __checks.finish()
+    })
+}
+
+/// Run the rules of `validators` on the converted value of `result`
+fn validated(
+    vaerdi_name: &Ident,
+    validators: &Validators,
+    result: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if validators.is_empty() {
+        return result;
+    }
+
+    let check = validation(vaerdi_name, validators, quote!(&__value));
+    quote!((#result).and_then(|__value| {
+        let __checked = #check;
+        __checked.map(|()| __value)
+    }))
+}
+
+/// Check the rules of a single field, recording failures in `__errors`
+/// without adding to their path
+pub fn field_validate(
+    vaerdi_name: &Ident,
+    attrs: &FieldAttrs,
+    value: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if attrs.validate.is_empty() {
+        return quote!();
+    }
+
+    let check = validation(vaerdi_name, &attrs.validate, value);
+    quote!(
+        if let Err(err) = #check {
+            __errors.push(err);
+        }
+    )
+}
+
+/// Check the rules of every field, reading a reference to the field with
+/// `access` and recording failures in `__errors`
+pub fn named_validate(
+    vaerdi_name: &Ident,
+    fields: &[NamedField],
+    access: impl Fn(&NamedField) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let checks = fields
+        .iter()
+        .filter(|field| !field.attrs.skip && !field.attrs.validate.is_empty())
+        .map(|field| {
+            if field.attrs.flatten {
+                field_validate(vaerdi_name, &field.attrs, access(field))
+            } else {
+                let key = &field.key;
+                let check = validation(vaerdi_name, &field.attrs.validate, access(field));
+                quote!(__errors.field(#key, #check);)
+            }
+        });
+
+    quote!(#(#checks)*)
+}
+
+/// Check the rules of every tuple field, reading a reference to the field
+/// with `access` and recording failures in `__errors`
+pub fn tuple_validate(
+    vaerdi_name: &Ident,
+    fields: &[TupleField],
+    access: impl Fn(&TupleField) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let checks = fields
+        .iter()
+        .filter(|field| !field.attrs.validate.is_empty())
+        .map(|field| {
+            let index = field.index;
+            let check = validation(vaerdi_name, &field.attrs.validate, access(field));
+            quote!(__errors.index(#index, #check);)
+        });
+
+    quote!(#(#checks)*)
+}
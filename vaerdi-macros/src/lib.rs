@@ -1,411 +1,193 @@
 extern crate proc_macro;
+mod attr;
+mod bound;
+mod enums;
+mod fields;
+mod literal;
+mod pattern;
+mod structs;
+
+use enums::{
+    derive_enum_from_value, derive_enum_into_value, derive_enum_schema, derive_enum_validate,
+};
 use proc_macro::TokenStream;
-use proc_macro2::Ident;
-use quote::{format_ident, quote};
-use syn::{parse_macro_input, Data, DataEnum, DataStruct, DeriveInput, Field, Generics};
+use structs::{
+    derive_struct_from_value, derive_struct_from_value_ref, derive_struct_into_value,
+    derive_struct_schema, derive_struct_validate,
+};
+use syn::{parse_macro_input, Data, DeriveInput};
 
-#[proc_macro_derive(IntoValue)]
+#[proc_macro_derive(IntoValue, attributes(vaerdi))]
 pub fn derive_into_value(input: TokenStream) -> TokenStream {
     let DeriveInput {
         ident,
         data,
         generics,
+        attrs,
         ..
     } = parse_macro_input!(input as DeriveInput);
 
-    match data {
-        Data::Enum(e) => derive_enum_into_value(ident, generics, e),
-        Data::Struct(e) => derive_struct_into_value(ident, generics, e),
-        _ => panic!(""),
-    }
+    let ret = match data {
+        Data::Enum(e) => derive_enum_into_value(ident, generics, &attrs, e),
+        Data::Struct(e) => derive_struct_into_value(ident, generics, &attrs, e),
+        Data::Union(_) => Err(syn::Error::new(
+            ident.span(),
+            "IntoValue cannot be derived for unions",
+        )),
+    };
+
+    into_token_stream(ret)
 }
 
-#[proc_macro_derive(FromValue)]
+#[proc_macro_derive(FromValue, attributes(vaerdi))]
 pub fn derive_from_value(input: TokenStream) -> TokenStream {
     let DeriveInput {
         ident,
         data,
         generics,
+        attrs,
         ..
     } = parse_macro_input!(input as DeriveInput);
 
-    match data {
-        Data::Enum(e) => derive_enum_from_value(ident, generics, e),
-        Data::Struct(e) => derive_struct_from_value(ident, generics, e),
-        _ => panic!(""),
-    }
-}
-
-fn derive_enum_into_value(enum_name: Ident, generics: Generics, item: DataEnum) -> TokenStream {
-    let is_numbers = item.variants.iter().all(|m| m.discriminant.is_some());
-    let is_labels = item
-        .variants
-        .iter()
-        .all(|m| m.discriminant.is_none() && m.fields.is_empty());
-
-    let vaerdi_name = format_ident!("vaerdi");
-
-    let variants = if is_numbers {
-        item.variants
-            .iter()
-            .map(|m| {
-                let (_, int) = m.discriminant.as_ref().unwrap();
-                let ident = &m.ident;
-                quote!(#enum_name::#ident => #vaerdi_name::Value::Number(#int.into()))
-            })
-            .collect::<Vec<_>>()
-    } else if is_labels {
-        item.variants
-            .iter()
-            .map(|m| {
-                let ident = &m.ident;
-                let name = m.ident.to_string();
-                quote!(#enum_name::#ident => #vaerdi_name::Value::String(#name.into()))
-            })
-            .collect::<Vec<_>>()
-    } else {
-        item.variants
-            .iter()
-            .map(|m| {
-                let ident = &m.ident;
-                let name = m.ident.to_string();
-                let len = m.fields.len();
-
-                if len == 1 {
-                    let field = m.fields.iter().next().unwrap();
-                    // NewType type struct
-                    if field.ident.is_none() {
-                        return quote!(#enum_name::#ident(field) => {
-                            let mut map = #vaerdi_name::Map::default();
-                            map.insert("type", #name);
-                            map.insert("content", field);
-                            #vaerdi_name::Value::Map(map)
-                        });
-                    }
-                } else if len == 0 {
-                    return quote!(#enum_name::#ident => {
-                        let mut map = #vaerdi_name::Map::default();
-                        map.insert("type", #name);
-                        #vaerdi_name::Value::Map(map)
-                    });
-                }
-
-                let fields = m.fields.iter().map(|m| {
-                    let name = m.ident.as_ref().expect("expected name");
-                    let name_str = name.to_string();
-                    quote!(
-                        content.insert(#name_str, #name);
-                    )
-                });
-
-                let field_idents = m.fields.iter().map(|m| m.ident.as_ref().unwrap());
-
-                quote!(#enum_name::#ident { #(#field_idents),*} => {
-                    let mut map = #vaerdi_name::Map::default();
-                    map.insert("type", #name);
-
-                    let mut content = #vaerdi_name::Map::default();
-                    #(#fields)*
-
-                    map.insert("content", content);
-
-                    #vaerdi_name::Value::Map(map)
-                })
-            })
-            .map(Into::into)
-            .collect::<Vec<_>>()
+    let ret = match data {
+        Data::Enum(e) => derive_enum_from_value(ident, generics, &attrs, e),
+        Data::Struct(e) => derive_struct_from_value(ident, generics, &attrs, e),
+        Data::Union(_) => Err(syn::Error::new(
+            ident.span(),
+            "FromValue cannot be derived for unions",
+        )),
     };
 
-    let (ty, imp, wh) = generics.split_for_impl();
-
-    quote!(
-        impl #imp From<#enum_name #ty> for #vaerdi_name::Value #wh {
-            fn from(from: #enum_name) -> #vaerdi_name::Value {
-                match from {
-                    #(#variants),*
-                }
-            }
-        }
-    )
-    .into()
+    into_token_stream(ret)
 }
 
-fn derive_struct_into_value(name: Ident, generics: Generics, item: DataStruct) -> TokenStream {
-    let vaerdi_name = format_ident!("vaerdi");
-
-    let len = item.fields.len();
-
-    let (ty, imp, wh) = generics.split_for_impl();
-
-    if len == 1 {
-        let field = item.fields.iter().next().unwrap();
-        // NewType type struct
-        if field.ident.is_none() {
-            return quote!(
-
-                impl #imp From<#name #ty> for #vaerdi_name::Value #wh {
-                    fn from(from: #name) ->  #vaerdi_name::Value {
-                        from.0.into()
-                    }
-                }
-
-            )
-            .into();
-        }
-    }
-
-    let (ty, imp, wh) = generics.split_for_impl();
-
-    let fields = item.fields.iter().map(|m| {
-        let name = m.ident.as_ref().expect("expected name");
-        let name_str = name.to_string();
-        quote!(
-            map.insert(#name_str, from.#name);
-        )
-    });
-
-    quote!(
+#[proc_macro_derive(FromValueRef, attributes(vaerdi))]
+pub fn derive_from_value_ref(input: TokenStream) -> TokenStream {
+    let DeriveInput {
+        ident,
+        data,
+        generics,
+        attrs,
+        ..
+    } = parse_macro_input!(input as DeriveInput);
 
-        impl #imp From<#name #ty> for #vaerdi_name::Value #wh {
-            fn from(from: #name) ->  #vaerdi_name::Value {
-                let mut map = #vaerdi_name::Map::with_capacity(#len);
-                #(
-                    #fields
-                )*
-                #vaerdi_name::Value::Map(map)
-            }
-        }
+    let ret = match data {
+        Data::Struct(e) => derive_struct_from_value_ref(ident, generics, &attrs, e),
+        _ => Err(syn::Error::new(
+            ident.span(),
+            "FromValueRef can only be derived for structs",
+        )),
+    };
 
-    )
-    .into()
+    into_token_stream(ret)
 }
 
-fn derive_struct_from_value(name: Ident, generics: Generics, item: DataStruct) -> TokenStream {
-    let vaerdi_name = format_ident!("vaerdi");
-
-    let len = item.fields.len();
-
-    if len == 1 {
-        let field = item.fields.iter().next().unwrap();
-        // NewType type struct
-        if field.ident.is_none() {
-            return derive_new_type_struct_from_value(name, generics, &item, field);
-        }
-    }
-
-    let (ty, imp, wh) = generics.split_for_impl();
-
-    let fields = item
-        .fields
-        .iter()
-        .map(|m| {
-            //
-            let name = m.ident.as_ref().unwrap();
-            let name_str = name.to_string();
-            let ty = &m.ty;
-            quote!(
-                #name: match map.remove(#name_str) {
-                    Some(value) => <#ty as #vaerdi_name::convert::FromValue>::from_value(value)?,
-                    None => return Err(#vaerdi_name::ConvertError::unknown(#vaerdi_name::format!("field '{}' not found", #name_str)))
-                }
-            )
-        })
-        .collect::<Vec<_>>();
-
-    quote!(
-
-        impl #imp #vaerdi_name::convert::FromValue for #name #ty #wh {
-            type Error = #vaerdi_name::ConvertError;
-            fn from_value(mut from: #vaerdi_name::Value) -> Result<#name #ty, Self::Error> {
-                let map = match from.as_map_mut() {
-                    Some(map) => map,
-                    None => return Err(#vaerdi_name::ConvertError::invalid_type(#vaerdi_name::Type::Map, from.get_type()))
-                };
-
-                Ok(#name {
-                    #(#fields),*
-                })
+#[proc_macro_derive(ValueSchema, attributes(vaerdi))]
+pub fn derive_value_schema(input: TokenStream) -> TokenStream {
+    let DeriveInput {
+        ident,
+        data,
+        generics,
+        attrs,
+        ..
+    } = parse_macro_input!(input as DeriveInput);
 
-            }
-        }
+    let ret = match data {
+        Data::Enum(e) => derive_enum_schema(ident, generics, &attrs, e),
+        Data::Struct(e) => derive_struct_schema(ident, generics, &attrs, e),
+        Data::Union(_) => Err(syn::Error::new(
+            ident.span(),
+            "ValueSchema cannot be derived for unions",
+        )),
+    };
 
-    )
-    .into()
+    into_token_stream(ret)
 }
 
-fn derive_new_type_struct_from_value(
-    name: Ident,
-    generics: Generics,
-    _item: &DataStruct,
-    field: &Field,
-) -> TokenStream {
-    let vaerdi_name = format_ident!("vaerdi");
-    let ty = &field.ty;
-    let (gty, imp, wh) = generics.split_for_impl();
-
-    quote!(
-
-        impl #imp #vaerdi_name::convert::FromValue for #name #gty #wh {
-            type Error = #vaerdi_name::ConvertError;
-            fn from_value(from: #vaerdi_name::Value) -> Result<#name, Self::Error> {
-                Ok(#name(<#ty as #vaerdi_name::convert::FromValue>::from_value(from)?))
-            }
-        }
-
+#[proc_macro_derive(Validate, attributes(vaerdi))]
+pub fn derive_validate(input: TokenStream) -> TokenStream {
+    let DeriveInput {
+        ident,
+        data,
+        generics,
+        attrs,
+        ..
+    } = parse_macro_input!(input as DeriveInput);
 
+    let ret = match data {
+        Data::Enum(e) => derive_enum_validate(ident, generics, &attrs, e),
+        Data::Struct(e) => derive_struct_validate(ident, generics, &attrs, e),
+        Data::Union(_) => Err(syn::Error::new(
+            ident.span(),
+            "Validate cannot be derived for unions",
+        )),
+    };
 
-    )
-    .into()
+    into_token_stream(ret)
 }
 
-fn derive_enum_from_value(enum_name: Ident, generics: Generics, item: DataEnum) -> TokenStream {
-    let is_numbers = item.variants.iter().all(|m| m.discriminant.is_some());
-    let is_labels = item
-        .variants
-        .iter()
-        .all(|m| m.discriminant.is_none() && m.fields.is_empty());
-
-    let vaerdi_name = format_ident!("vaerdi");
-
-    if is_numbers {
-        let variants = item.variants.iter().map(|item| {
-            let (_, expr) = item.discriminant.as_ref().unwrap();
-            let name = &item.ident;
-            quote!(#expr => Ok(#enum_name::#name))
-        });
-        quote!(
-            
-
-            impl #vaerdi_name::convert::FromValue for #enum_name {
-                type Error = #vaerdi_name::ConvertError;
-                fn from_value(from: #vaerdi_name::Value) -> Result<#enum_name, Self::Error> {
-                    let Some(number) = from.as_number() else {
-                        return Err(#vaerdi_name::ConvertError::invalid_type(#vaerdi_name::Type::I32, from.get_type()));
-                    };
-
-                    match number.as_i32() {
-                        #(#variants),*,
-                        v => {
-                            return Err(#vaerdi_name::ConvertError::unknown(format!("invalid variant: {}",v)));
-                        }
-                    }
-                }
-            }
-
-        )
-        .into()
-    } else if is_labels {
-        let variants = item.variants.iter().map(|item| {
-            let name = &item.ident;
-            let name_str = item.ident.to_string();
-            quote!(#name_str => Ok(#enum_name::#name))
-        });
-        quote!(
-            impl #vaerdi_name::convert::FromValue for #enum_name {
-                type Error = #vaerdi_name::ConvertError;
-                fn from_value(from: #vaerdi_name::Value) -> Result<#enum_name, Self::Error> {
-                    let Some(name) = from.as_string() else {
-                        return Err(#vaerdi_name::ConvertError::invalid_type(#vaerdi_name::Type::String, from.get_type()));
-                    };
-
-                    match &**name {
-                        #(#variants),*,
-                        v => {
-                            return Err(#vaerdi_name::ConvertError::unknown_variant(v));
-                        }
-                    }
-                }
-            }
-        )
-        .into()
-    } else {
-        let variants = item.variants.iter().map(|var| {
-            let name = &var.ident;
-            let name_str = var.ident.to_string();
-
-            if var.fields.is_empty() {
-                quote!(#name_str => {
-                    Ok(#enum_name::#name)
-                })
-            } else {
-                if var.fields.len() == 1 {
-                    let field = var.fields.iter().next().unwrap();
-                    let ty = &field.ty;
-                    if field.ident.is_none() {
-                        return quote!(#name_str => {
-                            let Some(content) = map.remove("content") else {
-                                return Err(#vaerdi_name::ConvertError::unknown("field 'content' not found"))
-                            };
-                            Ok(#enum_name::#name(<#ty as #vaerdi_name::convert::FromValue>::from_value(content)?))
-                        });
-                    }
-                }
-
-                let fields = var.fields.iter().map(|m| {
-                    let field_name = m.ident.as_ref().unwrap();
-                    let field_name_str = field_name.to_string();
-                    let ty = &m.ty;
-
-                    quote!(
-                        #field_name: match content.remove(#field_name_str) {
-                            Some(m) => <#ty as #vaerdi_name::convert::FromValue>::from_value(m)?,
-                            None => {
-                                return Err(#vaerdi_name::ConvertError::unknown("field not found"))
-
-                            }
-                        }
-                    )
-                });
-
-                quote!(
-                    #name_str => {
-                        let Some(mut content) = map.remove("content") else {
-                            return Err(#vaerdi_name::ConvertError::unknown("field 'content' not found"))
-                        };
-
-                        let Some(mut content) = content.as_map_mut() else {
-                            return Err(#vaerdi_name::ConvertError::invalid_type(#vaerdi_name::Type::Map, content.get_type()));
-                        };
-
-                        Ok(#enum_name::#name {
-                            #(#fields),*,
-                        })
-
-                    }
-                )
-            }
-        });
-
-        let (ty, imp, wh) = generics.split_for_impl();
-
-        quote!(
+/// A `Uuid` parsed at compile time: `uuid!("67e55044-10b1-426f-9247-bb680e5fe0c8")`
+#[proc_macro]
+pub fn uuid(input: TokenStream) -> TokenStream {
+    into_token_stream(literal::uuid(input.into()))
+}
 
+/// A `NaiveDate` checked at compile time: `date!(2024-01-02)`
+#[proc_macro]
+pub fn date(input: TokenStream) -> TokenStream {
+    into_token_stream(literal::date(input.into()))
+}
 
-            impl #imp #vaerdi_name::convert::FromValue for #enum_name #ty #wh {
-                type Error = #vaerdi_name::ConvertError;
-                fn from_value(mut from: #vaerdi_name::Value) -> Result<#enum_name #ty, Self::Error> {
-                    let Some(mut map) = from.as_map_mut() else {
-                        return Err(#vaerdi_name::ConvertError::invalid_type(#vaerdi_name::Type::Map, from.get_type()))
-                    };
+/// A `NaiveTime` checked at compile time: `time!(12:30:00)`
+#[proc_macro]
+pub fn time(input: TokenStream) -> TokenStream {
+    into_token_stream(literal::time(input.into()))
+}
 
-                    let Some(ty) = map.get("type") else {
-                        return Err(#vaerdi_name::ConvertError::unknown("field 'type' not found"))
-                    };
+/// A `NaiveDateTime` checked at compile time: `datetime!(2024-01-02 12:30:00)`
+#[proc_macro]
+pub fn datetime(input: TokenStream) -> TokenStream {
+    into_token_stream(literal::datetime(input.into()))
+}
 
-                    let Some(ty_str) = ty.as_string() else {
-                        return Err(#vaerdi_name::ConvertError::invalid_type(#vaerdi_name::Type::String, ty.get_type()))
-                    };
+/// Destructure a `Value` by its shape.
+///
+/// ```ignore
+/// let name = match_value!(value {
+///     { "user": { "id": id @ Uuid, "name": name @ String, "tags": [first, ..] } } => name,
+///     { "error": message } => return Err(message.to_string()),
+///     _ => String::from("unknown"),
+/// })?;
+/// ```
+///
+/// The arms are tried in order and the first one that matches, and whose
+/// `if` guard holds, is evaluated.
+/// The result is a `Result<T, ConvertError>` that holds the errors of every
+/// arm when none of them matches.
+///
+/// - `{ "key": pattern, ... }` matches a map with the keys, other keys are ignored
+/// - `[pattern, ...]` matches a list of exactly that length, `[first, .., last]`
+///   a list of at least that length
+/// - `name` binds a `&Value`, `name @ Type` converts it with `FromValue` and
+///   `name @ { ... }` binds it and matches the inner pattern
+/// - `_` matches anything, `_ @ Type` anything that converts to `Type`
+/// - `null` and literals like `"text"`, `1` or `true` match equal values
+#[proc_macro]
+pub fn match_value(input: TokenStream) -> TokenStream {
+    into_token_stream(pattern::match_value(parse_macro_input!(
+        input as pattern::MatchValue
+    )))
+}
 
-                    match ty_str.as_str() {
-                        #(#variants),*,
-                        v => {
-                            return Err(#vaerdi_name::ConvertError::unknown_variant(v));
-                        }
-                    }
-                }
-            }
+#[doc(hidden)]
+#[proc_macro]
+pub fn value_literal(input: TokenStream) -> TokenStream {
+    into_token_stream(literal::value_literal(input.into()))
+}
 
-        )
-        .into()
+fn into_token_stream(ret: syn::Result<proc_macro2::TokenStream>) -> TokenStream {
+    match ret {
+        Ok(ret) => ret.into(),
+        Err(err) => err.to_compile_error().into(),
     }
 }
@@ -0,0 +1,236 @@
+use proc_macro2::{Literal, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, Lit, LitStr};
+
+/// The text of a literal macro, either a single string literal or the tokens
+/// written out without whitespace, like `2024-01-02` or `12:30:00`
+fn literal_text(input: TokenStream) -> syn::Result<(String, Span)> {
+    if let Ok(lit) = syn::parse2::<LitStr>(input.clone()) {
+        return Ok((lit.value(), lit.span()));
+    }
+
+    let span = input.span();
+    let mut text = String::new();
+    for token in input {
+        match token {
+            TokenTree::Literal(lit) => text.push_str(&lit.to_string()),
+            TokenTree::Punct(punct) => text.push(punct.as_char()),
+            TokenTree::Ident(ident) => text.push_str(&ident.to_string()),
+            TokenTree::Group(group) => {
+                return Err(syn::Error::new(group.span(), "unexpected group"))
+            }
+        }
+    }
+
+    if text.is_empty() {
+        return Err(syn::Error::new(span, "expected a literal"));
+    }
+
+    Ok((text, span))
+}
+
+fn digits(input: &str, len: usize) -> Option<u32> {
+    if input.len() != len || !input.bytes().all(|m| m.is_ascii_digit()) {
+        return None;
+    }
+    input.parse().ok()
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// `YYYY-MM-DD`
+fn parse_date(text: &str) -> Option<(i32, u32, u32)> {
+    let mut parts = text.split('-');
+    let year = digits(parts.next()?, 4)? as i32;
+    let month = digits(parts.next()?, 2)?;
+    let day = digits(parts.next()?, 2)?;
+
+    if parts.next().is_some() || !(1..=12).contains(&month) {
+        return None;
+    }
+
+    if day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    Some((year, month, day))
+}
+
+/// `HH:MM`, `HH:MM:SS` or `HH:MM:SS.fraction`
+fn parse_time(text: &str) -> Option<(u32, u32, u32, u32)> {
+    let (text, nano) = match text.split_once('.') {
+        Some((text, fraction)) => {
+            if fraction.is_empty() || fraction.len() > 9 {
+                return None;
+            }
+            let nano = digits(fraction, fraction.len())? * 10u32.pow(9 - fraction.len() as u32);
+            (text, nano)
+        }
+        None => (text, 0),
+    };
+
+    let mut parts = text.split(':');
+    let hour = digits(parts.next()?, 2)?;
+    let minute = digits(parts.next()?, 2)?;
+    let second = match parts.next() {
+        Some(second) => digits(second, 2)?,
+        None if nano == 0 => 0,
+        None => return None,
+    };
+
+    if parts.next().is_some() || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    Some((hour, minute, second, nano))
+}
+
+fn date_tokens(vaerdi_name: &syn::Ident, (year, month, day): (i32, u32, u32)) -> TokenStream {
+    quote!(
+        match #vaerdi_name::NaiveDate::from_ymd_opt(#year, #month, #day) {
+            Some(date) => date,
+            None => unreachable!(),
+        }
+    )
+}
+
+fn time_tokens(
+    vaerdi_name: &syn::Ident,
+    (hour, minute, second, nano): (u32, u32, u32, u32),
+) -> TokenStream {
+    quote!(
+        match #vaerdi_name::NaiveTime::from_hms_nano_opt(#hour, #minute, #second, #nano) {
+            Some(time) => time,
+            None => unreachable!(),
+        }
+    )
+}
+
+pub fn uuid(input: TokenStream) -> syn::Result<TokenStream> {
+    let lit: LitStr = syn::parse2(input)?;
+    let text = lit.value();
+
+    let hex = match text.len() {
+        32 => text.clone(),
+        36 if [8, 13, 18, 23]
+            .iter()
+            .all(|&idx| &text[idx..idx + 1] == "-") =>
+        {
+            text.replace('-', "")
+        }
+        _ => String::new(),
+    };
+
+    let value = match u128::from_str_radix(&hex, 16) {
+        Ok(value) if hex.bytes().all(|m| m.is_ascii_hexdigit()) => value,
+        _ => {
+            return Err(syn::Error::new(
+                lit.span(),
+                "invalid uuid, expected 32 hex digits optionally grouped as 8-4-4-4-12",
+            ))
+        }
+    };
+
+    let vaerdi_name = format_ident!("vaerdi");
+    let value = Literal::u128_suffixed(value);
+    Ok(quote!(#vaerdi_name::Uuid::from_u128(#value)))
+}
+
+pub fn date(input: TokenStream) -> syn::Result<TokenStream> {
+    let (text, span) = literal_text(input)?;
+    let Some(date) = parse_date(&text) else {
+        return Err(syn::Error::new(span, "invalid date, expected YYYY-MM-DD"));
+    };
+
+    Ok(date_tokens(&format_ident!("vaerdi"), date))
+}
+
+pub fn time(input: TokenStream) -> syn::Result<TokenStream> {
+    let (text, span) = literal_text(input)?;
+    let Some(time) = parse_time(&text) else {
+        return Err(syn::Error::new(
+            span,
+            "invalid time, expected HH:MM, HH:MM:SS or HH:MM:SS.fraction",
+        ));
+    };
+
+    Ok(time_tokens(&format_ident!("vaerdi"), time))
+}
+
+pub fn datetime(input: TokenStream) -> syn::Result<TokenStream> {
+    let (text, span) = literal_text(input)?;
+
+    // The date has a fixed width, the time follows after an optional `T` or space
+    let parsed = text.get(..10).zip(text.get(10..)).and_then(|(date, time)| {
+        let time = time.strip_prefix(['T', ' ']).unwrap_or(time);
+        Some((parse_date(date)?, parse_time(time)?))
+    });
+
+    let Some((date, time)) = parsed else {
+        return Err(syn::Error::new(
+            span,
+            "invalid datetime, expected YYYY-MM-DD HH:MM:SS or YYYY-MM-DDTHH:MM:SS",
+        ));
+    };
+
+    let vaerdi_name = format_ident!("vaerdi");
+    let date = date_tokens(&vaerdi_name, date);
+    let time = time_tokens(&vaerdi_name, time);
+
+    Ok(quote!(#vaerdi_name::NaiveDateTime::new(#date, #time)))
+}
+
+/// A literal in `value!` turned into the matching variant of `Value`
+pub fn value_literal(input: TokenStream) -> syn::Result<TokenStream> {
+    // A `literal` fragment includes the sign of a negative number
+    let mut tokens = input.into_iter().peekable();
+    let negative = match tokens.peek() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '-' => {
+            tokens.next();
+            true
+        }
+        _ => false,
+    };
+
+    let lit: Lit = syn::parse2(tokens.collect())?;
+    let vaerdi_name = format_ident!("vaerdi");
+
+    if negative && !matches!(lit, Lit::Int(_) | Lit::Float(_)) {
+        return Err(syn::Error::new(lit.span(), "only numbers can be negative"));
+    }
+    let sign = negative.then(|| quote!(-));
+
+    let ret = match &lit {
+        Lit::Str(lit) => quote!(#vaerdi_name::Value::String(#lit.into())),
+        Lit::ByteStr(lit) => {
+            quote!(#vaerdi_name::Value::Bytes(::core::convert::From::from(&#lit[..])))
+        }
+        Lit::Char(lit) => quote!(#vaerdi_name::Value::Char(#lit)),
+        Lit::Bool(lit) => quote!(#vaerdi_name::Value::Bool(#lit)),
+        Lit::Int(int) => match int.suffix() {
+            "u128" | "i128" => {
+                return Err(syn::Error::new(
+                    int.span(),
+                    "Value has no 128 bit numbers, use a 64 bit suffix",
+                ))
+            }
+            "isize" => {
+                let value = Literal::i64_suffixed(int.base10_parse()?);
+                quote!(#vaerdi_name::Value::from(#sign #value))
+            }
+            _ => quote!(#vaerdi_name::Value::from(#sign #lit)),
+        },
+        Lit::Float(_) => quote!(#vaerdi_name::Value::from(#sign #lit)),
+        Lit::Byte(_) => quote!(#vaerdi_name::Value::from(#lit)),
+        _ => return Err(syn::Error::new(lit.span(), "unsupported literal")),
+    };
+
+    Ok(ret)
+}
@@ -0,0 +1,426 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    braced, bracketed,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Expr, Ident, Lit, LitStr, Token, Type,
+};
+
+/// `match_value!(expr { pattern => body, ... })`
+pub struct MatchValue {
+    expr: Expr,
+    arms: Vec<Arm>,
+}
+
+struct Arm {
+    pattern: Pattern,
+    guard: Option<Expr>,
+    body: Expr,
+}
+
+enum Pattern {
+    /// `_`
+    Wild,
+    /// `null`
+    Null,
+    /// `"text"`, `1`, `-1.5`, `true`, ...
+    Literal(Option<Token![-]>, Lit),
+    /// `name`, `name @ Type` or `name @ { ... }`
+    Bind {
+        name: Ident,
+        ty: Option<Type>,
+        pattern: Option<Box<Pattern>>,
+    },
+    /// `_ @ Type`
+    Typed(Type),
+    /// `{ "key": pattern, ... }`
+    Map(Vec<(LitStr, Pattern)>),
+    /// `[pattern, .., pattern]`
+    List {
+        head: Vec<Pattern>,
+        tail: Option<Vec<Pattern>>,
+    },
+}
+
+impl Parse for MatchValue {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let expr = Expr::parse_without_eager_brace(input)?;
+
+        let content;
+        braced!(content in input);
+
+        let mut arms = Vec::new();
+        while !content.is_empty() {
+            let pattern = content.parse()?;
+            let guard = match content.parse::<Option<Token![if]>>()? {
+                Some(_) => Some(content.parse()?),
+                None => None,
+            };
+            content.parse::<Token![=>]>()?;
+            let body: Expr = content.parse()?;
+
+            let needs_comma = !matches!(body, Expr::Block(_)) && !content.is_empty();
+            if needs_comma {
+                content.parse::<Token![,]>()?;
+            } else {
+                content.parse::<Option<Token![,]>>()?;
+            }
+
+            arms.push(Arm {
+                pattern,
+                guard,
+                body,
+            });
+        }
+
+        if arms.is_empty() {
+            return Err(syn::Error::new(input.span(), "expected at least one arm"));
+        }
+
+        Ok(MatchValue { expr, arms })
+    }
+}
+
+impl Parse for Pattern {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(syn::token::Brace) {
+            let content;
+            braced!(content in input);
+            let entries = Punctuated::<(LitStr, Pattern), Token![,]>::parse_terminated_with(
+                &content,
+                |input| {
+                    let key = input.parse()?;
+                    input.parse::<Token![:]>()?;
+                    Ok((key, input.parse()?))
+                },
+            )?;
+            return Ok(Pattern::Map(entries.into_iter().collect()));
+        }
+
+        if input.peek(syn::token::Bracket) {
+            let content;
+            bracketed!(content in input);
+
+            let mut head = Vec::new();
+            let mut tail = None;
+            while !content.is_empty() {
+                if content.peek(Token![..]) {
+                    let dots = content.parse::<Token![..]>()?;
+                    if tail.is_some() {
+                        return Err(syn::Error::new_spanned(dots, "`..` can only be used once"));
+                    }
+                    tail = Some(Vec::new());
+                } else {
+                    let pattern = content.parse()?;
+                    match &mut tail {
+                        Some(tail) => tail.push(pattern),
+                        None => head.push(pattern),
+                    }
+                }
+
+                if !content.is_empty() {
+                    content.parse::<Token![,]>()?;
+                }
+            }
+
+            return Ok(Pattern::List { head, tail });
+        }
+
+        if input.peek(Token![_]) {
+            input.parse::<Token![_]>()?;
+            if input.parse::<Option<Token![@]>>()?.is_some() {
+                return Ok(Pattern::Typed(input.parse()?));
+            }
+            return Ok(Pattern::Wild);
+        }
+
+        if input.peek(Ident) {
+            let name: Ident = input.parse()?;
+            if name == "null" {
+                return Ok(Pattern::Null);
+            }
+
+            if input.parse::<Option<Token![@]>>()?.is_none() {
+                return Ok(Pattern::Bind {
+                    name,
+                    ty: None,
+                    pattern: None,
+                });
+            }
+
+            if input.peek(syn::token::Brace) || input.peek(syn::token::Bracket) {
+                return Ok(Pattern::Bind {
+                    name,
+                    ty: None,
+                    pattern: Some(Box::new(input.parse()?)),
+                });
+            }
+
+            return Ok(Pattern::Bind {
+                name,
+                ty: Some(input.parse()?),
+                pattern: None,
+            });
+        }
+
+        let sign = input.parse::<Option<Token![-]>>()?;
+        let lit: Lit = input.parse()?;
+        if sign.is_some() && !matches!(lit, Lit::Int(_) | Lit::Float(_)) {
+            return Err(syn::Error::new(lit.span(), "only numbers can be negative"));
+        }
+
+        Ok(Pattern::Literal(sign, lit))
+    }
+}
+
+struct Context<'a> {
+    vaerdi_name: &'a Ident,
+    label: &'a syn::Lifetime,
+    counter: usize,
+    bindings: Vec<Ident>,
+}
+
+impl<'a> Context<'a> {
+    fn next_ident(&mut self) -> Ident {
+        self.counter += 1;
+        format_ident!("__value_{}", self.counter)
+    }
+
+    fn bind(&mut self, name: &Ident) -> syn::Result<()> {
+        if self.bindings.contains(name) {
+            return Err(syn::Error::new(
+                name.span(),
+                format!("`{name}` is bound more than once in the same pattern"),
+            ));
+        }
+        self.bindings.push(name.clone());
+        Ok(())
+    }
+
+    /// Leave the arm with `error`, located at `path`
+    fn fail(&self, error: TokenStream, path: &[TokenStream]) -> TokenStream {
+        let vaerdi_name = self.vaerdi_name;
+        let label = self.label;
+        if path.is_empty() {
+            quote!(break #label ::core::result::Result::Err(#error))
+        } else {
+            quote!(
+                break #label ::core::result::Result::Err(
+                    #error.with_path([#(#vaerdi_name::convert::Path::#path),*])
+                )
+            )
+        }
+    }
+
+    fn pattern(
+        &mut self,
+        pattern: &Pattern,
+        value: &Ident,
+        path: &[TokenStream],
+    ) -> syn::Result<TokenStream> {
+        let vaerdi_name = self.vaerdi_name;
+
+        let ret = match pattern {
+            Pattern::Wild => quote!(),
+            Pattern::Null => {
+                let fail = self.fail(
+                    quote!(#vaerdi_name::convert::ConvertError::unknown("expected null")),
+                    path,
+                );
+                quote!(if !#value.is_null() { #fail; })
+            }
+            Pattern::Literal(sign, lit) => {
+                let sign_text = if sign.is_some() { "-" } else { "" };
+                let message = format!("expected {sign_text}{}", lit.to_token_stream());
+                let fail = self.fail(
+                    quote!(#vaerdi_name::convert::ConvertError::unknown(#message)),
+                    path,
+                );
+                quote!(if *#value != #vaerdi_name::Value::from(#sign #lit) { #fail; })
+            }
+            Pattern::Typed(ty) => {
+                let convert = self.convert(ty, value, path);
+                quote!(let _: #ty = #convert;)
+            }
+            Pattern::Bind { name, ty, pattern } => {
+                self.bind(name)?;
+                let inner = match pattern {
+                    Some(pattern) => self.pattern(pattern, value, path)?,
+                    None => quote!(),
+                };
+                match ty {
+                    Some(ty) => {
+                        let convert = self.convert(ty, value, path);
+                        quote!(#inner let #name: #ty = #convert;)
+                    }
+                    None => quote!(#inner let #name: &#vaerdi_name::Value = #value;),
+                }
+            }
+            Pattern::Map(entries) => {
+                let map = self.next_ident();
+                let fail = self.fail(
+                    quote!(#vaerdi_name::convert::ConvertError::invalid_type(
+                        #vaerdi_name::Type::Map,
+                        #value.get_type()
+                    )),
+                    path,
+                );
+
+                let mut ret = quote!(
+                    let ::core::option::Option::Some(#map) = #value.as_map() else { #fail; };
+                );
+
+                for (key, pattern) in entries {
+                    let field = self.next_ident();
+                    let fail = self.fail(
+                        quote!(#vaerdi_name::convert::ConvertError::missing_field(#key)),
+                        path,
+                    );
+
+                    let mut path = path.to_vec();
+                    path.push(quote!(Field(#vaerdi_name::String::from(#key))));
+                    let inner = self.pattern(pattern, &field, &path)?;
+
+                    ret.extend(quote!(
+                        let ::core::option::Option::Some(#field) = #map.get(#key) else { #fail; };
+                        #inner
+                    ));
+                }
+
+                ret
+            }
+            Pattern::List { head, tail } => {
+                let list = self.next_ident();
+                let fail = self.fail(
+                    quote!(#vaerdi_name::convert::ConvertError::invalid_type(
+                        #vaerdi_name::Type::List,
+                        #value.get_type()
+                    )),
+                    path,
+                );
+
+                let len = head.len() + tail.as_ref().map_or(0, Vec::len);
+                let len_fail = self.fail(
+                    quote!(#vaerdi_name::convert::ConvertError::invalid_length(#len, #list.len())),
+                    path,
+                );
+                let check = match tail {
+                    Some(_) => quote!(#list.len() < #len),
+                    None => quote!(#list.len() != #len),
+                };
+
+                let mut ret = quote!(
+                    let ::core::option::Option::Some(#list) = #value.as_list() else { #fail; };
+                    if #check { #len_fail; }
+                );
+
+                let indices = (0..head.len())
+                    .map(|idx| quote!(#idx))
+                    .chain(tail.iter().flat_map(|tail| {
+                        let list = &list;
+                        (0..tail.len())
+                            .rev()
+                            .map(move |back| quote!(#list.len() - 1 - #back))
+                    }));
+
+                for (pattern, idx) in head.iter().chain(tail.iter().flatten()).zip(indices) {
+                    if matches!(pattern, Pattern::Wild) {
+                        continue;
+                    }
+
+                    let item = self.next_ident();
+                    let mut path = path.to_vec();
+                    path.push(quote!(Index(#idx)));
+                    let inner = self.pattern(pattern, &item, &path)?;
+
+                    ret.extend(quote!(
+                        let #item = &#list.iter().as_slice()[#idx];
+                        #inner
+                    ));
+                }
+
+                ret
+            }
+        };
+
+        Ok(ret)
+    }
+
+    fn convert(&self, ty: &Type, value: &Ident, path: &[TokenStream]) -> TokenStream {
+        let vaerdi_name = self.vaerdi_name;
+        let fail = self.fail(
+            quote!(::core::convert::Into::<#vaerdi_name::convert::ConvertError>::into(err)),
+            path,
+        );
+        quote!(
+            match <#ty as #vaerdi_name::convert::FromValue>::from_value(
+                ::core::clone::Clone::clone(#value)
+            ) {
+                ::core::result::Result::Ok(ret) => ret,
+                ::core::result::Result::Err(err) => { #fail; }
+            }
+        )
+    }
+}
+
+pub fn match_value(input: MatchValue) -> syn::Result<TokenStream> {
+    let vaerdi_name = format_ident!("vaerdi");
+    let label = syn::Lifetime::new("'__vaerdi_arm", Span::call_site());
+    let value = format_ident!("__value");
+    let expr = &input.expr;
+
+    // Every arm extracts its bindings inside a labeled block, and the body runs
+    // outside of it, so `return` and `?` in a body behave as in a `match`
+    let mut ret = quote!(match __errors.finish() {
+        ::core::result::Result::Err(err) => ::core::result::Result::Err(err),
+        ::core::result::Result::Ok(()) => ::core::unreachable!(),
+    });
+
+    for arm in input.arms.iter().rev() {
+        let mut ctx = Context {
+            vaerdi_name: &vaerdi_name,
+            label: &label,
+            counter: 0,
+            bindings: Vec::new(),
+        };
+
+        let mut pattern = ctx.pattern(&arm.pattern, &value, &[])?;
+        // A binding may only be used by the guard
+        let unused = arm.guard.is_some().then(|| quote!(unused_variables,));
+        if let Some(guard) = &arm.guard {
+            let fail = ctx.fail(
+                quote!(#vaerdi_name::convert::ConvertError::unknown("the guard did not match")),
+                &[],
+            );
+            pattern.extend(quote!(if !(#guard) { #fail; }));
+        }
+        let bindings = &ctx.bindings;
+        let body = arm.body.to_token_stream();
+
+        ret = quote!(
+            #[allow(unused_labels)]
+            let __arm: ::core::result::Result<_, #vaerdi_name::convert::ConvertError> = #label: {
+                #pattern
+                ::core::result::Result::Ok((#(#bindings,)*))
+            };
+
+            match __arm {
+                // A body that diverges, like `return`, makes the `Ok` unreachable
+                #[allow(#unused unreachable_code)]
+                ::core::result::Result::Ok((#(#bindings,)*)) => ::core::result::Result::Ok(#body),
+                ::core::result::Result::Err(err) => {
+                    __errors.push(err);
+                    #ret
+                }
+            }
+        );
+    }
+
+    Ok(quote!({
+        let #value: &#vaerdi_name::Value = &(#expr);
+        #[allow(unused_mut)]
+        let mut __errors = #vaerdi_name::convert::ErrorCollector::new();
+        #ret
+    }))
+}
@@ -0,0 +1,340 @@
+use crate::{
+    attr::ContainerAttrs,
+    bound::with_bound,
+    fields::{
+        collect_fields, construct_fields, field_schema, field_validate, insert_fields,
+        named_fields, named_schema, named_validate, tuple_field_from_value, tuple_field_into_value,
+        tuple_fields, tuple_from_value, tuple_into_value, tuple_schema, tuple_validate, NamedField,
+        Receiver, Source, TupleField,
+    },
+};
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::{parse_quote, Attribute, DataStruct, Fields, Generics, Index, Lifetime};
+
+enum Shape<'a> {
+    /// `struct Unit;` is stored as null
+    Unit,
+    /// `struct NewType(T);` is stored as `T`
+    NewType(Box<TupleField<'a>>),
+    /// `struct Tuple(A, B);` is stored as a list
+    Tuple(Vec<TupleField<'a>>),
+    /// `struct Named { a: A }` is stored as a map
+    Named(Vec<NamedField<'a>>),
+}
+
+impl<'a> Shape<'a> {
+    fn new(item: &'a DataStruct, container: &ContainerAttrs) -> syn::Result<Shape<'a>> {
+        let shape = match &item.fields {
+            Fields::Unit => Shape::Unit,
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                Shape::NewType(Box::new(tuple_fields(&fields.unnamed)?.remove(0)))
+            }
+            Fields::Unnamed(fields) => Shape::Tuple(tuple_fields(&fields.unnamed)?),
+            Fields::Named(fields) => {
+                Shape::Named(named_fields(&fields.named, container.rename_all)?)
+            }
+        };
+        Ok(shape)
+    }
+}
+
+pub fn derive_struct_into_value(
+    name: Ident,
+    generics: Generics,
+    attrs: &[Attribute],
+    item: DataStruct,
+) -> syn::Result<TokenStream> {
+    let container = ContainerAttrs::parse(attrs)?;
+    let vaerdi_name = format_ident!("vaerdi");
+    let shape = Shape::new(&item, &container)?;
+
+    let body = |receiver: Receiver| {
+        let reference = match receiver {
+            Receiver::Owned => quote!(),
+            Receiver::Borrowed => quote!(&),
+        };
+
+        match &shape {
+            Shape::Unit => quote!(#vaerdi_name::Value::Null),
+            Shape::NewType(field) => {
+                let value = tuple_field_into_value(
+                    &vaerdi_name,
+                    field,
+                    receiver,
+                    quote!(#reference from.0),
+                );
+                quote!(::core::convert::Into::<#vaerdi_name::Value>::into(#value))
+            }
+            Shape::Tuple(fields) => tuple_into_value(&vaerdi_name, fields, receiver, |field| {
+                let index = Index::from(field.index);
+                quote!(#reference from.#index)
+            }),
+            Shape::Named(fields) => {
+                let len = fields.len();
+                let insert = insert_fields(
+                    &vaerdi_name,
+                    fields,
+                    &format_ident!("map"),
+                    receiver,
+                    |field| {
+                        let ident = field.ident;
+                        quote!(#reference from.#ident)
+                    },
+                );
+                quote!(
+                    let mut map = #vaerdi_name::Map::with_capacity(#len);
+                    #insert
+                    #vaerdi_name::Value::Map(map)
+                )
+            }
+        }
+    };
+
+    let owned = body(Receiver::Owned);
+    let borrowed = body(Receiver::Borrowed);
+
+    let owned_generics = with_bound(
+        &generics,
+        quote!(::core::convert::Into<#vaerdi_name::Value>),
+    );
+    let (imp, ty, wh) = owned_generics.split_for_impl();
+
+    let mut borrowed_generics = with_bound(&generics, quote!(#vaerdi_name::convert::IntoValue));
+    borrowed_generics.params.insert(0, parse_quote!('__vaerdi));
+    let (ref_imp, _, ref_wh) = borrowed_generics.split_for_impl();
+
+    Ok(quote!(
+        impl #imp From<#name #ty> for #vaerdi_name::Value #wh {
+            #[allow(unused_variables)]
+            fn from(from: #name #ty) -> #vaerdi_name::Value {
+                #owned
+            }
+        }
+
+        impl #ref_imp From<&'__vaerdi #name #ty> for #vaerdi_name::Value #ref_wh {
+            #[allow(unused_variables)]
+            fn from(from: &'__vaerdi #name #ty) -> #vaerdi_name::Value {
+                #borrowed
+            }
+        }
+    ))
+}
+
+pub fn derive_struct_from_value(
+    name: Ident,
+    generics: Generics,
+    attrs: &[Attribute],
+    item: DataStruct,
+) -> syn::Result<TokenStream> {
+    let container = ContainerAttrs::parse(attrs)?;
+    let vaerdi_name = format_ident!("vaerdi");
+    let shape = Shape::new(&item, &container)?;
+
+    let body = match &shape {
+        Shape::Unit => unit_from_value(&vaerdi_name, &name),
+        Shape::NewType(field) => {
+            let from_value =
+                tuple_field_from_value(&vaerdi_name, field, Source::Owned, quote!(from))?;
+            quote!((#from_value).map(#name))
+        }
+        Shape::Tuple(fields) => tuple_from_value(
+            &vaerdi_name,
+            fields,
+            Source::Owned,
+            quote!(from),
+            quote!(#name),
+        )?,
+        Shape::Named(fields) => {
+            let collect = collect_fields(
+                &vaerdi_name,
+                fields,
+                Source::Owned,
+                container.deny_unknown_fields,
+            )?;
+            let construct = construct_fields(fields);
+
+            quote!(
+                let mut from = from;
+                let __map = match from.as_map_mut() {
+                    Some(map) => map,
+                    None => return Err(#vaerdi_name::ConvertError::invalid_type(#vaerdi_name::Type::Map, from.get_type()))
+                };
+
+                let mut __errors = #vaerdi_name::convert::ErrorCollector::new();
+
+                #collect
+
+                __errors.finish()?;
+
+                Ok(#name {
+                    #construct
+                })
+            )
+        }
+    };
+
+    let generics = with_bound(&generics, quote!(#vaerdi_name::convert::FromValue));
+    let (imp, ty, wh) = generics.split_for_impl();
+
+    Ok(quote!(
+        impl #imp #vaerdi_name::convert::FromValue for #name #ty #wh {
+            type Error = #vaerdi_name::ConvertError;
+            fn from_value(from: #vaerdi_name::Value) -> Result<Self, Self::Error> {
+                #body
+            }
+        }
+    ))
+}
+
+pub fn derive_struct_from_value_ref(
+    name: Ident,
+    generics: Generics,
+    attrs: &[Attribute],
+    item: DataStruct,
+) -> syn::Result<TokenStream> {
+    let container = ContainerAttrs::parse(attrs)?;
+    let vaerdi_name = format_ident!("vaerdi");
+    let shape = Shape::new(&item, &container)?;
+
+    // Borrow for the struct's own lifetime if it has one
+    let mut impl_generics = generics.clone();
+    let lifetime = match generics.lifetimes().next() {
+        Some(param) => param.lifetime.clone(),
+        None => {
+            let lifetime = Lifetime::new("'__vaerdi", name.span());
+            impl_generics.params.insert(0, parse_quote!(#lifetime));
+            lifetime
+        }
+    };
+
+    let source = Source::Borrowed(&lifetime);
+
+    let body = match &shape {
+        Shape::Unit => unit_from_value(&vaerdi_name, &name),
+        Shape::NewType(field) => {
+            let from_value = tuple_field_from_value(&vaerdi_name, field, source, quote!(from))?;
+            quote!((#from_value).map(#name))
+        }
+        Shape::Tuple(fields) => {
+            tuple_from_value(&vaerdi_name, fields, source, quote!(from), quote!(#name))?
+        }
+        Shape::Named(fields) => {
+            let collect =
+                collect_fields(&vaerdi_name, fields, source, container.deny_unknown_fields)?;
+            let construct = construct_fields(fields);
+
+            quote!(
+                let #vaerdi_name::ValueRef::Map(__map) = from else {
+                    return Err(#vaerdi_name::ConvertError::invalid_type(#vaerdi_name::Type::Map, from.get_type()))
+                };
+
+                let mut __errors = #vaerdi_name::convert::ErrorCollector::new();
+
+                #collect
+
+                __errors.finish()?;
+
+                Ok(#name {
+                    #construct
+                })
+            )
+        }
+    };
+
+    let impl_generics = with_bound(
+        &impl_generics,
+        quote!(#vaerdi_name::convert::FromValueRef<#lifetime>),
+    );
+    let (imp, _, wh) = impl_generics.split_for_impl();
+    let (_, ty, _) = generics.split_for_impl();
+
+    Ok(quote!(
+        impl #imp #vaerdi_name::convert::FromValueRef<#lifetime> for #name #ty #wh {
+            type Error = #vaerdi_name::ConvertError;
+            fn from_value_ref(from: #vaerdi_name::ValueRef<#lifetime>) -> Result<Self, Self::Error> {
+                #body
+            }
+        }
+    ))
+}
+
+pub fn derive_struct_schema(
+    name: Ident,
+    generics: Generics,
+    attrs: &[Attribute],
+    item: DataStruct,
+) -> syn::Result<TokenStream> {
+    let container = ContainerAttrs::parse(attrs)?;
+    let vaerdi_name = format_ident!("vaerdi");
+    let shape = Shape::new(&item, &container)?;
+
+    let body = match &shape {
+        Shape::Unit => quote!(#vaerdi_name::schema::TypeSchema::Null),
+        Shape::NewType(field) => field_schema(&vaerdi_name, &field.field.ty, &field.attrs),
+        Shape::Tuple(fields) => tuple_schema(&vaerdi_name, fields),
+        Shape::Named(fields) => {
+            let schema = named_schema(&vaerdi_name, &name.to_string(), fields);
+            quote!(#schema.into())
+        }
+    };
+
+    let generics = with_bound(&generics, quote!(#vaerdi_name::schema::ValueSchema));
+    let (imp, ty, wh) = generics.split_for_impl();
+
+    Ok(quote!(
+        impl #imp #vaerdi_name::schema::ValueSchema for #name #ty #wh {
+            fn schema() -> #vaerdi_name::schema::TypeSchema {
+                #body
+            }
+        }
+
+        #vaerdi_name::__typed_impl!([#imp] #name #ty, [#wh]);
+    ))
+}
+
+pub fn derive_struct_validate(
+    name: Ident,
+    generics: Generics,
+    attrs: &[Attribute],
+    item: DataStruct,
+) -> syn::Result<TokenStream> {
+    let container = ContainerAttrs::parse(attrs)?;
+    let vaerdi_name = format_ident!("vaerdi");
+    let shape = Shape::new(&item, &container)?;
+
+    let checks = match &shape {
+        Shape::Unit => quote!(),
+        Shape::NewType(field) => field_validate(&vaerdi_name, &field.attrs, quote!(&self.0)),
+        Shape::Tuple(fields) => tuple_validate(&vaerdi_name, fields, |field| {
+            let index = Index::from(field.index);
+            quote!(&self.#index)
+        }),
+        Shape::Named(fields) => named_validate(&vaerdi_name, fields, |field| {
+            let ident = field.ident;
+            quote!(&self.#ident)
+        }),
+    };
+
+    let (imp, ty, wh) = generics.split_for_impl();
+
+    Ok(quote!(
+        impl #imp #vaerdi_name::validate::Validate for #name #ty #wh {
+            fn validate(&self) -> Result<(), #vaerdi_name::ConvertError> {
+                let mut __errors = #vaerdi_name::convert::ErrorCollector::new();
+                #checks
+                __errors.finish()
+            }
+        }
+    ))
+}
+
+fn unit_from_value(vaerdi_name: &Ident, name: &Ident) -> TokenStream {
+    quote!(
+        if from.is_null() {
+            Ok(#name)
+        } else {
+            Err(#vaerdi_name::ConvertError::unknown("expected null"))
+        }
+    )
+}
@@ -6,7 +6,7 @@ use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use core::convert::TryInto;
 use uuid::Uuid;
 
-use super::ConvertError;
+use super::{registry, ConvertError};
 use crate::{Number, Type, Value};
 
 macro_rules! convert_n {
@@ -57,7 +57,115 @@ pub(crate) fn parse_number(value: &str, to: Type) -> Result<Number, ConvertError
     }
 }
 
+/// The order in which the members of a union target are tried.
+///
+/// Integers come first, narrowest to widest, so a value lands in the smallest
+/// integer type that can hold it. Floats follow; `F32` is only picked when the
+/// conversion is lossless. The remaining types are tried from the most to the
+/// least specific, with the catch-all `String` and `Bytes` last.
+const UNION_PREFERENCE: [Type; 21] = [
+    Type::U8,
+    Type::I8,
+    Type::U16,
+    Type::I16,
+    Type::U32,
+    Type::I32,
+    Type::U64,
+    Type::I64,
+    Type::F32,
+    Type::F64,
+    Type::Bool,
+    Type::Char,
+    Type::Uuid,
+    Type::DateTime,
+    Type::Date,
+    Type::Time,
+    Type::Map,
+    Type::List,
+    Type::Json,
+    Type::String,
+    Type::Bytes,
+];
+
+/// Convert `value` to a union target and report which member was chosen.
+///
+/// A value that already belongs to the union is returned unchanged. Otherwise
+/// the members are tried in a fixed preference order: the narrowest integer
+/// that fits, then the lossless float, then the non-numeric types with
+/// `String` and `Bytes` last. The first member that converts wins.
+pub fn convert_union(value: Value, to: Type) -> Result<(Type, Value), ConvertError> {
+    let ty = value.get_type();
+    if !ty.is_empty() && to.contains(ty) {
+        return Ok((ty, value));
+    }
+
+    for member in UNION_PREFERENCE {
+        if !to.contains(member) {
+            continue;
+        }
+
+        let Ok(out) = convert_single(value.clone(), member) else {
+            continue;
+        };
+
+        if member == Type::F32 && !is_lossless_f32(&value, &out) {
+            continue;
+        }
+
+        return Ok((member, out));
+    }
+
+    Err(ConvertError::invalid_type(to, ty))
+}
+
+fn is_lossless_f32(from: &Value, to: &Value) -> bool {
+    let Value::Number(to) = to else {
+        return false;
+    };
+
+    match from {
+        Value::Number(from) => from.as_f64() == to.as_f64(),
+        Value::String(from) => from.parse::<f64>().ok() == Some(to.as_f64()),
+        _ => true,
+    }
+}
+
+/// Convert `value` to the type `to`.
+///
+/// Converters registered in the global [`registry`](super::registry) take
+/// precedence over the builtin conversions.
 pub fn convert(value: Value, to: Type) -> Result<Value, ConvertError> {
+    let ty = value.get_type();
+    if !ty.is_empty() && to.contains(ty) {
+        return Ok(value);
+    }
+
+    let value = match registry::try_convert(value, to) {
+        Ok(ret) => return ret,
+        Err(value) => value,
+    };
+
+    if to.is_union() {
+        return convert_union(value, to).map(|(_, value)| value);
+    }
+
+    convert_single(value, to)
+}
+
+/// Coerce an argument of a gerning call to the declared parameter type.
+///
+/// This is [`convert`] with the error mapped for gerning, so registered
+/// converters are used and a union parameter type picks its member the way
+/// [`convert_union`] does.
+#[cfg(feature = "gerning")]
+pub fn coerce_argument(
+    value: Value,
+    expected: Type,
+) -> Result<Value, gerning::arguments::ArgumentError<Value>> {
+    convert(value, expected).map_err(Into::into)
+}
+
+fn convert_single(value: Value, to: Type) -> Result<Value, ConvertError> {
     if value.get_type() == to {
         return Ok(value);
     }
@@ -92,7 +200,7 @@ pub fn convert(value: Value, to: Type) -> Result<Value, ConvertError> {
             Type::String => Value::String(c.to_string().into()),
             _ => {
                 if to.is_int() {
-                    Value::Number((c as u32).try_into()?)
+                    Value::Number(convert_number((c as u32).into(), to)?)
                 } else {
                     return Err(ConvertError::invalid_type(to, Type::Char));
                 }
@@ -164,3 +272,111 @@ pub fn convert(value: Value, to: Type) -> Result<Value, ConvertError> {
 
     Ok(out)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn union(value: impl Into<Value>, to: Type) -> (Type, Value) {
+        convert_union(value.into(), to).unwrap()
+    }
+
+    #[test]
+    fn members_are_kept() {
+        let to = Type::String | Type::Uuid;
+        assert_eq!(union("text", to), (Type::String, Value::from("text")));
+        assert_eq!(union(200u16, Type::int()), (Type::U16, Value::from(200u16)));
+        assert_eq!(
+            convert(Value::from(1.5f64), Type::number()).unwrap(),
+            Value::from(1.5f64)
+        );
+    }
+
+    #[test]
+    fn narrowest_integer() {
+        assert_eq!(union("200", Type::int()), (Type::U8, Value::from(200u8)));
+        assert_eq!(union("-1", Type::int()), (Type::I8, Value::from(-1i8)));
+        assert_eq!(
+            union(300i64, Type::U8 | Type::U16 | Type::U32),
+            (Type::U16, Value::from(300u16))
+        );
+        assert_eq!(
+            union(-40000i64, Type::I8 | Type::I16 | Type::I32),
+            (Type::I32, Value::from(-40000i32))
+        );
+        assert_eq!(
+            union("70000", Type::int()),
+            (Type::U32, Value::from(70000u32))
+        );
+        assert_eq!(
+            union(true, Type::I16 | Type::I64 | Type::String),
+            (Type::I16, Value::from(1i16))
+        );
+    }
+
+    #[test]
+    fn lossless_float() {
+        let to = Type::F32 | Type::String;
+        assert_eq!(union(1.5f64, to), (Type::F32, Value::from(1.5f32)));
+        assert_eq!(union(0.1f64, to), (Type::String, Value::from("0.1")));
+        assert_eq!(
+            union("0.25", Type::float()),
+            (Type::F32, Value::from(0.25f32))
+        );
+        assert_eq!(
+            union("0.1", Type::float()),
+            (Type::F64, Value::from(0.1f64))
+        );
+        assert_eq!(
+            union(100_000u32, Type::float()),
+            (Type::F64, Value::from(100_000.0f64))
+        );
+    }
+
+    #[test]
+    fn preference_order() {
+        let uuid = "6a2f41a3-c54c-fce8-32d2-0324e1c32e22";
+        assert_eq!(
+            union(uuid, Type::Uuid | Type::Bytes),
+            (Type::Uuid, Value::Uuid(uuid.parse().unwrap()))
+        );
+        assert_eq!(
+            union("12", Type::U8 | Type::Bytes),
+            (Type::U8, Value::from(12u8))
+        );
+        assert_eq!(
+            union("abc", Type::U8 | Type::Bytes),
+            (Type::Bytes, Value::Bytes(b"abc".to_vec().into()))
+        );
+        assert_eq!(
+            union(42u8, Type::Bool | Type::String),
+            (Type::Bool, Value::Bool(true))
+        );
+    }
+
+    #[test]
+    fn no_member_fits() {
+        let err = convert_union(Value::List(Default::default()), Type::int()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            ConvertError::invalid_type(Type::int(), Type::List).to_string()
+        );
+        assert!(convert(Value::from("abc"), Type::int()).is_err());
+    }
+
+    #[cfg(feature = "gerning")]
+    #[test]
+    fn gerning_arguments() {
+        use gerning::arguments::ArgumentError;
+
+        let to = Type::U8 | Type::Bool;
+        assert_eq!(coerce_argument("7".into(), to).unwrap(), Value::from(7u8));
+
+        match coerce_argument(Value::List(Default::default()), to) {
+            Err(ArgumentError::IvalidType { expected, found }) => {
+                assert_eq!((expected, found), (to, Type::List));
+            }
+            ret => panic!("expected an invalid type error, got {ret:?}"),
+        }
+    }
+}
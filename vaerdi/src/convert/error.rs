@@ -2,6 +2,7 @@ use crate::{number::TryFromNumberError, Type};
 use alloc::{
     boxed::Box,
     string::{String, ToString},
+    vec::Vec,
 };
 use avagarden::error::BoxError;
 use core::{
@@ -10,11 +11,47 @@ use core::{
     num::{ParseFloatError, ParseIntError},
 };
 
+/// A single segment of the location of an error inside a value
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Path {
+    Field(crate::String),
+    Index(usize),
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Path::Field(name) => f.write_str(name),
+            Path::Index(idx) => write!(f, "[{idx}]"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum ConvertErrorKind {
-    Type { expected: Type, found: Type },
-    UnknownVariant { name: String },
+    Type {
+        expected: Type,
+        found: Type,
+    },
+    UnknownVariant {
+        name: String,
+    },
+    MissingField {
+        name: String,
+    },
+    UnknownField {
+        name: String,
+    },
+    Length {
+        expected: usize,
+        found: usize,
+    },
+    /// The value converted but failed a validation rule
+    Validation {
+        message: String,
+    },
     Unknown(BoxError<'static>),
+    Multiple(Vec<ConvertError>),
     Infallible,
 }
 
@@ -24,7 +61,23 @@ impl fmt::Display for ConvertErrorKind {
             Self::Infallible => write!(f, "infallible"),
             Self::Type { expected, found } => write!(f, "expected: {expected}, found: {found}"),
             Self::UnknownVariant { name } => write!(f, "unknown variant: {name}"),
+            Self::MissingField { name } => write!(f, "missing field: {name}"),
+            Self::UnknownField { name } => write!(f, "unknown field: {name}"),
+            Self::Length { expected, found } => {
+                write!(f, "expected length: {expected}, found: {found}")
+            }
+            Self::Validation { message } => write!(f, "invalid value: {message}"),
             Self::Unknown(err) => write!(f, "{err}"),
+            Self::Multiple(errors) => {
+                write!(f, "{} errors: ", errors.len())?;
+                for (idx, err) in errors.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str("; ")?;
+                    }
+                    err.fmt(f)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -32,41 +85,115 @@ impl fmt::Display for ConvertErrorKind {
 #[derive(Debug)]
 pub struct ConvertError {
     kind: ConvertErrorKind,
-    #[allow(unused)]
+    path: Vec<Path>,
     context: Option<String>,
 }
 
 impl ConvertError {
-    pub fn invalid_type(expected: Type, found: Type) -> ConvertError {
+    fn new(kind: ConvertErrorKind) -> ConvertError {
         ConvertError {
-            kind: ConvertErrorKind::Type { expected, found },
+            kind,
+            path: Vec::new(),
             context: None,
         }
     }
 
+    pub fn invalid_type(expected: Type, found: Type) -> ConvertError {
+        ConvertError::new(ConvertErrorKind::Type { expected, found })
+    }
+
     pub fn unknown<S>(error: S) -> ConvertError
     where
         S: Into<BoxError<'static>>, // S: worm_shared::Error + Send + Sync + 'static,
     {
-        ConvertError {
-            kind: ConvertErrorKind::Unknown(error.into()),
-            context: None,
-        }
+        ConvertError::new(ConvertErrorKind::Unknown(error.into()))
     }
 
     pub fn unknown_variant(name: impl ToString) -> ConvertError {
-        ConvertError {
-            kind: ConvertErrorKind::UnknownVariant {
-                name: name.to_string(),
-            },
-            context: None,
-        }
+        ConvertError::new(ConvertErrorKind::UnknownVariant {
+            name: name.to_string(),
+        })
+    }
+
+    pub fn missing_field(name: impl ToString) -> ConvertError {
+        ConvertError::new(ConvertErrorKind::MissingField {
+            name: name.to_string(),
+        })
+    }
+
+    pub fn unknown_field(name: impl ToString) -> ConvertError {
+        ConvertError::new(ConvertErrorKind::UnknownField {
+            name: name.to_string(),
+        })
+    }
+
+    pub fn invalid_length(expected: usize, found: usize) -> ConvertError {
+        ConvertError::new(ConvertErrorKind::Length { expected, found })
+    }
+
+    pub fn validation(message: impl ToString) -> ConvertError {
+        ConvertError::new(ConvertErrorKind::Validation {
+            message: message.to_string(),
+        })
     }
 
     pub fn with_context(mut self, ctx: impl ToString) -> ConvertError {
         self.context = Some(ctx.to_string());
         self
     }
+
+    /// Mark the error as happening inside the field `name`
+    pub fn with_field(self, name: impl Into<crate::String>) -> ConvertError {
+        self.with_path([Path::Field(name.into())])
+    }
+
+    /// Mark the error as happening at index `idx` of a list
+    pub fn with_index(self, idx: usize) -> ConvertError {
+        self.with_path([Path::Index(idx)])
+    }
+
+    /// Prepend `path` to the location of the error.
+    /// Aggregated errors pass the path on to each of their errors.
+    pub fn with_path<I>(mut self, path: I) -> ConvertError
+    where
+        I: IntoIterator<Item = Path>,
+    {
+        let path = path.into_iter().collect::<Vec<_>>();
+        if path.is_empty() {
+            return self;
+        }
+
+        match &mut self.kind {
+            ConvertErrorKind::Multiple(errors) => {
+                for err in errors.iter_mut() {
+                    err.path.splice(0..0, path.iter().cloned());
+                }
+            }
+            _ => {
+                self.path.splice(0..0, path);
+            }
+        }
+
+        self
+    }
+
+    /// The location of the error, outermost segment first
+    pub fn path(&self) -> &[Path] {
+        &self.path
+    }
+
+    pub fn kind(&self) -> &ConvertErrorKind {
+        &self.kind
+    }
+
+    /// All the errors this error is made of.
+    /// For an error that is not aggregated this is the error itself.
+    pub fn errors(&self) -> &[ConvertError] {
+        match &self.kind {
+            ConvertErrorKind::Multiple(errors) => errors,
+            _ => core::slice::from_ref(self),
+        }
+    }
 }
 
 impl fmt::Display for ConvertError {
@@ -75,18 +202,92 @@ impl fmt::Display for ConvertError {
             write!(f, "{}: ", ctx)?;
         }
 
+        if !self.path.is_empty() {
+            for (idx, segment) in self.path.iter().enumerate() {
+                if idx > 0 && matches!(segment, Path::Field(_)) {
+                    f.write_str(".")?;
+                }
+                segment.fmt(f)?;
+            }
+            f.write_str(": ")?;
+        }
+
         self.kind.fmt(f)
     }
 }
 
+/// Collects errors instead of stopping at the first one.
+///
+/// Used by the derived `FromValue` implementations to report every failing
+/// field of a struct at once.
+#[derive(Debug, Default)]
+pub struct ErrorCollector {
+    errors: Vec<ConvertError>,
+}
+
+impl ErrorCollector {
+    pub fn new() -> ErrorCollector {
+        ErrorCollector::default()
+    }
+
+    pub fn push(&mut self, error: impl Into<ConvertError>) {
+        let error = error.into();
+        match error.kind {
+            ConvertErrorKind::Multiple(errors) => self.errors.extend(errors),
+            _ => self.errors.push(error),
+        }
+    }
+
+    /// Record the error of `result`, if any, as happening inside the field `name`
+    pub fn field<T, E>(&mut self, name: &str, result: Result<T, E>) -> Option<T>
+    where
+        E: Into<ConvertError>,
+    {
+        match result {
+            Ok(ret) => Some(ret),
+            Err(err) => {
+                self.push(err.into().with_field(name));
+                None
+            }
+        }
+    }
+
+    /// Record the error of `result`, if any, as happening at index `idx`
+    pub fn index<T, E>(&mut self, idx: usize, result: Result<T, E>) -> Option<T>
+    where
+        E: Into<ConvertError>,
+    {
+        match result {
+            Ok(ret) => Some(ret),
+            Err(err) => {
+                self.push(err.into().with_index(idx));
+                None
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn finish(mut self) -> Result<(), ConvertError> {
+        match self.errors.len() {
+            0 => Ok(()),
+            1 => Err(self.errors.remove(0)),
+            _ => Err(ConvertError::new(ConvertErrorKind::Multiple(self.errors))),
+        }
+    }
+}
+
 impl avagarden::error::Error for ConvertError {}
 
 impl From<Infallible> for ConvertError {
     fn from(_: Infallible) -> Self {
-        ConvertError {
-            kind: ConvertErrorKind::Infallible,
-            context: None,
-        }
+        ConvertError::new(ConvertErrorKind::Infallible)
     }
 }
 
@@ -95,15 +296,11 @@ impl From<ConvertError> for gerning::arguments::ArgumentError<crate::Value> {
     fn from(value: ConvertError) -> Self {
         match value.kind {
             ConvertErrorKind::Infallible => gerning::arguments::ArgumentError::Infallible,
-            ConvertErrorKind::Type { expected, found } => {
+            ConvertErrorKind::Type { expected, found } if value.path.is_empty() => {
                 gerning::arguments::ArgumentError::IvalidType { expected, found }
             }
-            ConvertErrorKind::Unknown(_err) => {
-                unimplemented!("cannot be represented as an argument error")
-            }
-            ConvertErrorKind::UnknownVariant { .. } => {
-                unimplemented!("cannot be represented as an argument error")
-            }
+            // The message keeps the path and, for `Multiple`, every error
+            _ => gerning::arguments::ArgumentError::Custom(value.to_string().into()),
         }
     }
 }
@@ -116,10 +313,7 @@ impl From<ConvertError> for ConvertErrorKind {
 
 impl From<TryFromNumberError> for ConvertError {
     fn from(value: TryFromNumberError) -> Self {
-        ConvertError {
-            kind: ConvertErrorKind::Unknown(Box::new(value)),
-            context: None,
-        }
+        ConvertError::new(ConvertErrorKind::Unknown(Box::new(value)))
     }
 }
 
@@ -134,3 +328,147 @@ impl From<ParseFloatError> for ConvertError {
         ConvertError::unknown(value.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec, vec::Vec};
+
+    use super::*;
+
+    fn paths(err: &ConvertError) -> Vec<String> {
+        err.errors().iter().map(|err| err.to_string()).collect()
+    }
+
+    #[test]
+    fn path_rendering() {
+        let err = ConvertError::missing_field("c")
+            .with_field("b")
+            .with_index(2)
+            .with_field("a");
+        assert_eq!(
+            err.path(),
+            [
+                Path::Field("a".into()),
+                Path::Index(2),
+                Path::Field("b".into())
+            ]
+        );
+        assert_eq!(err.to_string(), "a[2].b: missing field: c");
+
+        let err = ConvertError::invalid_type(Type::U8, Type::String)
+            .with_index(1)
+            .with_index(0);
+        assert_eq!(err.to_string(), "[0][1]: expected: u8, found: string");
+
+        let err = ConvertError::invalid_length(2, 3)
+            .with_field("pair")
+            .with_context("Point");
+        assert_eq!(err.to_string(), "Point: pair: expected length: 2, found: 3");
+
+        let err = ConvertError::unknown_variant("Blue").with_path([]);
+        assert!(err.path().is_empty());
+        assert_eq!(err.to_string(), "unknown variant: Blue");
+    }
+
+    #[test]
+    fn collector() {
+        let mut collector = ErrorCollector::new();
+        assert_eq!(collector.field("a", Ok::<_, ConvertError>(1)), Some(1));
+        assert!(collector.is_empty());
+        assert!(collector.finish().is_ok());
+
+        let mut collector = ErrorCollector::new();
+        assert_eq!(
+            collector.index(3, Err::<u8, _>(ConvertError::validation("too big"))),
+            None
+        );
+        let err = collector.finish().unwrap_err();
+        assert!(matches!(err.kind(), ConvertErrorKind::Validation { .. }));
+        assert_eq!(err.to_string(), "[3]: invalid value: too big");
+
+        let mut collector = ErrorCollector::new();
+        collector.field("a", Err::<(), _>(ConvertError::missing_field("x")));
+        collector.field("b", Err::<(), _>(ConvertError::unknown_field("y")));
+        let err = collector.finish().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "2 errors: a: missing field: x; b: unknown field: y"
+        );
+    }
+
+    #[test]
+    fn multiple_is_flattened() {
+        let mut inner = ErrorCollector::new();
+        inner.field("x", Err::<(), _>(ConvertError::missing_field("x")));
+        inner.index(1, Err::<(), _>(ConvertError::invalid_length(1, 2)));
+        let inner = inner.finish().unwrap_err();
+
+        // The path of an aggregated error goes to each of its errors
+        let inner = inner.with_field("point");
+        assert_eq!(
+            paths(&inner),
+            [
+                "point.x: missing field: x",
+                "point[1]: expected length: 1, found: 2"
+            ]
+        );
+
+        let mut outer = ErrorCollector::new();
+        outer.field("outer", Err::<(), _>(inner));
+        outer.push(ConvertError::unknown_field("z"));
+        assert_eq!(outer.len(), 3);
+
+        let err = outer.finish().unwrap_err();
+        assert_eq!(err.errors().len(), 3);
+        assert_eq!(
+            paths(&err),
+            [
+                "outer.point.x: missing field: x",
+                "outer.point[1]: expected length: 1, found: 2",
+                "unknown field: z"
+            ]
+        );
+        assert!(err
+            .errors()
+            .iter()
+            .all(|err| !matches!(err.kind(), ConvertErrorKind::Multiple(_))));
+    }
+
+    #[cfg(feature = "gerning")]
+    #[test]
+    fn gerning_mapping() {
+        use gerning::arguments::ArgumentError;
+
+        assert!(matches!(
+            ArgumentError::from(ConvertError::new(ConvertErrorKind::Infallible)),
+            ArgumentError::Infallible
+        ));
+
+        assert!(matches!(
+            ArgumentError::from(ConvertError::invalid_type(Type::U8, Type::Bool)),
+            ArgumentError::IvalidType {
+                expected: Type::U8,
+                found: Type::Bool
+            }
+        ));
+
+        let err = ConvertError::invalid_type(Type::U8, Type::Bool).with_field("a");
+        match ArgumentError::from(err) {
+            ArgumentError::Custom(message) => {
+                assert_eq!(message, "a: expected: u8, found: bool")
+            }
+            err => panic!("expected a custom error, got {err:?}"),
+        }
+
+        let mut collector = ErrorCollector::new();
+        collector.field("a", Err::<(), _>(ConvertError::missing_field("x")));
+        collector.field("b", Err::<(), _>(ConvertError::missing_field("y")));
+        match ArgumentError::from(collector.finish().unwrap_err()) {
+            ArgumentError::Custom(message) => assert_eq!(
+                message,
+                "2 errors: a: missing field: x; b: missing field: y"
+            ),
+            err => panic!("expected a custom error, got {err:?}"),
+        }
+    }
+}
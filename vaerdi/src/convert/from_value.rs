@@ -1,8 +1,22 @@
-use super::error::ConvertError;
+use super::{error::ConvertError, MapKey};
 use crate::{bytes::Bytes, kow::Kow, r#type, string::String, List, Map, Number, Type, Value};
-use alloc::{borrow::Cow, boxed::Box, rc::Rc, string::ToString, sync::Arc, vec::Vec};
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    rc::Rc,
+    string::ToString,
+    sync::Arc,
+    vec::Vec,
+};
 use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
-use core::convert::{Infallible, TryInto};
+use core::{
+    convert::{Infallible, TryInto},
+    hash::{BuildHasher, Hash},
+};
+use hashbrown::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::collections::{HashMap as StdHashMap, HashSet as StdHashSet};
 use uuid::Uuid;
 
 pub trait FromValue: Sized {
@@ -82,7 +96,6 @@ from_impl!(Time => NaiveTime, into_time, as_time, as_time_mut);
 from_impl!(DateTime => NaiveDateTime, into_datetime, as_datetime, as_datetime_mut);
 from_impl!(Date => NaiveDate, into_date, as_date, as_date_mut);
 from_impl!(Uuid => Uuid, into_uuid, as_uuid, as_uuid_mut);
-// from_impl!(JsonValue, into_json, as_json, as_json_mut);
 
 from_impl!(
     @integer
@@ -103,6 +116,14 @@ from_impl!(
     f64 => F64
 );
 
+impl FromValue for Number {
+    type Error = ConvertError;
+    fn from_value(from: Value) -> Result<Self, Self::Error> {
+        from.into_number()
+            .map_err(|err| ConvertError::invalid_type(Type::number(), err.get_type()))
+    }
+}
+
 impl FromValue for usize {
     type Error = ConvertError;
 
@@ -129,13 +150,135 @@ where
 
         let ret = list
             .into_iter()
-            .map(T::from_value)
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(Into::into)?;
+            .enumerate()
+            .map(|(idx, value)| {
+                T::from_value(value).map_err(|err| Into::<ConvertError>::into(err).with_index(idx))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
         Ok(ret)
     }
 }
 
+macro_rules! map_from_value {
+    ($($map: ident $(, $hasher: ident)? where $($bound: path),+);*) => {
+        $(
+            impl<K, V $(, $hasher)?> FromValue for $map<K, V $(, $hasher)?>
+            where
+                K: MapKey $(+ $bound)+,
+                V: FromValue,
+                $($hasher: BuildHasher + Default,)?
+            {
+                type Error = ConvertError;
+                fn from_value(value: Value) -> Result<Self, Self::Error> {
+                    let Value::Map(map) = value else {
+                        return Err(ConvertError::invalid_type(Type::Map, value.get_type()));
+                    };
+
+                    map.into_iter()
+                        .map(|(key, value)| {
+                            let k = K::from_key(&key).map_err(|err| err.with_field(key.clone()))?;
+                            let v = V::from_value(value)
+                                .map_err(|err| Into::<ConvertError>::into(err).with_field(key))?;
+                            Ok((k, v))
+                        })
+                        .collect()
+                }
+            }
+        )*
+    };
+}
+
+map_from_value!(HashMap, S where Eq, Hash; BTreeMap where Ord);
+
+#[cfg(feature = "std")]
+map_from_value!(StdHashMap, S where Eq, Hash);
+
+macro_rules! list_from_value {
+    ($($list: ident $(, $hasher: ident)? $(where $($bound: path),+)?);*) => {
+        $(
+            impl<T $(, $hasher)?> FromValue for $list<T $(, $hasher)?>
+            where
+                T: FromValue $($(+ $bound)+)?,
+                $($hasher: BuildHasher + Default,)?
+            {
+                type Error = ConvertError;
+                fn from_value(value: Value) -> Result<Self, Self::Error> {
+                    Ok(Vec::<T>::from_value(value)?.into_iter().collect())
+                }
+            }
+        )*
+    };
+}
+
+list_from_value!(HashSet, S where Eq, Hash; BTreeSet where Ord; VecDeque);
+
+#[cfg(feature = "std")]
+list_from_value!(StdHashSet, S where Eq, Hash);
+
+impl<T, const N: usize> FromValue for [T; N]
+where
+    T: FromValue,
+{
+    type Error = ConvertError;
+    fn from_value(value: Value) -> Result<Self, Self::Error> {
+        if let Value::List(list) = &value {
+            if list.len() != N {
+                return Err(ConvertError::invalid_length(N, list.len()));
+            }
+        }
+
+        let found = Vec::<T>::from_value(value)?;
+        match found.try_into() {
+            Ok(ret) => Ok(ret),
+            Err(found) => Err(ConvertError::invalid_length(N, found.len())),
+        }
+    }
+}
+
+macro_rules! tuple_from_value {
+    ($($len: literal => ($($idx: tt $name: ident),+)),*) => {
+        $(
+            impl<$($name),+> FromValue for ($($name,)+)
+            where
+                $($name: FromValue),+
+            {
+                type Error = ConvertError;
+                fn from_value(value: Value) -> Result<Self, Self::Error> {
+                    let Value::List(list) = value else {
+                        return Err(ConvertError::invalid_type(Type::List, value.get_type()));
+                    };
+
+                    if list.len() != $len {
+                        return Err(ConvertError::invalid_length($len, list.len()));
+                    }
+
+                    let mut iter = list.into_iter();
+
+                    Ok(($(
+                        $name::from_value(iter.next().unwrap())
+                            .map_err(|err| Into::<ConvertError>::into(err).with_index($idx))?,
+                    )+))
+                }
+            }
+        )*
+    };
+}
+
+tuple_from_value!(
+    1 => (0 A),
+    2 => (0 A, 1 B),
+    3 => (0 A, 1 B, 2 C),
+    4 => (0 A, 1 B, 2 C, 3 D),
+    5 => (0 A, 1 B, 2 C, 3 D, 4 E),
+    6 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F),
+    7 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G),
+    8 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H),
+    9 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I),
+    10 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J),
+    11 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K),
+    12 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K, 11 L)
+);
+
 impl FromValue for alloc::string::String {
     type Error = ConvertError;
     fn from_value(value: Value) -> Result<Self, Self::Error> {
@@ -275,6 +418,24 @@ where
     }
 }
 
+/// Values JSON has no type for are converted like `JsonValue::from` does
+#[cfg(feature = "json")]
+impl FromValue for crate::json::JsonValue {
+    type Error = Infallible;
+    fn from_value(value: Value) -> Result<Self, Self::Error> {
+        Ok(value.into())
+    }
+}
+
+/// Writes the value as JSON text, which it keeps parsed
+#[cfg(feature = "json")]
+impl FromValue for crate::json::RawJson {
+    type Error = Infallible;
+    fn from_value(value: Value) -> Result<Self, Self::Error> {
+        Ok(crate::json::RawJson::from_value(value))
+    }
+}
+
 impl FromValue for DateTime<FixedOffset> {
     type Error = ConvertError;
     fn from_value(value: Value) -> Result<Self, Self::Error> {
@@ -284,3 +445,140 @@ impl FromValue for DateTime<FixedOffset> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec};
+
+    use super::*;
+
+    fn round_trip<T>(value: T) -> T
+    where
+        T: FromValue<Error = ConvertError> + Into<Value>,
+    {
+        T::from_value(value.into()).unwrap()
+    }
+
+    fn error<T: FromValue<Error = ConvertError>>(value: impl Into<Value>) -> alloc::string::String {
+        T::from_value(value.into()).err().unwrap().to_string()
+    }
+
+    #[test]
+    fn maps() {
+        let map = BTreeMap::from([(1u8, "one".to_string()), (2, "two".to_string())]);
+        assert_eq!(
+            Value::from(&map),
+            Value::from_iter([
+                (String::from("1"), Value::from("one")),
+                (String::from("2"), Value::from("two"))
+            ])
+        );
+        assert_eq!(round_trip(map.clone()), map);
+
+        let map = HashMap::<String, i32>::from_iter([("a".into(), 1), ("b".into(), -2)]);
+        assert_eq!(round_trip(map.clone()), map);
+
+        let uuid = Uuid::from_u128(42);
+        let map = BTreeMap::from([(uuid, true)]);
+        assert_eq!(round_trip(map.clone()), map);
+
+        #[cfg(feature = "std")]
+        {
+            let map = StdHashMap::from([(-1i64, vec![1u8]), (7, vec![])]);
+            assert_eq!(round_trip(map.clone()), map);
+        }
+
+        let value = Value::from_iter([(String::from("x"), Value::from(1i32))]);
+        assert_eq!(
+            error::<BTreeMap<u8, i32>>(value),
+            "x: invalid key 'x': invalid digit found in string"
+        );
+
+        let value = Value::from_iter([(String::from("a"), Value::from("b"))]);
+        assert_eq!(
+            error::<BTreeMap<String, bool>>(value),
+            "a: expected: bool, found: string"
+        );
+        assert_eq!(
+            error::<BTreeMap<String, bool>>(vec![true]),
+            "expected: map, found: list"
+        );
+    }
+
+    #[test]
+    fn sets() {
+        let set = BTreeSet::from([3i32, 1, 2]);
+        assert_eq!(Value::from(&set), Value::from(vec![1i32, 2, 3]));
+        assert_eq!(round_trip(set.clone()), set);
+
+        let set = HashSet::<String>::from_iter(["a".into(), "b".into()]);
+        assert_eq!(round_trip(set.clone()), set);
+
+        #[cfg(feature = "std")]
+        {
+            let set = StdHashSet::from([1u16, 2]);
+            assert_eq!(round_trip(set.clone()), set);
+        }
+
+        // Duplicates collapse
+        assert_eq!(
+            BTreeSet::<u8>::from_value(vec![1u8, 1, 2].into()).unwrap(),
+            BTreeSet::from([1, 2])
+        );
+
+        let deque = VecDeque::from([1u8, 2, 3]);
+        assert_eq!(Value::from(&deque), Value::from(vec![1u8, 2, 3]));
+        assert_eq!(round_trip(deque.clone()), deque);
+
+        assert_eq!(
+            error::<VecDeque<u8>>(Value::from_iter([Value::from(1u8), Value::from("2")])),
+            "[1]: expected: u8, found: string"
+        );
+    }
+
+    #[test]
+    fn arrays() {
+        assert_eq!(Value::from([1u8, 2, 3]), Value::from(vec![1u8, 2, 3]));
+        assert_eq!(Value::from(&[true, false]), Value::from(vec![true, false]));
+        assert_eq!(round_trip([1u8, 2, 3]), [1, 2, 3]);
+        assert_eq!(round_trip::<[u8; 0]>([]), []);
+
+        assert_eq!(
+            error::<[u8; 3]>(vec![1u8, 2]),
+            "expected length: 3, found: 2"
+        );
+        assert_eq!(
+            error::<[u8; 2]>(Value::from_iter([Value::from(1u8), Value::from(true)])),
+            "[1]: expected: u8, found: bool"
+        );
+    }
+
+    #[test]
+    fn tuples() {
+        assert_eq!(round_trip((1u8,)), (1,));
+        assert_eq!(
+            Value::from((1u8, "a", true)),
+            Value::from_iter([Value::from(1u8), Value::from("a"), Value::from(true)])
+        );
+        assert_eq!(
+            round_trip((1u8, "a".to_string(), true)),
+            (1, "a".to_string(), true)
+        );
+
+        let pair = (1u8, "a".to_string());
+        assert_eq!(
+            <(u8, alloc::string::String)>::from_value(Value::from(&pair)).unwrap(),
+            pair
+        );
+
+        assert_eq!(
+            error::<(u8, bool)>(vec![1u8, 2, 3]),
+            "expected length: 2, found: 3"
+        );
+        assert_eq!(
+            error::<(u8, bool, u8)>(vec![1u8, 2, 3]),
+            "[1]: expected: bool, found: u8"
+        );
+        assert_eq!(error::<(u8,)>(1u8), "expected: list, found: u8");
+    }
+}
@@ -0,0 +1,252 @@
+use super::{ConvertError, FromValue};
+use crate::{bytes::Bytes, kow::Kow, List, Map, Number, Type, Value, ValueRef};
+use alloc::{boxed::Box, vec::Vec};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use core::convert::Infallible;
+use uuid::Uuid;
+
+/// Like [`FromValue`] but borrows from the value instead of consuming it
+pub trait FromValueRef<'a>: Sized {
+    type Error: Into<ConvertError>;
+    fn from_value_ref(value: ValueRef<'a>) -> Result<Self, Self::Error>;
+}
+
+impl<'a> FromValueRef<'a> for ValueRef<'a> {
+    type Error = Infallible;
+    fn from_value_ref(value: ValueRef<'a>) -> Result<Self, Self::Error> {
+        Ok(value)
+    }
+}
+
+macro_rules! borrow_impl {
+    ($($variant: ident => $ty: ty),*) => {
+        $(
+            impl<'a> FromValueRef<'a> for $ty {
+                type Error = ConvertError;
+                fn from_value_ref(value: ValueRef<'a>) -> Result<Self, Self::Error> {
+                    match value {
+                        ValueRef::$variant(v) => Ok(v),
+                        _ => Err(ConvertError::invalid_type(Type::$variant, value.get_type())),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+borrow_impl!(
+    String => &'a str,
+    Bytes => &'a Bytes,
+    Map => &'a Map,
+    List => &'a List
+);
+
+impl<'a> FromValueRef<'a> for Number {
+    type Error = ConvertError;
+    fn from_value_ref(value: ValueRef<'a>) -> Result<Self, Self::Error> {
+        match value {
+            ValueRef::Number(n) => Ok(n),
+            _ => Err(ConvertError::invalid_type(Type::number(), value.get_type())),
+        }
+    }
+}
+
+impl<'a> FromValueRef<'a> for &'a [u8] {
+    type Error = ConvertError;
+    fn from_value_ref(value: ValueRef<'a>) -> Result<Self, Self::Error> {
+        match value {
+            ValueRef::Bytes(v) => Ok(&**v),
+            _ => Err(ConvertError::invalid_type(Type::Bytes, value.get_type())),
+        }
+    }
+}
+
+impl<'a> FromValueRef<'a> for Kow<'a, str> {
+    type Error = ConvertError;
+    fn from_value_ref(value: ValueRef<'a>) -> Result<Self, Self::Error> {
+        <&'a str>::from_value_ref(value).map(Kow::Ref)
+    }
+}
+
+impl<'a> FromValueRef<'a> for Kow<'a, [u8]> {
+    type Error = ConvertError;
+    fn from_value_ref(value: ValueRef<'a>) -> Result<Self, Self::Error> {
+        <&'a [u8]>::from_value_ref(value).map(Kow::Ref)
+    }
+}
+
+// Bridges to `FromValue` for types where turning the `ValueRef` into an owned
+// `Value` is cheap or where an owned copy is what the caller asked for.
+macro_rules! bridge_impl {
+    ($($ty: ty),*) => {
+        $(
+            impl<'a> FromValueRef<'a> for $ty {
+                type Error = <$ty as FromValue>::Error;
+                fn from_value_ref(value: ValueRef<'a>) -> Result<Self, Self::Error> {
+                    <$ty as FromValue>::from_value(value.into())
+                }
+            }
+        )*
+    };
+}
+
+bridge_impl!(
+    bool,
+    u8,
+    i8,
+    u16,
+    i16,
+    u32,
+    i32,
+    u64,
+    i64,
+    usize,
+    f32,
+    f64,
+    NaiveDate,
+    NaiveDateTime,
+    NaiveTime,
+    Uuid,
+    crate::String,
+    alloc::string::String,
+    Value
+);
+
+#[cfg(feature = "json")]
+bridge_impl!(crate::json::JsonValue, crate::json::RawJson);
+
+impl<'a, T> FromValueRef<'a> for Option<T>
+where
+    T: FromValueRef<'a>,
+{
+    type Error = T::Error;
+    fn from_value_ref(value: ValueRef<'a>) -> Result<Self, Self::Error> {
+        if value.is_null() {
+            Ok(None)
+        } else {
+            T::from_value_ref(value).map(Option::Some)
+        }
+    }
+}
+
+impl<'a, T> FromValueRef<'a> for Box<T>
+where
+    T: FromValueRef<'a>,
+{
+    type Error = T::Error;
+    fn from_value_ref(value: ValueRef<'a>) -> Result<Self, Self::Error> {
+        Ok(Box::new(T::from_value_ref(value)?))
+    }
+}
+
+impl<'a, T> FromValueRef<'a> for Vec<T>
+where
+    T: FromValueRef<'a>,
+{
+    type Error = ConvertError;
+    fn from_value_ref(value: ValueRef<'a>) -> Result<Self, Self::Error> {
+        let ValueRef::List(list) = value else {
+            return Err(ConvertError::invalid_type(Type::List, value.get_type()));
+        };
+
+        list.iter()
+            .enumerate()
+            .map(|(idx, value)| {
+                T::from_value_ref(value.as_ref())
+                    .map_err(|err| Into::<ConvertError>::into(err).with_index(idx))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec};
+
+    use super::*;
+
+    #[test]
+    fn borrows() {
+        let value = Value::from("text");
+        let text = value.borrow_as::<&str>().unwrap();
+        assert_eq!(text, "text");
+        assert!(core::ptr::eq(text, &**value.as_string().unwrap()));
+        assert!(matches!(
+            value.borrow_as::<Kow<str>>(),
+            Ok(Kow::Ref("text"))
+        ));
+
+        let value = Value::Bytes(vec![1u8, 2].into());
+        assert_eq!(value.borrow_as::<&[u8]>().unwrap(), [1, 2]);
+        assert!(core::ptr::eq(
+            value.borrow_as::<&Bytes>().unwrap(),
+            value.as_bytes().unwrap()
+        ));
+        assert!(matches!(
+            value.borrow_as::<Kow<[u8]>>(),
+            Ok(Kow::Ref([1, 2]))
+        ));
+
+        let value = Value::from_iter([(crate::String::from("a"), Value::from(1u8))]);
+        assert!(core::ptr::eq(
+            value.borrow_as::<&Map>().unwrap(),
+            value.as_map().unwrap()
+        ));
+
+        let value = Value::from(vec![1u8, 2]);
+        assert_eq!(value.borrow_as::<&List>().unwrap().len(), 2);
+        assert_eq!(value.borrow_as::<Vec<u8>>().unwrap(), [1, 2]);
+    }
+
+    #[test]
+    fn copies() {
+        assert_eq!(
+            Value::from(7u8).borrow_as::<Number>().unwrap(),
+            Number::U8(7)
+        );
+        assert_eq!(Value::from(7u8).borrow_as::<u8>().unwrap(), 7);
+        assert!(Value::from(true).borrow_as::<bool>().unwrap());
+        assert_eq!(
+            Value::from("owned")
+                .borrow_as::<alloc::string::String>()
+                .unwrap(),
+            "owned"
+        );
+
+        let value = Value::from(vec!["a", "b"]);
+        assert_eq!(value.borrow_as::<Value>().unwrap(), value);
+        assert!(matches!(
+            value.borrow_as::<ValueRef>(),
+            Ok(ValueRef::List(_))
+        ));
+    }
+
+    #[test]
+    fn wrappers() {
+        assert_eq!(Value::Null.borrow_as::<Option<&str>>().unwrap(), None);
+        assert_eq!(
+            Value::from("a").borrow_as::<Option<&str>>().unwrap(),
+            Some("a")
+        );
+        assert_eq!(*Value::from("a").borrow_as::<Box<&str>>().unwrap(), "a");
+
+        let value = Value::from(vec!["a", "b"]);
+        assert_eq!(value.borrow_as::<Vec<&str>>().unwrap(), ["a", "b"]);
+    }
+
+    #[test]
+    fn errors() {
+        let err = Value::from(1u8).borrow_as::<&str>().unwrap_err();
+        assert_eq!(err.to_string(), "expected: string, found: u8");
+
+        let err = Value::from("a").borrow_as::<Number>().unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            super::super::ConvertErrorKind::Type { .. }
+        ));
+
+        let value = Value::from_iter([Value::from("a"), Value::from(1u8)]);
+        let err = value.borrow_as::<Vec<&str>>().unwrap_err();
+        assert_eq!(err.to_string(), "[1]: expected: string, found: u8");
+    }
+}
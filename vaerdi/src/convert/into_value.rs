@@ -1,16 +1,72 @@
+use super::{ConvertError, MapKey};
 #[cfg(feature = "gerning")]
 use crate::Type;
-use crate::{kow::Kow, number::Number, string::String, value_ref::ValueRef, List, Map, Value};
+use crate::{
+    bytes::Bytes, kow::Kow, number::Number, string::String, value_ref::ValueRef, List, Map, Value,
+};
 use alloc::{
     borrow::Cow,
     boxed::Box,
+    collections::{BTreeMap, BTreeSet, VecDeque},
     string::{String as StdString, ToString},
     sync::Arc,
     vec::Vec,
 };
 use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use core::{convert::Infallible, iter::FromIterator};
+use hashbrown::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::collections::{HashMap as StdHashMap, HashSet as StdHashSet};
+#[cfg(feature = "std")]
+use std::{
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+};
 use uuid::Uuid;
 
+/// Conversion into a `Value` that leaves `self` in place.
+///
+/// Implemented for every type with a `From<&T> for Value`, which the
+/// `IntoValue` derive generates next to the owned `From<T> for Value`.
+pub trait IntoValue {
+    fn to_value(&self) -> Value;
+}
+
+impl<T: ?Sized> IntoValue for T
+where
+    for<'a> &'a T: Into<Value>,
+{
+    fn to_value(&self) -> Value {
+        self.into()
+    }
+}
+
+/// Conversion into a `Value` for types that cannot always be represented,
+/// like a `u128` beyond the range of a `u64`.
+///
+/// Every type with `Into<Value>` converts without failing, and so do pairs,
+/// which have no owned `Into<Value>` because it would overlap with collecting
+/// `(String, Value)` items into a map.
+pub trait TryIntoValue {
+    type Error: Into<ConvertError>;
+    fn try_into_value(self) -> Result<Value, Self::Error>;
+}
+
+impl<T: Into<Value>> TryIntoValue for T {
+    type Error = Infallible;
+    fn try_into_value(self) -> Result<Value, Self::Error> {
+        Ok(self.into())
+    }
+}
+
+/// The owned conversion of a pair, which cannot have a `From<(A, B)> for Value`
+impl<A: Into<Value>, B: Into<Value>> TryIntoValue for (A, B) {
+    type Error = Infallible;
+    fn try_into_value(self) -> Result<Value, Self::Error> {
+        Ok(Value::List(List::from_iter([self.0.into(), self.1.into()])))
+    }
+}
+
 macro_rules! into_value {
     ($($ty: ty => $val: ident),*) => {
         $(
@@ -73,6 +129,8 @@ into_value!(
     bool => Bool,
     List => List,
     Map => Map,
+    Bytes => Bytes,
+    char => Char,
     // HashMap<String, Value> => Map,
     NaiveDate => Date,
     NaiveDateTime => DateTime,
@@ -112,11 +170,189 @@ impl<T: Into<Value>> From<Vec<T>> for Value {
     }
 }
 
-// impl<K: ToString, V: Into<Value>> From<HashMap<K, V>> for Value {
-//     fn from(value: HashMap<K, V>) -> Self {
-//         Value::List(value.into())
-//     }
-// }
+macro_rules! map_into_value {
+    ($($map: ident $(, $hasher: ident)?);*) => {
+        $(
+            impl<K, V $(, $hasher)?> From<$map<K, V $(, $hasher)?>> for Value
+            where
+                K: MapKey,
+                V: Into<Value>,
+            {
+                fn from(value: $map<K, V $(, $hasher)?>) -> Self {
+                    Value::Map(
+                        value
+                            .into_iter()
+                            .map(|(k, v)| (k.to_key(), v.into()))
+                            .collect(),
+                    )
+                }
+            }
+
+            impl<'a, K, V $(, $hasher)?> From<&'a $map<K, V $(, $hasher)?>> for Value
+            where
+                K: MapKey,
+                V: IntoValue,
+            {
+                fn from(value: &'a $map<K, V $(, $hasher)?>) -> Self {
+                    Value::Map(
+                        value
+                            .iter()
+                            .map(|(k, v)| (k.to_key(), v.to_value()))
+                            .collect(),
+                    )
+                }
+            }
+
+            #[cfg(feature = "gerning")]
+            impl<K, V $(, $hasher)?> gerning::Typed<Value> for $map<K, V $(, $hasher)?> {
+                fn get_type() -> Type {
+                    Type::Map
+                }
+            }
+        )*
+    };
+}
+
+map_into_value!(HashMap, S; BTreeMap);
+
+#[cfg(feature = "std")]
+map_into_value!(StdHashMap, S);
+
+macro_rules! list_into_value {
+    ($($list: ident $(, $hasher: ident)?);*) => {
+        $(
+            impl<T $(, $hasher)?> From<$list<T $(, $hasher)?>> for Value
+            where
+                T: Into<Value>,
+            {
+                fn from(value: $list<T $(, $hasher)?>) -> Self {
+                    Value::List(value.into_iter().map(Into::into).collect())
+                }
+            }
+
+            impl<'a, T $(, $hasher)?> From<&'a $list<T $(, $hasher)?>> for Value
+            where
+                T: IntoValue,
+            {
+                fn from(value: &'a $list<T $(, $hasher)?>) -> Self {
+                    Value::List(value.iter().map(T::to_value).collect())
+                }
+            }
+
+            #[cfg(feature = "gerning")]
+            impl<T $(, $hasher)?> gerning::Typed<Value> for $list<T $(, $hasher)?> {
+                fn get_type() -> Type {
+                    Type::List
+                }
+            }
+        )*
+    };
+}
+
+list_into_value!(HashSet, S; BTreeSet; VecDeque);
+
+#[cfg(feature = "std")]
+list_into_value!(StdHashSet, S);
+
+impl<T, const N: usize> From<[T; N]> for Value
+where
+    T: Into<Value>,
+{
+    fn from(value: [T; N]) -> Self {
+        Value::List(value.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<'a, T, const N: usize> From<&'a [T; N]> for Value
+where
+    T: IntoValue,
+{
+    fn from(value: &'a [T; N]) -> Self {
+        value.as_slice().into()
+    }
+}
+
+impl<'a, T> From<&'a [T]> for Value
+where
+    T: IntoValue,
+{
+    fn from(value: &'a [T]) -> Self {
+        Value::List(value.iter().map(T::to_value).collect())
+    }
+}
+
+impl<'a, T> From<&'a Vec<T>> for Value
+where
+    T: IntoValue,
+{
+    fn from(value: &'a Vec<T>) -> Self {
+        value.as_slice().into()
+    }
+}
+
+macro_rules! tuple_into_value {
+    ($(($($name: ident),+)),*) => {
+        $(
+            impl<$($name),+> From<($($name,)+)> for Value
+            where
+                $($name: Into<Value>),+
+            {
+                #[allow(non_snake_case)]
+                fn from(($($name,)+): ($($name,)+)) -> Self {
+                    Value::List(List::from_iter([$($name.into()),+]))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! tuple_ref_into_value {
+    ($(($($name: ident),+)),*) => {
+        $(
+            impl<'a, $($name),+> From<&'a ($($name,)+)> for Value
+            where
+                $($name: IntoValue),+
+            {
+                #[allow(non_snake_case)]
+                fn from(($($name,)+): &'a ($($name,)+)) -> Self {
+                    Value::List(List::from_iter([$($name.to_value()),+]))
+                }
+            }
+        )*
+    };
+}
+
+tuple_ref_into_value!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+    (A, B, C, D, E, F, G, H, I),
+    (A, B, C, D, E, F, G, H, I, J),
+    (A, B, C, D, E, F, G, H, I, J, K),
+    (A, B, C, D, E, F, G, H, I, J, K, L)
+);
+
+// Pairs are left out. With `From<(A, B)>` the `FromIterator` impls of `Value`
+// would overlap, and collecting `(String, Value)` into a map must keep working.
+// They convert with `TryIntoValue` or by reference instead.
+tuple_into_value!(
+    (A),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+    (A, B, C, D, E, F, G, H, I),
+    (A, B, C, D, E, F, G, H, I, J),
+    (A, B, C, D, E, F, G, H, I, J, K),
+    (A, B, C, D, E, F, G, H, I, J, K, L)
+);
 
 impl<V: Into<Value>> From<Option<V>> for Value {
     fn from(value: Option<V>) -> Self {
@@ -127,6 +363,45 @@ impl<V: Into<Value>> From<Option<V>> for Value {
     }
 }
 
+impl<'a, V> From<&'a Option<V>> for Value
+where
+    V: IntoValue,
+{
+    fn from(value: &'a Option<V>) -> Self {
+        match value {
+            Some(value) => value.to_value(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl<'a, 'b, T> From<&'a &'b T> for Value
+where
+    T: IntoValue + ?Sized,
+{
+    fn from(value: &'a &'b T) -> Self {
+        (**value).to_value()
+    }
+}
+
+impl<'a> From<&'a Value> for Value {
+    fn from(value: &'a Value) -> Self {
+        value.clone()
+    }
+}
+
+impl<'a, 'b> From<&'a Cow<'b, str>> for Value {
+    fn from(value: &'a Cow<'b, str>) -> Self {
+        Value::String((**value).into())
+    }
+}
+
+impl<'a, 'b> From<&'a Kow<'b, str>> for Value {
+    fn from(value: &'a Kow<'b, str>) -> Self {
+        Value::String((**value).into())
+    }
+}
+
 // impl From<Value> for Option<Value> {
 //     fn from(value: Value) -> Self {
 //         if value.is_null() {
@@ -185,6 +460,15 @@ where
     }
 }
 
+impl<'a, T> From<&'a Box<T>> for Value
+where
+    T: IntoValue + ?Sized,
+{
+    fn from(value: &'a Box<T>) -> Self {
+        (**value).to_value()
+    }
+}
+
 impl<T> From<Box<[T]>> for Value
 where
     T: Into<Value>,
@@ -205,6 +489,15 @@ where
     }
 }
 
+impl<'a, T> From<&'a Arc<T>> for Value
+where
+    T: IntoValue + ?Sized,
+{
+    fn from(value: &'a Arc<T>) -> Self {
+        (**value).to_value()
+    }
+}
+
 impl<T> From<Arc<[T]>> for Value
 where
     T: Into<Value> + Clone,
@@ -223,3 +516,152 @@ where
         value.to_utc().into()
     }
 }
+
+impl<'a, T> From<&'a DateTime<T>> for Value
+where
+    T: TimeZone,
+{
+    fn from(value: &'a DateTime<T>) -> Self {
+        value.to_utc().into()
+    }
+}
+
+macro_rules! try_into_value {
+    ($($ty: ty => $target: ty),*) => {
+        $(
+            impl TryIntoValue for $ty {
+                type Error = ConvertError;
+                fn try_into_value(self) -> Result<Value, Self::Error> {
+                    match <$target>::try_from(self) {
+                        Ok(value) => Ok(value.into()),
+                        Err(_) => Err(ConvertError::unknown(alloc::format!(
+                            "{self} does not fit in a {}",
+                            stringify!($target)
+                        ))),
+                    }
+                }
+            }
+
+            impl TryIntoValue for &$ty {
+                type Error = ConvertError;
+                fn try_into_value(self) -> Result<Value, Self::Error> {
+                    (*self).try_into_value()
+                }
+            }
+        )*
+    };
+}
+
+try_into_value!(u128 => u64, isize => i64);
+
+impl TryIntoValue for i128 {
+    type Error = ConvertError;
+    fn try_into_value(self) -> Result<Value, Self::Error> {
+        match i64::try_from(self) {
+            Ok(value) => Ok(value.into()),
+            Err(_) => match u64::try_from(self) {
+                Ok(value) => Ok(value.into()),
+                Err(_) => Err(ConvertError::unknown(alloc::format!(
+                    "{self} does not fit in a 64 bit number"
+                ))),
+            },
+        }
+    }
+}
+
+impl TryIntoValue for &i128 {
+    type Error = ConvertError;
+    fn try_into_value(self) -> Result<Value, Self::Error> {
+        (*self).try_into_value()
+    }
+}
+
+#[cfg(feature = "std")]
+macro_rules! try_os_into_value {
+    ($($ty: ty),*) => {
+        $(
+            impl TryIntoValue for &$ty {
+                type Error = ConvertError;
+                fn try_into_value(self) -> Result<Value, Self::Error> {
+                    match self.to_str() {
+                        Some(value) => Ok(value.into()),
+                        None => Err(ConvertError::unknown(alloc::format!(
+                            "{self:?} is not valid UTF-8"
+                        ))),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "std")]
+try_os_into_value!(Path, PathBuf, OsStr, OsString);
+
+#[cfg(feature = "std")]
+impl TryIntoValue for PathBuf {
+    type Error = ConvertError;
+    fn try_into_value(self) -> Result<Value, Self::Error> {
+        self.as_path().try_into_value()
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryIntoValue for OsString {
+    type Error = ConvertError;
+    fn try_into_value(self) -> Result<Value, Self::Error> {
+        self.as_os_str().try_into_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec};
+
+    use super::*;
+
+    #[test]
+    fn pairs() {
+        let expected = Value::from_iter([Value::from(1u8), Value::from("a")]);
+        assert_eq!((1u8, "a").try_into_value().unwrap(), expected);
+        assert_eq!(Value::from(&(1u8, "a")), expected);
+        assert_eq!((1u8, "a").to_value(), expected);
+
+        // Collecting pairs still builds a map
+        let map = Value::from_iter([(String::from("a"), Value::from(1u8))]);
+        assert!(map.is_map());
+    }
+
+    #[test]
+    fn wide_integers() {
+        assert_eq!(5u128.try_into_value().unwrap(), Value::from(5u64));
+        assert_eq!(
+            u128::MAX.try_into_value().unwrap_err().to_string(),
+            "340282366920938463463374607431768211455 does not fit in a u64"
+        );
+        assert_eq!((-5i128).try_into_value().unwrap(), Value::from(-5i64));
+        assert_eq!(
+            (u64::MAX as i128).try_into_value().unwrap(),
+            Value::from(u64::MAX)
+        );
+        assert!(i128::MIN.try_into_value().is_err());
+        assert_eq!((&-1isize).try_into_value().unwrap(), Value::from(-1i64));
+    }
+
+    #[test]
+    fn collections() {
+        assert_eq!(
+            Value::from(vec![(1u8, 2u8, 3u8)]),
+            Value::from_iter([Value::from(vec![1u8, 2, 3])])
+        );
+        assert_eq!(Value::from(Some(vec![1u8])), Value::from(vec![1u8]));
+        assert_eq!(Value::from(None::<u8>), Value::Null);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn paths() {
+        let path = std::path::PathBuf::from("a/b");
+        assert_eq!(path.try_into_value().unwrap(), Value::from("a/b"));
+    }
+}
@@ -0,0 +1,92 @@
+use super::ConvertError;
+use crate::String;
+use alloc::string::ToString;
+use uuid::Uuid;
+
+/// Conversion between map keys and the string keys of a [`Map`](crate::Map).
+///
+/// Works like `ToString`/`FromStr` and is used by the map impls of
+/// `FromValue` and `From<_> for Value`.
+pub trait MapKey: Sized {
+    fn to_key(&self) -> String;
+    fn from_key(key: &str) -> Result<Self, ConvertError>;
+}
+
+impl MapKey for String {
+    fn to_key(&self) -> String {
+        self.clone()
+    }
+
+    fn from_key(key: &str) -> Result<Self, ConvertError> {
+        Ok(key.into())
+    }
+}
+
+impl MapKey for alloc::string::String {
+    fn to_key(&self) -> String {
+        self.into()
+    }
+
+    fn from_key(key: &str) -> Result<Self, ConvertError> {
+        Ok(key.into())
+    }
+}
+
+macro_rules! key_impl {
+    ($($ty: ty),*) => {
+        $(
+            impl MapKey for $ty {
+                fn to_key(&self) -> String {
+                    self.to_string().into()
+                }
+
+                fn from_key(key: &str) -> Result<Self, ConvertError> {
+                    key.parse()
+                        .map_err(|err| ConvertError::unknown(crate::format!("invalid key '{key}': {err}")))
+                }
+            }
+        )*
+    };
+}
+
+key_impl!(bool, char, u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, Uuid);
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn keys() {
+        assert_eq!(42u8.to_key(), "42");
+        assert_eq!(u8::from_key("42").unwrap(), 42);
+        assert_eq!(
+            i128::from_key("-170141183460469231731687303715884105728").unwrap(),
+            i128::MIN
+        );
+        assert!(bool::from_key("true").unwrap());
+        assert_eq!('x'.to_key(), "x");
+        assert_eq!(char::from_key("x").unwrap(), 'x');
+
+        let uuid = Uuid::from_u128(1);
+        assert_eq!(Uuid::from_key(&uuid.to_key()).unwrap(), uuid);
+
+        assert_eq!(String::from_key("a b").unwrap(), "a b");
+        assert_eq!(alloc::string::String::from_key("key").unwrap(), "key");
+    }
+
+    #[test]
+    fn invalid_keys() {
+        assert_eq!(
+            u8::from_key("256").unwrap_err().to_string(),
+            "invalid key '256': number too large to fit in target type"
+        );
+        assert_eq!(
+            bool::from_key("yes").unwrap_err().to_string(),
+            "invalid key 'yes': provided string was not `true` or `false`"
+        );
+        assert!(char::from_key("xy").is_err());
+        assert!(Uuid::from_key("not-a-uuid").is_err());
+    }
+}
@@ -1,10 +1,20 @@
 mod convt;
 mod error;
 mod from_value;
+mod from_value_ref;
 mod into_value;
+mod map_key;
+pub mod registry;
 
 pub use self::{
-    convt::convert,
-    error::{ConvertError, ConvertErrorKind},
+    convt::{convert, convert_union},
+    error::{ConvertError, ConvertErrorKind, ErrorCollector, Path},
     from_value::FromValue,
+    from_value_ref::FromValueRef,
+    into_value::{IntoValue, TryIntoValue},
+    map_key::MapKey,
+    registry::{ConvertTarget, ConverterRegistry},
 };
+
+#[cfg(feature = "gerning")]
+pub use self::convt::coerce_argument;
@@ -0,0 +1,411 @@
+use alloc::{collections::VecDeque, string::ToString, sync::Arc, vec, vec::Vec};
+use avagarden::sync::{Lazy, RwLock};
+use core::fmt;
+
+use super::ConvertError;
+use crate::{String, Type, Value};
+
+type Converter = Arc<dyn Fn(Value) -> Result<Value, ConvertError> + Send + Sync>;
+
+/// The source or target of a registered converter
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ConvertTarget {
+    Type(Type),
+    Named(String),
+}
+
+impl ConvertTarget {
+    /// Returns true if a value at `self` is also at `other`
+    fn satisfies(&self, other: &ConvertTarget) -> bool {
+        match (self, other) {
+            (ConvertTarget::Type(ty), ConvertTarget::Type(other)) => {
+                !ty.is_empty() && other.contains(*ty)
+            }
+            (ConvertTarget::Named(name), ConvertTarget::Named(other)) => name == other,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ConvertTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvertTarget::Type(ty) => ty.fmt(f),
+            ConvertTarget::Named(name) => name.fmt(f),
+        }
+    }
+}
+
+impl From<Type> for ConvertTarget {
+    fn from(value: Type) -> Self {
+        ConvertTarget::Type(value)
+    }
+}
+
+impl From<String> for ConvertTarget {
+    fn from(value: String) -> Self {
+        ConvertTarget::Named(value)
+    }
+}
+
+impl<'a> From<&'a str> for ConvertTarget {
+    fn from(value: &'a str) -> Self {
+        ConvertTarget::Named(value.into())
+    }
+}
+
+impl From<alloc::string::String> for ConvertTarget {
+    fn from(value: alloc::string::String) -> Self {
+        ConvertTarget::Named(value.into())
+    }
+}
+
+/// User defined conversions between types and named targets.
+///
+/// Converters are chained: with `A -> B` and `B -> C` registered, a value of
+/// type `A` can be converted to `C`. The chain with the fewest steps is used.
+#[derive(Clone, Default)]
+pub struct ConverterRegistry {
+    converters: Vec<(ConvertTarget, ConvertTarget, Converter)>,
+}
+
+impl ConverterRegistry {
+    pub fn new() -> ConverterRegistry {
+        ConverterRegistry::default()
+    }
+
+    pub fn register<F>(
+        &mut self,
+        from: impl Into<ConvertTarget>,
+        to: impl Into<ConvertTarget>,
+        converter: F,
+    ) -> &mut Self
+    where
+        F: Fn(Value) -> Result<Value, ConvertError> + Send + Sync + 'static,
+    {
+        self.converters
+            .push((from.into(), to.into(), Arc::new(converter)));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.converters.is_empty()
+    }
+
+    pub fn can_convert(
+        &self,
+        from: impl Into<ConvertTarget>,
+        to: impl Into<ConvertTarget>,
+    ) -> bool {
+        let from = from.into();
+        let to = to.into();
+        from.satisfies(&to) || self.find_path(&from, &to).is_some()
+    }
+
+    /// Convert `value` using the registered converters only
+    pub fn convert(
+        &self,
+        value: Value,
+        to: impl Into<ConvertTarget>,
+    ) -> Result<Value, ConvertError> {
+        let from = ConvertTarget::Type(value.get_type());
+        let to = to.into();
+
+        if from.satisfies(&to) {
+            return Ok(value);
+        }
+
+        match self.find_path(&from, &to) {
+            Some(path) => run(path, value),
+            None => Err(ConvertError::unknown(crate::format!(
+                "no converter from {from} to {to}"
+            ))),
+        }
+    }
+
+    /// Breadth first search for the shortest chain of converters from `from` to `to`
+    fn find_path(&self, from: &ConvertTarget, to: &ConvertTarget) -> Option<Vec<Converter>> {
+        let mut parents: Vec<Option<Option<usize>>> = vec![None; self.converters.len()];
+        let mut queue = VecDeque::new();
+
+        for (idx, (source, _, _)) in self.converters.iter().enumerate() {
+            if from.satisfies(source) {
+                parents[idx] = Some(None);
+                queue.push_back(idx);
+            }
+        }
+
+        while let Some(idx) = queue.pop_front() {
+            let (_, target, _) = &self.converters[idx];
+
+            if target.satisfies(to) {
+                let mut path = Vec::new();
+                let mut current = Some(idx);
+                while let Some(idx) = current {
+                    path.push(self.converters[idx].2.clone());
+                    current = parents[idx].flatten();
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for (next, (source, _, _)) in self.converters.iter().enumerate() {
+                if parents[next].is_none() && target.satisfies(source) {
+                    parents[next] = Some(Some(idx));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(feature = "gerning")]
+impl ConverterRegistry {
+    /// Coerce an argument of a gerning call with this registry.
+    /// Falls back to [`coerce_argument`](super::coerce_argument) when the registry has no chain.
+    pub fn coerce_argument(
+        &self,
+        value: Value,
+        expected: Type,
+    ) -> Result<Value, gerning::arguments::ArgumentError<Value>> {
+        let from = ConvertTarget::Type(value.get_type());
+        let to = ConvertTarget::Type(expected);
+
+        if !from.satisfies(&to) {
+            if let Some(path) = self.find_path(&from, &to) {
+                return run(path, value).map_err(Into::into);
+            }
+        }
+
+        super::coerce_argument(value, expected)
+    }
+}
+
+impl fmt::Debug for ConverterRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.converters
+                    .iter()
+                    .map(|(from, to, _)| (from.to_string(), to.to_string())),
+            )
+            .finish()
+    }
+}
+
+fn run(path: Vec<Converter>, mut value: Value) -> Result<Value, ConvertError> {
+    for converter in path {
+        value = converter(value)?;
+    }
+    Ok(value)
+}
+
+static REGISTRY: Lazy<RwLock<ConverterRegistry>> =
+    Lazy::new(|| RwLock::new(ConverterRegistry::default()));
+
+/// Register a converter in the global registry.
+/// The global registry is consulted by [`convert`](super::convert) before the builtin conversions.
+pub fn register<F>(from: impl Into<ConvertTarget>, to: impl Into<ConvertTarget>, converter: F)
+where
+    F: Fn(Value) -> Result<Value, ConvertError> + Send + Sync + 'static,
+{
+    REGISTRY.write().register(from, to, converter);
+}
+
+/// Convert `value` to a type or a named target using the global registry.
+/// Conversions to a type fall back to the builtin conversions.
+pub fn convert_to(value: Value, to: impl Into<ConvertTarget>) -> Result<Value, ConvertError> {
+    match to.into() {
+        ConvertTarget::Type(ty) => super::convert(value, ty),
+        to => {
+            let from = ConvertTarget::Type(value.get_type());
+            if from.satisfies(&to) {
+                return Ok(value);
+            }
+
+            match lookup(&from, &to) {
+                Some(path) => run(path, value),
+                None => Err(ConvertError::unknown(crate::format!(
+                    "no converter from {from} to {to}"
+                ))),
+            }
+        }
+    }
+}
+
+/// Convert `value` with the global registry if it has a chain for the conversion
+pub(crate) fn try_convert(value: Value, to: Type) -> Result<Result<Value, ConvertError>, Value> {
+    match lookup(
+        &ConvertTarget::Type(value.get_type()),
+        &ConvertTarget::Type(to),
+    ) {
+        Some(path) => Ok(run(path, value)),
+        None => Err(value),
+    }
+}
+
+// The converters are cloned out of the registry so the lock is not held
+// while they run, converters are free to call `convert` themselves.
+fn lookup(from: &ConvertTarget, to: &ConvertTarget) -> Option<Vec<Converter>> {
+    let registry = REGISTRY.read();
+    if registry.is_empty() {
+        return None;
+    }
+    registry.find_path(from, to)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, string::ToString};
+
+    use super::*;
+
+    fn registry() -> ConverterRegistry {
+        let mut registry = ConverterRegistry::new();
+        registry
+            .register(Type::String, "email", |value| match value.as_string() {
+                Some(email) if email.contains('@') => Ok(value),
+                _ => Err(ConvertError::validation("not an email")),
+            })
+            .register("email", "domain", |value| {
+                let email = value.as_string().unwrap();
+                Ok(Value::from(email.split_once('@').unwrap().1))
+            })
+            .register("domain", Type::Bytes, |value| {
+                Ok(Value::Bytes(value.as_string().unwrap().as_bytes().into()))
+            });
+        registry
+    }
+
+    #[test]
+    fn chaining() {
+        let registry = registry();
+        assert_eq!(
+            registry.convert("me@example.com".into(), "domain").unwrap(),
+            Value::from("example.com")
+        );
+        assert_eq!(
+            registry
+                .convert("me@example.com".into(), Type::Bytes)
+                .unwrap(),
+            Value::Bytes(b"example.com".to_vec().into())
+        );
+
+        let err = registry.convert("nobody".into(), "domain").unwrap_err();
+        assert_eq!(err.to_string(), "invalid value: not an email");
+
+        let err = registry.convert(Value::Bool(true), "domain").unwrap_err();
+        assert_eq!(err.to_string(), "no converter from bool to domain");
+    }
+
+    #[test]
+    fn shortest_path() {
+        let mut registry = registry();
+        assert_eq!(
+            registry
+                .find_path(&Type::String.into(), &Type::Bytes.into())
+                .map(|path| path.len()),
+            Some(3)
+        );
+
+        registry.register(Type::String, "domain", |value| {
+            Ok(Value::from(format!(
+                "direct {}",
+                value.as_string().unwrap()
+            )))
+        });
+        assert_eq!(
+            registry
+                .find_path(&Type::String.into(), &Type::Bytes.into())
+                .map(|path| path.len()),
+            Some(2)
+        );
+        assert_eq!(
+            registry.convert("a@b".into(), "domain").unwrap(),
+            Value::from("direct a@b")
+        );
+
+        assert!(registry
+            .find_path(&"domain".into(), &"email".into())
+            .is_none());
+    }
+
+    #[test]
+    fn can_convert() {
+        let mut registry = registry();
+        assert!(registry.can_convert(Type::String, "email"));
+        assert!(registry.can_convert(Type::String, Type::Bytes));
+        assert!(registry.can_convert("email", "domain"));
+        assert!(!registry.can_convert("domain", "email"));
+        assert!(!registry.can_convert(Type::U8, "email"));
+
+        // A type already satisfies a union containing it
+        assert!(registry.can_convert(Type::U8, Type::int()));
+        assert!(registry.can_convert("email", "email"));
+
+        // Sources that are unions accept each of their members
+        registry.register(Type::int(), "cents", Ok);
+        assert!(registry.can_convert(Type::U8, "cents"));
+        assert!(registry.can_convert(Type::I64, "cents"));
+        assert!(!registry.can_convert(Type::F64, "cents"));
+    }
+
+    #[test]
+    fn global_registry() {
+        register(Type::String, "test-upper", |value| {
+            Ok(Value::from(value.as_string().unwrap().to_uppercase()))
+        });
+        register("test-upper", "test-exclaim", |value| {
+            Ok(Value::from(format!("{}!", value.as_string().unwrap())))
+        });
+
+        assert_eq!(
+            Value::from("hi").convert_to("test-exclaim").unwrap(),
+            Value::from("HI!")
+        );
+        assert!(Value::from(1u8).convert_to("test-exclaim").is_err());
+
+        // Types go through the builtin conversions as before
+        assert_eq!(
+            Value::from("12").convert_to(Type::U8).unwrap(),
+            Value::from(12u8)
+        );
+
+        // and registered converters take precedence over them
+        register(Type::Bool, Type::Date, |value| {
+            let year = if value.as_bool() == Some(&true) {
+                2000
+            } else {
+                1970
+            };
+            Ok(Value::Date(
+                chrono::NaiveDate::from_ymd_opt(year, 1, 1).unwrap(),
+            ))
+        });
+        assert_eq!(
+            super::super::convert(Value::Bool(true), Type::Date).unwrap(),
+            Value::Date(chrono::NaiveDate::from_ymd_opt(2000, 1, 1).unwrap())
+        );
+    }
+
+    #[cfg(feature = "gerning")]
+    #[test]
+    fn gerning_arguments() {
+        let registry = registry();
+        assert_eq!(
+            registry
+                .coerce_argument("me@example.com".into(), Type::Bytes)
+                .unwrap(),
+            Value::Bytes(b"example.com".to_vec().into())
+        );
+        assert_eq!(
+            registry.coerce_argument("7".into(), Type::U8).unwrap(),
+            Value::from(7u8)
+        );
+        assert!(registry
+            .coerce_argument(Value::Bool(true), Type::Uuid)
+            .is_err());
+    }
+}
@@ -1,5 +1,8 @@
 use super::{number, ValueDeserializer};
-use crate::{json::JsonValue, Value};
+use crate::{
+    json::{JsonValue, RawJson},
+    Value,
+};
 use core::marker::PhantomData;
 use serde::{de, forward_to_deserialize_any};
 
@@ -40,6 +43,9 @@ where
                 }),
             )),
             JsonValue::String(s) => visitor.visit_str(&s),
+            JsonValue::Raw(raw) => {
+                ValueDeserializer::new(raw.into_value()).deserialize_any(visitor)
+            }
         }
     }
 
@@ -60,3 +66,28 @@ where
         self
     }
 }
+
+/// Values JSON has no type for are converted like `JsonValue::from` does
+impl<'de> de::Deserialize<'de> for JsonValue {
+    fn deserialize<D: de::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        Value::deserialize(d).map(JsonValue::from)
+    }
+}
+
+impl<'de, E> de::IntoDeserializer<'de, E> for JsonValue
+where
+    E: de::Error,
+{
+    type Deserializer = JsonDeserializer<E>;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        JsonDeserializer::new(self)
+    }
+}
+
+/// Deserializes any value and writes it as JSON text
+impl<'de> de::Deserialize<'de> for RawJson {
+    fn deserialize<D: de::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        Value::deserialize(d).map(RawJson::from_value)
+    }
+}
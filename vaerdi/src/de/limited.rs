@@ -0,0 +1,230 @@
+use alloc::{string::String as StdString, vec::Vec};
+use core::fmt;
+use serde::de::{self, DeserializeSeed};
+
+use crate::{
+    limits::{LimitError, LimitTracker, Limits},
+    Map, Value,
+};
+
+/// Deserialize a `Value` within `limits`.
+///
+/// Serde errors cannot hold a `LimitError`, so it is reported through
+/// `Error::custom` with the path where the limit was exceeded.
+pub fn deserialize_with_limits<'de, D>(deserializer: D, limits: Limits) -> Result<Value, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    let mut state = State {
+        tracker: LimitTracker::new(limits),
+        error: None,
+    };
+
+    let ret = ValueSeed { state: &mut state }.deserialize(deserializer);
+    match (ret, state.error) {
+        (Err(_), Some(err)) => Err(de::Error::custom(err)),
+        (ret, _) => ret,
+    }
+}
+
+struct State {
+    tracker: LimitTracker,
+    /// The limit that failed, its path is filled in as the error returns
+    error: Option<LimitError>,
+}
+
+impl State {
+    fn check<E, F>(&mut self, check: F) -> Result<(), E>
+    where
+        E: de::Error,
+        F: FnOnce(&mut LimitTracker) -> Result<(), LimitError>,
+    {
+        check(&mut self.tracker).map_err(|err| {
+            let ret = E::custom(&err);
+            self.error = Some(err);
+            ret
+        })
+    }
+
+    fn in_field(&mut self, name: &str) {
+        self.error = self.error.take().map(|err| err.with_field(name));
+    }
+
+    fn in_index(&mut self, idx: usize) {
+        self.error = self.error.take().map(|err| err.with_index(idx));
+    }
+}
+
+struct ValueSeed<'a> {
+    state: &'a mut State,
+}
+
+impl<'de> DeserializeSeed<'de> for ValueSeed<'_> {
+    type Value = Value;
+
+    fn deserialize<D: de::Deserializer<'de>>(self, d: D) -> Result<Value, D::Error> {
+        d.deserialize_any(self)
+    }
+}
+
+impl<'de> de::Visitor<'de> for ValueSeed<'_> {
+    type Value = Value;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("any value")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_i8<E: de::Error>(self, value: i8) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_i16<E: de::Error>(self, value: i16) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_i32<E: de::Error>(self, value: i32) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_u8<E: de::Error>(self, value: u8) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_u16<E: de::Error>(self, value: u16) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_u32<E: de::Error>(self, value: u32) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_f32<E: de::Error>(self, value: f32) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_char<E: de::Error>(self, value: char) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        Ok(Value::Char(value))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        self.state.check(|limits| limits.text(value.len()))?;
+        Ok(Value::String(value.into()))
+    }
+
+    fn visit_string<E: de::Error>(self, value: StdString) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        self.state.check(|limits| limits.text(value.len()))?;
+        Ok(Value::String(value.into()))
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        self.state.check(|limits| limits.text(value.len()))?;
+        Ok(Value::Bytes(value.to_vec().into()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        self.state.check(|limits| limits.text(value.len()))?;
+        Ok(Value::Bytes(value.into()))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Value, E> {
+        self.state.check(LimitTracker::value)?;
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: de::Deserializer<'de>>(self, d: D) -> Result<Value, D::Error> {
+        d.deserialize_any(self)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        self.state.check(LimitTracker::value)?;
+        self.state.check(LimitTracker::enter)?;
+
+        let mut values = Vec::new();
+        loop {
+            let idx = values.len();
+            let seed = ValueSeed {
+                state: &mut *self.state,
+            };
+            match seq.next_element_seed(seed) {
+                Ok(Some(value)) => {
+                    self.state.check(|limits| limits.item(idx))?;
+                    values.push(value);
+                }
+                Ok(None) => break,
+                Err(err) => {
+                    self.state.in_index(idx);
+                    return Err(err);
+                }
+            }
+        }
+
+        self.state.tracker.leave();
+        Ok(Value::List(values.into()))
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut access: A) -> Result<Value, A::Error> {
+        self.state.check(LimitTracker::value)?;
+        self.state.check(LimitTracker::enter)?;
+
+        let mut map = Map::default();
+        let mut idx = 0;
+        while let Some(key) = access.next_key::<StdString>()? {
+            self.state.check(|limits| limits.item(idx))?;
+            self.state.check(|limits| limits.text(key.len()))?;
+
+            let seed = ValueSeed {
+                state: &mut *self.state,
+            };
+            match access.next_value_seed(seed) {
+                Ok(value) => {
+                    map.insert(key, value);
+                }
+                Err(err) => {
+                    self.state.in_field(&key);
+                    return Err(err);
+                }
+            }
+            idx += 1;
+        }
+
+        self.state.tracker.leave();
+        Ok(Value::Map(map))
+    }
+}
@@ -1,6 +1,11 @@
-// mod json;
+#[cfg(feature = "json")]
+mod json;
+mod limited;
 mod number;
 mod time;
 mod value;
 
-pub use self::{number::*, value::*};
+pub use self::{limited::deserialize_with_limits, number::*, value::*};
+
+#[cfg(feature = "json")]
+pub use self::json::*;
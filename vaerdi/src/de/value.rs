@@ -2,7 +2,7 @@ use super::{
     number,
     time::{self, Time},
 };
-use crate::{value::Value, Map, String};
+use crate::{limits::Limits, value::Value, Map, String};
 use alloc::{
     borrow::ToOwned,
     string::{String as StdString, ToString},
@@ -332,9 +332,10 @@ impl<'de> de::Visitor<'de> for ValueVisitor {
     }
 }
 
+/// Deserializes within the default `Limits`, see [`deserialize_with_limits`](super::deserialize_with_limits)
 impl<'de> de::Deserialize<'de> for Value {
     fn deserialize<D: de::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
-        d.deserialize_any(ValueVisitor)
+        super::deserialize_with_limits(d, Limits::default())
     }
 }
 
@@ -0,0 +1,253 @@
+//! Canonical JSON as specified by RFC 8785, the JSON Canonicalization Scheme.
+//!
+//! Keys are sorted by their UTF-16 code units, strings only escape what JSON
+//! requires and numbers are written like ECMAScript's `Number.prototype.toString`.
+//!
+//! JCS only knows IEEE 754 doubles, so every number is converted to a `f64`
+//! first: integers above 2^53 and `F32` values are written as the double they
+//! become, like a JavaScript producer would. NaN and infinite floats fail with
+//! `WriteError::NonFinite`. The variants JSON has no type for are written as
+//! strings like [`to_string`](super::to_string) does:
+//!
+//! - `Bytes` as padded standard base64
+//! - `Date` as `2024-01-02`, `Time` as `12:30:00` and `DateTime` in UTC as `2024-01-02T12:30:00Z`
+//! - `Uuid` hyphenated in lowercase
+//! - `Char` as a string of the character
+
+use alloc::{format, string::String};
+use core::cmp::Ordering;
+
+use super::{
+    error::WriteError,
+    to_string::{to_string_with, DisplayOptions},
+};
+use crate::Value;
+
+/// Write `value` as canonical JSON, see the [module docs](self)
+pub fn to_canonical_string(value: &Value) -> Result<String, WriteError> {
+    to_string_with(value, &DisplayOptions::canonical())
+}
+
+/// Compare keys by their UTF-16 code units
+pub(super) fn compare_keys(a: &str, b: &str) -> Ordering {
+    a.encode_utf16().cmp(b.encode_utf16())
+}
+
+/// A finite `value` formatted like ECMAScript's `Number.prototype.toString`
+pub(super) fn format_number(value: f64) -> String {
+    if value == 0. {
+        // Negative zero too
+        return String::from("0");
+    }
+
+    // The shortest digits that round trip, like ECMAScript requires
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("exponent in scientific notation");
+    let mut digits = mantissa.replace('.', "");
+    let mut exponent = exponent.parse::<i32>().expect("exponent is a number");
+
+    if let Some((even, carry)) = round_half_even(value.abs(), &digits) {
+        digits = even;
+        exponent += i32::from(carry);
+    }
+
+    // The value is 0.digits * 10^n
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+
+    let mut out = String::new();
+    if value < 0. {
+        out.push('-');
+    }
+
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend((k..n).map(|_| '0'));
+    } else if 0 < n && n <= 21 {
+        let (int, fraction) = digits.split_at(n as usize);
+        out.push_str(int);
+        out.push('.');
+        out.push_str(fraction);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend((n..0).map(|_| '0'));
+        out.push_str(&digits);
+    } else {
+        let (first, rest) = digits.split_at(1);
+        out.push_str(first);
+        if !rest.is_empty() {
+            out.push('.');
+            out.push_str(rest);
+        }
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        out.push_str(&format!("e{sign}{}", (n - 1).abs()));
+    }
+
+    out
+}
+
+/// Rust breaks ties between two shortest digit strings away from zero where
+/// ECMAScript picks the even one. Returns the even digits and whether they
+/// carried into a new leading digit, when `value` lies exactly between the
+/// two candidates and `digits` is the odd one.
+fn round_half_even(value: f64, digits: &str) -> Option<(String, bool)> {
+    // Integers below 2^53 are exact, so their shortest digits never tie
+    const EXACT: f64 = 9007199254740992.0;
+    if is_even(digits.as_bytes())
+        || (value > -EXACT && value < EXACT && value as i64 as f64 == value)
+    {
+        return None;
+    }
+
+    // Every finite double has at most 767 significant decimal digits
+    let exact = format!("{value:.767e}");
+    let (exact, _) = exact.split_once('e')?;
+    let exact = exact.replace('.', "");
+    let (prefix, rest) = exact.split_at(digits.len());
+    if !rest.starts_with('5') || !rest[1..].bytes().all(|byte| byte == b'0') {
+        return None;
+    }
+
+    let mut even = prefix.as_bytes().to_vec();
+    let mut carry = false;
+    if !is_even(&even) {
+        // Round up, carrying through nines
+        carry = true;
+        for digit in even.iter_mut().rev() {
+            if *digit == b'9' {
+                *digit = b'0';
+            } else {
+                *digit += 1;
+                carry = false;
+                break;
+            }
+        }
+        if carry {
+            even.insert(0, b'1');
+        }
+    }
+
+    while even.len() > 1 && even[even.len() - 1] == b'0' {
+        even.pop();
+    }
+
+    Some((String::from_utf8(even).ok()?, carry))
+}
+
+fn is_even(digits: &[u8]) -> bool {
+    matches!(digits.last(), Some(b'0' | b'2' | b'4' | b'6' | b'8'))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, vec::Vec};
+
+    use super::*;
+    use crate::{json::parse, Map, Number};
+
+    /// Appendix B, the IEEE 754 bits of a double and its canonical form
+    const NUMBERS: &[(u64, &str)] = &[
+        (0x0000000000000000, "0"),
+        (0x8000000000000000, "0"),
+        (0x0000000000000001, "5e-324"),
+        (0x8000000000000001, "-5e-324"),
+        (0x7fefffffffffffff, "1.7976931348623157e+308"),
+        (0xffefffffffffffff, "-1.7976931348623157e+308"),
+        (0x4340000000000000, "9007199254740992"),
+        (0xc340000000000000, "-9007199254740992"),
+        (0x4430000000000000, "295147905179352830000"),
+        (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+        (0x44b52d02c7e14af6, "1e+23"),
+        (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+        (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+        (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+        (0x444b1ae4d6e2ef50, "1e+21"),
+        (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+        (0x3eb0c6f7a0b5ed8d, "0.000001"),
+        (0x41b3de4355555553, "333333333.3333332"),
+        (0x41b3de4355555554, "333333333.33333325"),
+        (0x41b3de4355555555, "333333333.3333333"),
+        (0x41b3de4355555556, "333333333.3333334"),
+        (0x41b3de4355555557, "333333333.33333343"),
+        (0xbecbf647612f3696, "-0.0000033333333333333333"),
+        (0x43143ff3c1cb0959, "1424953923781206.2"),
+    ];
+
+    /// The NaN and infinities of appendix B, which JCS rejects
+    const NON_FINITE: &[u64] = &[0x7fffffffffffffff, 0x7ff0000000000000, 0xfff0000000000000];
+
+    /// Section 3.2.3, the keys in the order they are sorted
+    const SORTED_KEYS: &[&str] = &[
+        "\r",
+        "1",
+        "\u{0080}",
+        "\u{00f6}",
+        "\u{20ac}",
+        "\u{1f600}",
+        "\u{fb33}",
+    ];
+
+    /// Section 3.2.2
+    const INPUT: &str = r#"{
+  "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+  "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+  "literals": [null, true, false]
+}"#;
+
+    const OUTPUT: &str = r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#;
+
+    #[test]
+    fn numbers() {
+        for &(bits, expected) in NUMBERS {
+            let value = Value::Number(Number::F64(f64::from_bits(bits)));
+            let output = to_canonical_string(&value).expect("finite number");
+            assert_eq!(output, expected, "{bits:#018x}");
+        }
+    }
+
+    #[test]
+    fn integers() {
+        for value in [
+            1.0,
+            -3.0,
+            15.0,
+            123456789.0,
+            9007199254740991.0,
+            -9007199254740991.0,
+        ] {
+            let output = to_canonical_string(&Value::Number(Number::F64(value))).unwrap();
+            assert_eq!(output, format!("{}", value as i64));
+        }
+    }
+
+    #[test]
+    fn non_finite() {
+        for &bits in NON_FINITE {
+            let value = Value::Number(Number::F64(f64::from_bits(bits)));
+            assert!(to_canonical_string(&value).is_err(), "{bits:#018x}");
+        }
+    }
+
+    #[test]
+    fn sorted_keys() {
+        let mut map = Map::default();
+        for key in SORTED_KEYS.iter().rev() {
+            map.insert(*key, Value::Null);
+        }
+        let expected = SORTED_KEYS
+            .iter()
+            .map(|key| format!("{}:null", to_canonical_string(&Value::from(*key)).unwrap()))
+            .collect::<Vec<_>>()
+            .join(",");
+        let output = to_canonical_string(&Value::Map(map)).unwrap();
+        assert_eq!(output, format!("{{{expected}}}"));
+    }
+
+    #[test]
+    fn document() {
+        let value = parse(INPUT).expect("valid json");
+        assert_eq!(to_canonical_string(&value).unwrap(), OUTPUT);
+    }
+}
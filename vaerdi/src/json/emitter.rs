@@ -0,0 +1,712 @@
+use alloc::vec::Vec;
+use base64::Engine;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Timelike};
+use core::fmt;
+
+use super::{
+    canonical,
+    error::WriteError,
+    extended,
+    raw::RawJson,
+    to_string::{BytesEncoding, DisplayOptions, NonFinite, TemporalEncoding},
+    value::JsonValue,
+};
+use crate::{bytes::Bytes, List, Map, Number, String, Value};
+
+/// Where a [`JsonEmitter`] writes to
+pub trait Output {
+    fn write_str(&mut self, value: &str) -> Result<(), WriteError>;
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<(), WriteError>;
+}
+
+/// Output to a `core::fmt::Write`
+#[derive(Debug)]
+pub struct FmtOutput<W>(pub W);
+
+impl<W: fmt::Write> Output for FmtOutput<W> {
+    fn write_str(&mut self, value: &str) -> Result<(), WriteError> {
+        Ok(self.0.write_str(value)?)
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<(), WriteError> {
+        Ok(self.0.write_fmt(args)?)
+    }
+}
+
+/// Output to a `std::io::Write`.
+/// Every token is a separate write, so wrap unbuffered writers in a `BufWriter`.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct IoOutput<W>(pub W);
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> Output for IoOutput<W> {
+    fn write_str(&mut self, value: &str) -> Result<(), WriteError> {
+        Ok(self.0.write_all(value.as_bytes())?)
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<(), WriteError> {
+        Ok(self.0.write_fmt(args)?)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    object: bool,
+    empty: bool,
+}
+
+/// Writes JSON piece by piece, without building a `Value` of the whole document.
+///
+/// ```ignore
+/// let mut emitter = JsonEmitter::new(String::new(), DisplayOptions::default());
+/// emitter.begin_object()?;
+/// emitter.key("rows")?;
+/// emitter.begin_array()?;
+/// for row in rows {
+///     emitter.value(&row)?;
+/// }
+/// emitter.end_array()?;
+/// emitter.end_object()?;
+/// let json = emitter.finish()?.0;
+/// ```
+#[derive(Debug)]
+pub struct JsonEmitter<O> {
+    output: O,
+    options: DisplayOptions,
+    stack: Vec<Frame>,
+    /// A key was written and its value comes next
+    has_key: bool,
+    /// The top level value is complete
+    done: bool,
+}
+
+impl<W: fmt::Write> JsonEmitter<FmtOutput<W>> {
+    pub fn new(output: W, options: DisplayOptions) -> JsonEmitter<FmtOutput<W>> {
+        JsonEmitter::with_output(FmtOutput(output), options)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> JsonEmitter<IoOutput<W>> {
+    pub fn from_writer(output: W, options: DisplayOptions) -> JsonEmitter<IoOutput<W>> {
+        JsonEmitter::with_output(IoOutput(output), options)
+    }
+}
+
+impl<O: Output> JsonEmitter<O> {
+    pub fn with_output(output: O, options: DisplayOptions) -> JsonEmitter<O> {
+        JsonEmitter {
+            output,
+            options,
+            stack: Vec::new(),
+            has_key: false,
+            done: false,
+        }
+    }
+
+    pub fn begin_object(&mut self) -> Result<(), WriteError> {
+        self.before_value()?;
+        self.output.write_str("{")?;
+        self.stack.push(Frame {
+            object: true,
+            empty: true,
+        });
+        Ok(())
+    }
+
+    pub fn end_object(&mut self) -> Result<(), WriteError> {
+        match self.stack.last() {
+            Some(frame) if frame.object && !self.has_key => self.end('}'),
+            Some(frame) if frame.object => Err(WriteError::InvalidState("missing value for key")),
+            _ => Err(WriteError::InvalidState("no object to end")),
+        }
+    }
+
+    pub fn begin_array(&mut self) -> Result<(), WriteError> {
+        self.before_value()?;
+        self.output.write_str("[")?;
+        self.stack.push(Frame {
+            object: false,
+            empty: true,
+        });
+        Ok(())
+    }
+
+    pub fn end_array(&mut self) -> Result<(), WriteError> {
+        match self.stack.last() {
+            Some(frame) if !frame.object => self.end(']'),
+            _ => Err(WriteError::InvalidState("no array to end")),
+        }
+    }
+
+    /// The key of the next value in the current object
+    pub fn key(&mut self, key: &str) -> Result<(), WriteError> {
+        let Some(frame) = self.stack.last_mut() else {
+            return Err(WriteError::InvalidState("key outside of an object"));
+        };
+
+        if !frame.object {
+            return Err(WriteError::InvalidState("key outside of an object"));
+        } else if self.has_key {
+            return Err(WriteError::InvalidState("missing value for key"));
+        }
+
+        let empty = core::mem::replace(&mut frame.empty, false);
+        if !empty {
+            self.output.write_str(",")?;
+        }
+        self.write_newline()?;
+        self.write_str(key)?;
+        self.output.write_str(":")?;
+        if self.options.indent.is_some() {
+            self.output.write_str(" ")?;
+        }
+
+        self.has_key = true;
+        Ok(())
+    }
+
+    /// Write a whole value
+    pub fn value(&mut self, value: &Value) -> Result<(), WriteError> {
+        if self.options.extended {
+            if let Some(tag) = extended::tag(value) {
+                return self.tagged(tag, value);
+            }
+        }
+
+        match value {
+            Value::Map(map) => self.object(map),
+            Value::List(list) => self.list(list),
+            value => {
+                self.before_value()?;
+                self.write_scalar(value)?;
+                self.after_value();
+                Ok(())
+            }
+        }
+    }
+
+    /// Write a whole object
+    pub fn object(&mut self, map: &Map) -> Result<(), WriteError> {
+        self.begin_object()?;
+        if self.options.sort_keys {
+            for (key, value) in self.sorted(map) {
+                self.key(key)?;
+                self.value(value)?;
+            }
+        } else {
+            for (key, value) in map.iter() {
+                self.key(key)?;
+                self.value(value)?;
+            }
+        }
+        self.end_object()
+    }
+
+    /// Write a whole array
+    pub fn list(&mut self, list: &List) -> Result<(), WriteError> {
+        self.begin_array()?;
+        for value in list.iter() {
+            self.value(value)?;
+        }
+        self.end_array()
+    }
+
+    /// Write a whole `JsonValue`
+    pub fn json_value(&mut self, value: &JsonValue) -> Result<(), WriteError> {
+        match value {
+            // The tags of extended JSON are decided on `Value`
+            _ if self.options.extended => self.value(&Value::from(value)),
+            JsonValue::Object(map) => {
+                self.begin_object()?;
+                if self.options.sort_keys {
+                    for (key, value) in self.sorted(map) {
+                        self.key(key)?;
+                        self.json_value(value)?;
+                    }
+                } else {
+                    for (key, value) in map.iter() {
+                        self.key(key)?;
+                        self.json_value(value)?;
+                    }
+                }
+                self.end_object()
+            }
+            JsonValue::List(list) => {
+                self.begin_array()?;
+                for value in list.iter() {
+                    self.json_value(value)?;
+                }
+                self.end_array()
+            }
+            JsonValue::Raw(raw) => self.raw(raw),
+            value => {
+                self.before_value()?;
+                match value {
+                    JsonValue::String(s) => self.write_str(s)?,
+                    JsonValue::Number(n) => self.write_number(n)?,
+                    JsonValue::Bool(b) => write!(self.output, "{b}")?,
+                    _ => self.output.write_str("null")?,
+                }
+                self.after_value();
+                Ok(())
+            }
+        }
+    }
+
+    /// Write raw JSON as it is, without its surrounding whitespace.
+    /// Canonical and extended JSON write the parsed value instead.
+    pub fn raw(&mut self, raw: &RawJson) -> Result<(), WriteError> {
+        if self.options.canonical || self.options.extended {
+            return self.value(raw.value());
+        }
+
+        self.before_value()?;
+        self.output
+            .write_str(raw.as_str().trim_matches([' ', '\t', '\n', '\r']))?;
+        self.after_value();
+        Ok(())
+    }
+
+    /// Check that the document is complete and return the output
+    pub fn finish(self) -> Result<O, WriteError> {
+        if !self.done {
+            return Err(WriteError::InvalidState("document is not complete"));
+        }
+        Ok(self.output)
+    }
+
+    /// The output, complete or not
+    pub fn into_inner(self) -> O {
+        self.output
+    }
+
+    /// The entries of `map` in the order of its keys
+    fn sorted<'a, V>(&self, map: &'a Map<V>) -> Vec<(&'a String, &'a V)> {
+        let mut entries = map.iter().collect::<Vec<_>>();
+        if self.options.canonical {
+            entries.sort_by(|a, b| canonical::compare_keys(a.0, b.0));
+        } else {
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+        }
+        entries
+    }
+
+    fn before_value(&mut self) -> Result<(), WriteError> {
+        let Some(frame) = self.stack.last_mut() else {
+            if self.done {
+                return Err(WriteError::InvalidState("document is already complete"));
+            }
+            return Ok(());
+        };
+
+        if frame.object {
+            if !self.has_key {
+                return Err(WriteError::InvalidState("value without a key"));
+            }
+            self.has_key = false;
+            return Ok(());
+        }
+
+        let empty = core::mem::replace(&mut frame.empty, false);
+        if !empty {
+            self.output.write_str(",")?;
+        }
+        self.write_newline()
+    }
+
+    fn after_value(&mut self) {
+        if self.stack.is_empty() {
+            self.done = true;
+        }
+    }
+
+    fn end(&mut self, close: char) -> Result<(), WriteError> {
+        if let Some(frame) = self.stack.pop() {
+            if !frame.empty {
+                self.write_newline()?;
+            }
+        }
+        self.output.write_str(close.encode_utf8(&mut [0; 4]))?;
+        self.after_value();
+        Ok(())
+    }
+
+    fn write_newline(&mut self) -> Result<(), WriteError> {
+        if let Some(indent) = &self.options.indent {
+            self.output.write_str("\n")?;
+            for _ in 0..self.stack.len() {
+                self.output.write_str(indent)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_scalar(&mut self, value: &Value) -> Result<(), WriteError> {
+        match value {
+            Value::Bool(b) => write!(self.output, "{b}"),
+            Value::String(s) => self.write_str(s),
+            Value::Bytes(b) => self.write_bytes(b),
+            Value::Date(date) => self.write_date(date),
+            Value::DateTime(datetime) => self.write_datetime(datetime),
+            Value::Time(time) => self.write_time(time),
+            Value::Uuid(id) => write!(self.output, "\"{}\"", id.as_hyphenated()),
+            Value::Number(n) => self.write_number(n),
+            Value::Char(c) => self.write_str(c.encode_utf8(&mut [0; 4])),
+            Value::Null => self.output.write_str("null"),
+            Value::Map(map) => self.object(map),
+            Value::List(list) => self.list(list),
+        }
+    }
+
+    /// Write `value` as the object of its extended JSON tag
+    fn tagged(&mut self, tag: &str, value: &Value) -> Result<(), WriteError> {
+        self.begin_object()?;
+        self.key(tag)?;
+
+        if let Value::Map(map) = value {
+            self.object(map)?;
+        } else {
+            self.before_value()?;
+            self.write_extended(value)?;
+            self.after_value();
+        }
+
+        self.end_object()
+    }
+
+    /// The content of an extended JSON tag
+    fn write_extended(&mut self, value: &Value) -> Result<(), WriteError> {
+        match value {
+            Value::Number(Number::F32(n)) if n.is_finite() => write!(self.output, "{n:?}"),
+            Value::Number(Number::F32(n)) => self.write_str(extended::non_finite(*n as f64)),
+            Value::Number(Number::F64(n)) => self.write_str(extended::non_finite(*n)),
+            Value::Number(n) => write!(self.output, "{n}"),
+            Value::Bytes(bytes) => {
+                let encoded = base64::engine::general_purpose::STANDARD.encode(&**bytes);
+                write!(self.output, "\"{encoded}\"")
+            }
+            Value::Date(date) => write!(self.output, "\"{date}\""),
+            Value::DateTime(datetime) => write!(
+                self.output,
+                "\"{}\"",
+                datetime.format("%Y-%m-%dT%H:%M:%S%.f")
+            ),
+            Value::Time(time) => write!(self.output, "\"{time}\""),
+            value => self.write_scalar(value),
+        }
+    }
+
+    fn write_str(&mut self, value: &str) -> Result<(), WriteError> {
+        self.output.write_str("\"")?;
+
+        let mut start = 0;
+        for (idx, ch) in value.char_indices() {
+            let escape = match ch {
+                '"' => "\\\"",
+                '\\' => "\\\\",
+                '\n' => "\\n",
+                '\r' => "\\r",
+                '\t' => "\\t",
+                '\u{08}' => "\\b",
+                '\u{0C}' => "\\f",
+                ch if ch < ' ' || (self.options.ascii && !ch.is_ascii()) => "",
+                _ => continue,
+            };
+
+            self.output.write_str(&value[start..idx])?;
+            start = idx + ch.len_utf8();
+
+            if escape.is_empty() {
+                let mut units = [0; 2];
+                for unit in ch.encode_utf16(&mut units) {
+                    write!(self.output, "\\u{unit:04x}")?;
+                }
+            } else {
+                self.output.write_str(escape)?;
+            }
+        }
+
+        self.output.write_str(&value[start..])?;
+        self.output.write_str("\"")
+    }
+
+    fn write_number(&mut self, number: &Number) -> Result<(), WriteError> {
+        if self.options.canonical {
+            let float = number.as_f64();
+            if float.is_finite() {
+                return self.output.write_str(&canonical::format_number(float));
+            }
+        }
+
+        let float = match *number {
+            Number::F32(n) if n.is_finite() => return write!(self.output, "{n:?}"),
+            Number::F64(n) if n.is_finite() => return write!(self.output, "{n:?}"),
+            Number::F32(n) => n as f64,
+            Number::F64(n) => n,
+            n => return write!(self.output, "{n}"),
+        };
+
+        match self.options.non_finite {
+            NonFinite::Error => Err(WriteError::NonFinite(float)),
+            NonFinite::Null => self.output.write_str("null"),
+            NonFinite::String if float.is_nan() => self.output.write_str("\"NaN\""),
+            NonFinite::String if float > 0. => self.output.write_str("\"Infinity\""),
+            NonFinite::String => self.output.write_str("\"-Infinity\""),
+        }
+    }
+
+    fn write_bytes(&mut self, bytes: &Bytes) -> Result<(), WriteError> {
+        match self.options.bytes {
+            BytesEncoding::Base64 => {
+                let encoded = base64::engine::general_purpose::STANDARD.encode(&**bytes);
+                write!(self.output, "\"{encoded}\"")
+            }
+            BytesEncoding::Hex => {
+                self.output.write_str("\"")?;
+                for byte in bytes.iter() {
+                    write!(self.output, "{byte:02x}")?;
+                }
+                self.output.write_str("\"")
+            }
+            BytesEncoding::Array => {
+                self.output.write_str("[")?;
+                for (idx, byte) in bytes.iter().enumerate() {
+                    if idx > 0 {
+                        self.output.write_str(",")?;
+                    }
+                    write!(self.output, "{byte}")?;
+                }
+                self.output.write_str("]")
+            }
+        }
+    }
+
+    fn write_date(&mut self, date: &NaiveDate) -> Result<(), WriteError> {
+        match self.options.temporal {
+            TemporalEncoding::Rfc3339 | TemporalEncoding::Naive => {
+                write!(self.output, "\"{date}\"")
+            }
+            _ => self.write_datetime(&date.and_time(NaiveTime::MIN)),
+        }
+    }
+
+    fn write_time(&mut self, time: &NaiveTime) -> Result<(), WriteError> {
+        let seconds = time.num_seconds_from_midnight();
+        let nanos = time.nanosecond();
+
+        match self.options.temporal {
+            TemporalEncoding::Rfc3339 | TemporalEncoding::Naive => {
+                write!(self.output, "\"{time}\"")
+            }
+            TemporalEncoding::Timestamp if nanos == 0 => write!(self.output, "{seconds}"),
+            TemporalEncoding::Timestamp => {
+                let seconds = seconds as f64 + nanos as f64 / 1e9;
+                write!(self.output, "{seconds:?}")
+            }
+            TemporalEncoding::TimestampMillis => {
+                let millis = seconds as u64 * 1000 + (nanos / 1_000_000) as u64;
+                write!(self.output, "{millis}")
+            }
+        }
+    }
+
+    fn write_datetime(&mut self, datetime: &NaiveDateTime) -> Result<(), WriteError> {
+        let datetime = datetime.and_utc();
+
+        match self.options.temporal {
+            TemporalEncoding::Rfc3339 => write!(
+                self.output,
+                "\"{}\"",
+                datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+            ),
+            TemporalEncoding::Naive => write!(
+                self.output,
+                "\"{}\"",
+                datetime.naive_utc().format("%Y-%m-%dT%H:%M:%S%.f")
+            ),
+            TemporalEncoding::Timestamp => write!(self.output, "{}", datetime.timestamp()),
+            TemporalEncoding::TimestampMillis => {
+                write!(self.output, "{}", datetime.timestamp_millis())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String as AllocString;
+
+    use super::*;
+    use crate::json::{parse, to_fmt_writer};
+
+    fn emitter() -> JsonEmitter<FmtOutput<AllocString>> {
+        JsonEmitter::new(AllocString::new(), DisplayOptions::default())
+    }
+
+    fn invalid_state<T: fmt::Debug>(ret: Result<T, WriteError>) -> &'static str {
+        match ret {
+            Err(WriteError::InvalidState(message)) => message,
+            ret => panic!("expected an invalid state, found {ret:?}"),
+        }
+    }
+
+    #[test]
+    fn document() {
+        let mut emitter = emitter();
+        emitter.begin_object().unwrap();
+        emitter.key("rows").unwrap();
+        emitter.begin_array().unwrap();
+        emitter.value(&Value::from(1)).unwrap();
+        emitter.list(&List::from(alloc::vec![Value::Null])).unwrap();
+        emitter.end_array().unwrap();
+        emitter.key("raw").unwrap();
+        emitter.raw(&RawJson::new(" [1, 2] ").unwrap()).unwrap();
+        emitter.end_object().unwrap();
+
+        assert_eq!(
+            emitter.finish().unwrap().0,
+            r#"{"rows":[1,[null]],"raw":[1, 2]}"#
+        );
+    }
+
+    #[test]
+    fn pretty_document() {
+        let mut emitter = JsonEmitter::new(AllocString::new(), DisplayOptions::pretty());
+        emitter.begin_array().unwrap();
+        emitter.begin_object().unwrap();
+        emitter.end_object().unwrap();
+        emitter.begin_object().unwrap();
+        emitter.key("a").unwrap();
+        emitter.value(&Value::Bool(true)).unwrap();
+        emitter.end_object().unwrap();
+        emitter.end_array().unwrap();
+
+        assert_eq!(
+            emitter.finish().unwrap().0,
+            "[\n  {},\n  {\n    \"a\": true\n  }\n]"
+        );
+    }
+
+    #[test]
+    fn invalid_states() {
+        let mut emitter = emitter();
+        assert_eq!(invalid_state(emitter.key("a")), "key outside of an object");
+        assert_eq!(invalid_state(emitter.end_object()), "no object to end");
+        assert_eq!(invalid_state(emitter.end_array()), "no array to end");
+
+        emitter.begin_array().unwrap();
+        assert_eq!(invalid_state(emitter.key("a")), "key outside of an object");
+        assert_eq!(invalid_state(emitter.end_object()), "no object to end");
+
+        emitter.begin_object().unwrap();
+        assert_eq!(
+            invalid_state(emitter.value(&Value::Null)),
+            "value without a key"
+        );
+        assert_eq!(invalid_state(emitter.begin_array()), "value without a key");
+        assert_eq!(invalid_state(emitter.end_array()), "no array to end");
+
+        emitter.key("a").unwrap();
+        assert_eq!(invalid_state(emitter.key("b")), "missing value for key");
+        assert_eq!(invalid_state(emitter.end_object()), "missing value for key");
+        emitter.value(&Value::Null).unwrap();
+        emitter.end_object().unwrap();
+        emitter.end_array().unwrap();
+
+        assert_eq!(
+            invalid_state(emitter.value(&Value::Null)),
+            "document is already complete"
+        );
+        assert_eq!(
+            invalid_state(emitter.begin_object()),
+            "document is already complete"
+        );
+        assert_eq!(emitter.finish().unwrap().0, r#"[{"a":null}]"#);
+    }
+
+    #[test]
+    fn finish_incomplete() {
+        assert_eq!(
+            invalid_state(emitter().finish()),
+            "document is not complete"
+        );
+
+        let mut emitter = emitter();
+        emitter.begin_object().unwrap();
+        emitter.key("a").unwrap();
+        emitter.begin_array().unwrap();
+        emitter.value(&Value::from("b")).unwrap();
+        assert_eq!(emitter.into_inner().0, r#"{"a":["b""#);
+
+        let mut emitter = self::emitter();
+        emitter.begin_array().unwrap();
+        assert_eq!(invalid_state(emitter.finish()), "document is not complete");
+    }
+
+    #[test]
+    fn scalar_document() {
+        let mut emitter = emitter();
+        emitter.value(&Value::from("text")).unwrap();
+        assert_eq!(emitter.finish().unwrap().0, r#""text""#);
+    }
+
+    #[test]
+    fn fmt_writer() {
+        let value = parse(r#"[1, "a", {"b": null}]"#).unwrap();
+
+        let mut out = AllocString::from("value: ");
+        to_fmt_writer(&mut out, &value, &DisplayOptions::default()).unwrap();
+        assert_eq!(out, r#"value: [1,"a",{"b":null}]"#);
+
+        let err = to_fmt_writer(
+            &mut AllocString::new(),
+            &Value::from(f64::NAN),
+            &DisplayOptions::default().non_finite(NonFinite::Error),
+        )
+        .unwrap_err();
+        assert!(matches!(err, WriteError::NonFinite(_)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_writer() {
+        use crate::json::to_writer;
+
+        let value = parse(r#"{"a": [true, 1.5]}"#).unwrap();
+
+        let mut out = std::vec::Vec::new();
+        to_writer(&mut out, &value, &DisplayOptions::pretty()).unwrap();
+        assert_eq!(out, b"{\n  \"a\": [\n    true,\n    1.5\n  ]\n}");
+
+        let mut emitter = JsonEmitter::from_writer(std::vec::Vec::new(), DisplayOptions::default());
+        emitter.begin_array().unwrap();
+        emitter.value(&Value::from(1)).unwrap();
+        emitter.end_array().unwrap();
+        assert_eq!(emitter.finish().unwrap().0, b"[1]");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_errors() {
+        use crate::json::to_writer;
+        use std::string::ToString;
+
+        struct Failing;
+
+        impl std::io::Write for Failing {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("broken pipe"))
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let err = to_writer(Failing, &Value::Null, &DisplayOptions::default()).unwrap_err();
+        assert!(matches!(&err, WriteError::Io(err) if err.to_string() == "broken pipe"));
+    }
+}
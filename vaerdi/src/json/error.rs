@@ -0,0 +1,193 @@
+use core::fmt;
+
+use crate::limits::LimitError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The input ended in the middle of a value
+    Eof,
+    /// A character that cannot appear here
+    Unexpected {
+        found: char,
+        expected: &'static str,
+    },
+    InvalidNumber,
+    InvalidEscape,
+    /// A `\u` escape of a surrogate without its other half
+    LoneSurrogate,
+    /// A control character inside a string, these must be escaped
+    ControlCharacter,
+    /// Something other than whitespace after the value
+    TrailingCharacters,
+    /// The input is not valid UTF-8
+    InvalidUtf8,
+    /// An extended JSON tag with content that does not fit it
+    InvalidExtended,
+    /// The document exceeds the `Limits` of the parse options
+    Limit(LimitError),
+    /// Valid JSON, but not what the reader was asked for
+    Mismatch {
+        expected: &'static str,
+    },
+    /// Reading the input failed
+    #[cfg(feature = "std")]
+    Io(std::io::ErrorKind),
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Eof => write!(f, "unexpected end of input"),
+            Self::Unexpected { found, expected } => {
+                write!(f, "expected {expected}, found {found:?}")
+            }
+            Self::InvalidNumber => write!(f, "invalid number"),
+            Self::InvalidEscape => write!(f, "invalid escape sequence"),
+            Self::LoneSurrogate => write!(f, "unpaired surrogate in unicode escape"),
+            Self::ControlCharacter => write!(f, "control character in string"),
+            Self::TrailingCharacters => write!(f, "trailing characters after value"),
+            Self::InvalidUtf8 => write!(f, "invalid UTF-8"),
+            Self::InvalidExtended => write!(f, "invalid extended JSON value"),
+            Self::Limit(err) => err.fmt(f),
+            Self::Mismatch { expected } => write!(f, "expected {expected}"),
+            #[cfg(feature = "std")]
+            Self::Io(kind) => write!(f, "failed to read input: {kind}"),
+        }
+    }
+}
+
+/// An error in a JSON document and where it happened
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl ParseError {
+    /// An error at byte `offset` of `input`
+    pub fn new(kind: ParseErrorKind, input: &str, offset: usize) -> ParseError {
+        let offset = offset.min(input.len());
+        let before = &input.as_bytes()[..offset];
+        let line_start = before
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |idx| idx + 1);
+
+        // Count characters by skipping the continuation bytes of UTF-8
+        let column = before[line_start..]
+            .iter()
+            .filter(|&&b| b & 0xC0 != 0x80)
+            .count();
+
+        ParseError {
+            kind,
+            offset,
+            line: before.iter().filter(|&&b| b == b'\n').count() + 1,
+            column: column + 1,
+        }
+    }
+
+    /// An error at a location tracked by the caller
+    pub(crate) fn at(
+        kind: ParseErrorKind,
+        offset: usize,
+        line: usize,
+        column: usize,
+    ) -> ParseError {
+        ParseError {
+            kind,
+            offset,
+            line,
+            column,
+        }
+    }
+
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+
+    /// Mark a limit error as happening inside the field `name`
+    pub(crate) fn with_field(mut self, name: &str) -> ParseError {
+        if let ParseErrorKind::Limit(err) = self.kind {
+            self.kind = ParseErrorKind::Limit(err.with_field(name));
+        }
+        self
+    }
+
+    /// Mark a limit error as happening at index `idx` of a list
+    pub(crate) fn with_index(mut self, idx: usize) -> ParseError {
+        if let ParseErrorKind::Limit(err) = self.kind {
+            self.kind = ParseErrorKind::Limit(err.with_index(idx));
+        }
+        self
+    }
+
+    /// The byte offset of the error
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The line of the error, starting at 1
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column of the error in characters, starting at 1
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {} column {}",
+            self.kind, self.line, self.column
+        )
+    }
+}
+
+impl avagarden::error::Error for ParseError {}
+
+/// An error writing a value as JSON
+#[derive(Debug)]
+pub enum WriteError {
+    /// The output failed
+    Fmt(fmt::Error),
+    /// A NaN or infinite float, with `NonFinite::Error`
+    NonFinite(f64),
+    /// The calls to a `JsonEmitter` do not make a valid document
+    InvalidState(&'static str),
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fmt(err) => write!(f, "{err}"),
+            Self::NonFinite(value) => write!(f, "{value} cannot be written as JSON"),
+            Self::InvalidState(message) => write!(f, "invalid JSON: {message}"),
+            #[cfg(feature = "std")]
+            Self::Io(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl From<fmt::Error> for WriteError {
+    fn from(value: fmt::Error) -> Self {
+        WriteError::Fmt(value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for WriteError {
+    fn from(value: std::io::Error) -> Self {
+        WriteError::Io(value)
+    }
+}
+
+impl avagarden::error::Error for WriteError {}
@@ -0,0 +1,350 @@
+//! Extended JSON, a lossless encoding of every `Value` in plain JSON.
+//!
+//! Values JSON has no type for are written as objects with a single `$` key:
+//! `{"$u8": 5}`, `{"$f32": 1.5}`, `{"$bytes": "aGk="}`, `{"$date": "2024-01-02"}`,
+//! `{"$datetime": "2024-01-02T12:30:00.5"}`, `{"$time": "12:30:00"}`,
+//! `{"$uuid": "67e55044-10b1-426f-9247-bb680e5fe0c8"}` and `{"$char": "a"}`.
+//! Every number other than a finite `F64` is tagged with its width, non-finite
+//! floats as the strings `"NaN"`, `"Infinity"` and `"-Infinity"`. A map that
+//! would read as a tag is written as `{"$map": {...}}`.
+use base64::Engine;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+use super::error::ParseErrorKind;
+use crate::{bytes::Bytes, Map, Number, Value};
+
+const TAGS: &[&str] = &[
+    "$u8",
+    "$u16",
+    "$u32",
+    "$u64",
+    "$i8",
+    "$i16",
+    "$i32",
+    "$i64",
+    "$f32",
+    "$f64",
+    "$bytes",
+    "$date",
+    "$datetime",
+    "$time",
+    "$uuid",
+    "$char",
+    "$map",
+];
+
+/// The tag `value` is written with, `None` for the values plain JSON holds
+pub(super) fn tag(value: &Value) -> Option<&'static str> {
+    let tag = match value {
+        Value::Number(number) => match number {
+            Number::U8(_) => "$u8",
+            Number::U16(_) => "$u16",
+            Number::U32(_) => "$u32",
+            Number::U64(_) => "$u64",
+            Number::I8(_) => "$i8",
+            Number::I16(_) => "$i16",
+            Number::I32(_) => "$i32",
+            Number::I64(_) => "$i64",
+            Number::F32(_) => "$f32",
+            Number::F64(n) if n.is_finite() => return None,
+            Number::F64(_) => "$f64",
+        },
+        Value::Bytes(_) => "$bytes",
+        Value::Date(_) => "$date",
+        Value::DateTime(_) => "$datetime",
+        Value::Time(_) => "$time",
+        Value::Uuid(_) => "$uuid",
+        Value::Char(_) => "$char",
+        Value::Map(map) if is_tagged(map) => "$map",
+        Value::Bool(_) | Value::String(_) | Value::Map(_) | Value::List(_) | Value::Null => {
+            return None
+        }
+    };
+
+    Some(tag)
+}
+
+/// Whether `map` has the shape of a tagged value
+fn is_tagged(map: &Map) -> bool {
+    map.len() == 1 && map.iter().all(|(key, _)| key.starts_with('$'))
+}
+
+/// The text non-finite floats are written as
+pub(super) fn non_finite(value: f64) -> &'static str {
+    if value.is_nan() {
+        "NaN"
+    } else if value > 0. {
+        "Infinity"
+    } else {
+        "-Infinity"
+    }
+}
+
+/// The value an object stands for, decoding it when it is a known tag.
+///
+/// The parser keeps the value of a lone `$map` key as it is, as it is the
+/// content of a tag that must not be decoded.
+pub(super) fn decode(map: Map) -> Result<Value, ParseErrorKind> {
+    if map.len() != 1 || !map.iter().all(|(key, _)| TAGS.contains(&&**key)) {
+        return Ok(Value::Map(map));
+    }
+
+    let (tag, value) = map.into_iter().next().expect("one entry");
+    match (&*tag, value) {
+        ("$map", Value::Map(content)) => Ok(Value::Map(content_of(content)?)),
+        (tag, value) => decode_tag(tag, value).ok_or(ParseErrorKind::InvalidExtended),
+    }
+}
+
+/// The content of a `$map` tag, decoding the value the parser kept if it has one
+fn content_of(mut map: Map) -> Result<Map, ParseErrorKind> {
+    if map.len() == 1 {
+        if let Some(value) = map.remove("$map") {
+            let value = match value {
+                Value::Map(value) => decode(value)?,
+                value => value,
+            };
+            map.insert("$map", value);
+        }
+    }
+    Ok(map)
+}
+
+fn decode_tag(tag: &str, value: Value) -> Option<Value> {
+    let number = match (tag, value) {
+        ("$u8", Value::Number(n)) => Number::U8(n.try_into().ok()?),
+        ("$u16", Value::Number(n)) => Number::U16(n.try_into().ok()?),
+        ("$u32", Value::Number(n)) => Number::U32(n.try_into().ok()?),
+        ("$u64", Value::Number(n)) => Number::U64(n.try_into().ok()?),
+        ("$i8", Value::Number(n)) => Number::I8(n.try_into().ok()?),
+        ("$i16", Value::Number(n)) => Number::I16(n.try_into().ok()?),
+        ("$i32", Value::Number(n)) => Number::I32(n.try_into().ok()?),
+        ("$i64", Value::Number(n)) => Number::I64(n.try_into().ok()?),
+        ("$f32", Value::Number(n)) => Number::F32(n.as_f32()),
+        ("$f32", Value::String(s)) => Number::F32(parse_non_finite(&s)? as f32),
+        ("$f64", Value::Number(n)) => Number::F64(n.as_f64()),
+        ("$f64", Value::String(s)) => Number::F64(parse_non_finite(&s)?),
+        // The detectors of the parser may have converted the text already
+        ("$bytes", value @ Value::Bytes(_))
+        | ("$date", value @ Value::Date(_))
+        | ("$datetime", value @ Value::DateTime(_))
+        | ("$time", value @ Value::Time(_))
+        | ("$uuid", value @ Value::Uuid(_))
+        | ("$char", value @ Value::Char(_)) => return Some(value),
+        (tag, Value::String(s)) => return decode_str(tag, &s),
+        _ => return None,
+    };
+
+    Some(Value::Number(number))
+}
+
+fn decode_str(tag: &str, value: &str) -> Option<Value> {
+    let value = match tag {
+        "$bytes" => Value::Bytes(Bytes::from(
+            base64::engine::general_purpose::STANDARD
+                .decode(value)
+                .ok()?,
+        )),
+        "$date" => Value::Date(value.parse::<NaiveDate>().ok()?),
+        "$datetime" => Value::DateTime(value.parse::<NaiveDateTime>().ok()?),
+        "$time" => Value::Time(value.parse::<NaiveTime>().ok()?),
+        "$uuid" => Value::Uuid(uuid::Uuid::parse_str(value).ok()?),
+        "$char" => {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => Value::Char(ch),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+fn parse_non_finite(value: &str) -> Option<f64> {
+    match value {
+        "NaN" => Some(f64::NAN),
+        "Infinity" => Some(f64::INFINITY),
+        "-Infinity" => Some(f64::NEG_INFINITY),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec, vec::Vec};
+
+    use super::*;
+    use crate::json::{parse_with, to_string_with, DisplayOptions, ParseOptions};
+
+    fn write(value: &Value) -> String {
+        to_string_with(value, &DisplayOptions::default().extended(true)).unwrap()
+    }
+
+    fn read(input: &str) -> Result<Value, ParseErrorKind> {
+        parse_with(input, &ParseOptions::default().extended(true)).map_err(|err| err.kind().clone())
+    }
+
+    fn map<const N: usize>(entries: [(&str, Value); N]) -> Map {
+        let mut map = Map::default();
+        for (key, value) in entries {
+            map.insert(key, value);
+        }
+        map
+    }
+
+    fn round_trip(value: Value, expected: &str) {
+        let text = write(&value);
+        assert_eq!(text, expected);
+        assert_eq!(read(&text).unwrap(), value, "{text}");
+    }
+
+    #[test]
+    fn every_tag() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let time = NaiveTime::from_hms_milli_opt(12, 30, 0, 500).unwrap();
+        let uuid = uuid::Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+
+        round_trip(Value::from(5u8), r#"{"$u8":5}"#);
+        round_trip(Value::from(500u16), r#"{"$u16":500}"#);
+        round_trip(Value::from(5u32), r#"{"$u32":5}"#);
+        round_trip(Value::from(u64::MAX), r#"{"$u64":18446744073709551615}"#);
+        round_trip(Value::from(-5i8), r#"{"$i8":-5}"#);
+        round_trip(Value::from(-500i16), r#"{"$i16":-500}"#);
+        round_trip(Value::from(-5i32), r#"{"$i32":-5}"#);
+        round_trip(Value::from(i64::MIN), r#"{"$i64":-9223372036854775808}"#);
+        round_trip(Value::from(1.5f32), r#"{"$f32":1.5}"#);
+        round_trip(Value::from(1.5f64), "1.5");
+        round_trip(
+            Value::Bytes(Bytes::from(&b"hi"[..])),
+            r#"{"$bytes":"aGk="}"#,
+        );
+        round_trip(Value::Date(date), r#"{"$date":"2024-01-02"}"#);
+        round_trip(
+            Value::DateTime(date.and_time(time)),
+            r#"{"$datetime":"2024-01-02T12:30:00.500"}"#,
+        );
+        round_trip(Value::Time(time), r#"{"$time":"12:30:00.500"}"#);
+        round_trip(
+            Value::Uuid(uuid),
+            r#"{"$uuid":"67e55044-10b1-426f-9247-bb680e5fe0c8"}"#,
+        );
+        round_trip(Value::Char('é'), r#"{"$char":"é"}"#);
+        round_trip(Value::String("2024-01-02".into()), r#""2024-01-02""#);
+        round_trip(Value::Null, "null");
+        round_trip(
+            Value::List(vec![Value::Bool(true), Value::from(1u8)].into()),
+            r#"[true,{"$u8":1}]"#,
+        );
+    }
+
+    #[test]
+    fn maps_are_escaped() {
+        round_trip(
+            Value::Map(map([("$u8", Value::String("not a tag".into()))])),
+            r#"{"$map":{"$u8":"not a tag"}}"#,
+        );
+        round_trip(
+            Value::Map(map([("$other", Value::Null)])),
+            r#"{"$map":{"$other":null}}"#,
+        );
+        round_trip(
+            Value::Map(map([(
+                "$map",
+                Value::Map(map([("$u8", Value::from(5u8))])),
+            )])),
+            r#"{"$map":{"$map":{"$map":{"$u8":{"$u8":5}}}}}"#,
+        );
+
+        // Not the shape of a tag
+        round_trip(
+            Value::Map(map([("plain", Value::from(1u8))])),
+            r#"{"plain":{"$u8":1}}"#,
+        );
+
+        // Unknown tags and other maps are read as they are
+        let value = read(r#"{"$unknown": 1, "$u8": 2}"#).unwrap();
+        assert_eq!(value.as_map().map(Map::len), Some(2));
+        let value = read(r#"{"$unknown": 1}"#).unwrap();
+        assert_eq!(value, Value::Map(map([("$unknown", Value::from(1u8))])));
+    }
+
+    #[test]
+    fn non_finite_floats() {
+        round_trip(Value::from(f32::INFINITY), r#"{"$f32":"Infinity"}"#);
+        round_trip(Value::from(f32::NEG_INFINITY), r#"{"$f32":"-Infinity"}"#);
+        round_trip(Value::from(f64::INFINITY), r#"{"$f64":"Infinity"}"#);
+        round_trip(Value::from(f64::NEG_INFINITY), r#"{"$f64":"-Infinity"}"#);
+
+        assert_eq!(write(&Value::from(f32::NAN)), r#"{"$f32":"NaN"}"#);
+        assert_eq!(write(&Value::from(f64::NAN)), r#"{"$f64":"NaN"}"#);
+        assert!(matches!(
+            read(r#"{"$f32":"NaN"}"#).unwrap(),
+            Value::Number(Number::F32(n)) if n.is_nan()
+        ));
+        assert!(matches!(
+            read(r#"{"$f64":"NaN"}"#).unwrap(),
+            Value::Number(Number::F64(n)) if n.is_nan()
+        ));
+    }
+
+    #[test]
+    fn datetime_nanoseconds() {
+        let datetime = NaiveDate::from_ymd_opt(2024, 1, 2)
+            .unwrap()
+            .and_hms_nano_opt(12, 30, 0, 123_456_789)
+            .unwrap();
+        round_trip(
+            Value::DateTime(datetime),
+            r#"{"$datetime":"2024-01-02T12:30:00.123456789"}"#,
+        );
+
+        let time = NaiveTime::from_hms_nano_opt(0, 0, 1, 1).unwrap();
+        round_trip(Value::Time(time), r#"{"$time":"00:00:01.000000001"}"#);
+    }
+
+    #[test]
+    fn invalid_tags() {
+        let invalid: Vec<&str> = vec![
+            r#"{"$u8": 256}"#,
+            r#"{"$u8": -1}"#,
+            r#"{"$i8": 1.5}"#,
+            r#"{"$u64": "1"}"#,
+            r#"{"$f64": "inf"}"#,
+            r#"{"$f32": null}"#,
+            r#"{"$bytes": "not base64!"}"#,
+            r#"{"$date": "2024-13-01"}"#,
+            r#"{"$datetime": "2024-01-02"}"#,
+            r#"{"$time": "25:00:00"}"#,
+            r#"{"$uuid": "67e55044"}"#,
+            r#"{"$char": "ab"}"#,
+            r#"{"$char": ""}"#,
+            r#"{"$map": 1}"#,
+            r#"[{"$date": 1}]"#,
+        ];
+
+        for input in invalid {
+            assert_eq!(read(input), Err(ParseErrorKind::InvalidExtended), "{input}");
+        }
+
+        let err = parse_with(
+            "[1,\n {\"$u8\": 256}]",
+            &ParseOptions::default().extended(true),
+        )
+        .unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 2));
+    }
+
+    #[test]
+    fn without_the_option() {
+        // Written as plain JSON and read as plain maps
+        let value = Value::from(5u8);
+        assert_eq!(
+            to_string_with(&value, &DisplayOptions::default()).unwrap(),
+            "5"
+        );
+        let value = parse_with(r#"{"$u8": 5}"#, &ParseOptions::default()).unwrap();
+        assert_eq!(value, Value::Map(map([("$u8", Value::from(5u8))])));
+    }
+}
@@ -0,0 +1,401 @@
+//! The parts of the JSON5 grammar that differ from JSON, see <https://spec.json5.org>
+use alloc::string::String;
+
+use super::{
+    error::ParseErrorKind,
+    parse::{decode_unicode_escape, number_from_text, signed, unsigned},
+};
+use crate::Number;
+
+/// The length of the whitespace and comments at the start of `input`.
+/// An unterminated block comment runs to the end of the input.
+pub(super) fn space(input: &str) -> usize {
+    let mut idx = 0;
+
+    loop {
+        let rest = &input[idx..];
+        if let Some(comment) = rest.strip_prefix("//") {
+            idx += 2 + comment.find(is_line_terminator).unwrap_or(comment.len());
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            idx += 2 + comment.find("*/").map_or(comment.len(), |end| end + 2);
+        } else {
+            match rest.chars().next() {
+                Some(ch) if is_space(ch) => idx += ch.len_utf8(),
+                _ => return idx,
+            }
+        }
+    }
+}
+
+fn is_space(ch: char) -> bool {
+    match ch {
+        '\t' | '\u{0B}' | '\u{0C}' | ' ' | '\u{A0}' | '\u{FEFF}' => true,
+        // The other space separators of unicode
+        '\u{1680}' | '\u{2000}'..='\u{200A}' | '\u{202F}' | '\u{205F}' | '\u{3000}' => true,
+        ch => is_line_terminator(ch),
+    }
+}
+
+fn is_line_terminator(ch: char) -> bool {
+    matches!(ch, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
+
+/// The length of the unquoted key at the start of `input`, 0 if there is none.
+///
+/// Keys are letters, digits, `$` and `_`, not starting with a digit.
+/// Unicode escapes in keys are not supported.
+pub(super) fn identifier(input: &str) -> usize {
+    let mut chars = input.char_indices();
+
+    match chars.next() {
+        Some((_, ch)) if ch.is_alphabetic() || ch == '$' || ch == '_' => {}
+        _ => return 0,
+    }
+
+    chars
+        .find(|&(_, ch)| {
+            !(ch.is_alphanumeric() || matches!(ch, '$' | '_' | '\u{200C}' | '\u{200D}'))
+        })
+        .map_or(input.len(), |(idx, _)| idx)
+}
+
+/// The number at the start of `input` and its length, or the index of the
+/// first invalid byte.
+///
+/// On top of JSON numbers these can have a leading `+`, a leading or
+/// trailing decimal point, be hexadecimal or be `Infinity` or `NaN`.
+pub(super) fn number(input: &str) -> Result<(Number, usize), usize> {
+    let bytes = input.as_bytes();
+    let negative = bytes.first() == Some(&b'-');
+    let start = usize::from(matches!(bytes.first(), Some(b'+' | b'-')));
+    let rest = &input[start..];
+
+    if rest.starts_with("Infinity") {
+        let value = if negative {
+            f64::NEG_INFINITY
+        } else {
+            f64::INFINITY
+        };
+        return Ok((Number::F64(value), start + 8));
+    }
+
+    if rest.starts_with("NaN") {
+        return Ok((Number::F64(f64::NAN), start + 3));
+    }
+
+    if let Some(digits) = rest.strip_prefix("0x").or_else(|| rest.strip_prefix("0X")) {
+        let len = digits
+            .bytes()
+            .take_while(|byte| byte.is_ascii_hexdigit())
+            .count();
+        if len == 0 {
+            return Err(start + 2);
+        }
+        return Ok((hex(&digits[..len], negative), start + 2 + len));
+    }
+
+    let digits = |mut idx: usize| {
+        while let Some(b'0'..=b'9') = bytes.get(idx) {
+            idx += 1;
+        }
+        idx
+    };
+
+    let mut idx = match bytes.get(start) {
+        Some(b'0') => start + 1,
+        Some(b'1'..=b'9') => digits(start),
+        Some(b'.') => start,
+        _ => return Err(start),
+    };
+
+    if bytes.get(idx) == Some(&b'.') {
+        let end = digits(idx + 1);
+        // A lone point is not a number
+        if idx == start && end == idx + 1 {
+            return Err(end);
+        }
+        idx = end;
+    }
+
+    if let Some(b'e' | b'E') = bytes.get(idx) {
+        idx += 1;
+        if let Some(b'+' | b'-') = bytes.get(idx) {
+            idx += 1;
+        }
+        if !matches!(bytes.get(idx), Some(b'0'..=b'9')) {
+            return Err(idx);
+        }
+        idx = digits(idx);
+    }
+
+    let text = if negative {
+        &input[..idx]
+    } else {
+        &input[start..idx]
+    };
+
+    Ok((number_from_text(text), idx))
+}
+
+fn hex(digits: &str, negative: bool) -> Number {
+    match u64::from_str_radix(digits, 16) {
+        Ok(value) if !negative => unsigned(value),
+        Ok(value) if value <= i64::MAX as u64 + 1 => signed((value as i64).wrapping_neg()),
+        _ => {
+            let value = digits.bytes().fold(0.0, |acc, byte| {
+                acc * 16.0 + (byte as char).to_digit(16).unwrap_or(0) as f64
+            });
+            Number::F64(if negative { -value } else { value })
+        }
+    }
+}
+
+/// The string quoted by the first character of `input` and the length of it
+/// with the quotes. Errors hold the index of the offending byte.
+pub(super) fn string(input: &str) -> Result<(String, usize), (ParseErrorKind, usize)> {
+    let bytes = input.as_bytes();
+    let quote = bytes[0];
+    let mut output = String::new();
+    let mut start = 1;
+    let mut idx = 1;
+
+    loop {
+        match bytes.get(idx) {
+            None => return Err((ParseErrorKind::Eof, idx)),
+            Some(&byte) if byte == quote => {
+                output.push_str(&input[start..idx]);
+                return Ok((output, idx + 1));
+            }
+            Some(b'\\') => {
+                output.push_str(&input[start..idx]);
+                idx += escape(&input[idx..], &mut output).map_err(|(kind, at)| (kind, idx + at))?;
+                start = idx;
+            }
+            Some(b'\n' | b'\r') => return Err((ParseErrorKind::ControlCharacter, idx)),
+            Some(_) => idx += 1,
+        }
+    }
+}
+
+/// Decode the escape at the start of `input` into `output`, returns its length
+fn escape(input: &str, output: &mut String) -> Result<usize, (ParseErrorKind, usize)> {
+    let Some(ch) = input[1..].chars().next() else {
+        return Err((ParseErrorKind::Eof, 1));
+    };
+
+    let decoded = match ch {
+        'b' => '\u{08}',
+        'f' => '\u{0C}',
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        'v' => '\u{0B}',
+        '0' if !matches!(input.as_bytes().get(2), Some(b'0'..=b'9')) => '\0',
+        '0'..='9' => return Err((ParseErrorKind::InvalidEscape, 1)),
+        'x' => {
+            let code = input
+                .get(2..4)
+                .filter(|code| code.bytes().all(|byte| byte.is_ascii_hexdigit()))
+                .and_then(|code| u8::from_str_radix(code, 16).ok());
+            match code {
+                Some(code) => {
+                    output.push(code as char);
+                    return Ok(4);
+                }
+                None if input.len() < 4 => return Err((ParseErrorKind::Eof, input.len())),
+                None => return Err((ParseErrorKind::InvalidEscape, 1)),
+            }
+        }
+        'u' => {
+            let (ch, len) = decode_unicode_escape(input.as_bytes())?;
+            output.push(ch);
+            return Ok(len);
+        }
+        // An escaped line terminator continues the string on the next line
+        '\r' if input.as_bytes().get(2) == Some(&b'\n') => return Ok(3),
+        ch if is_line_terminator(ch) => return Ok(1 + ch.len_utf8()),
+        ch => ch,
+    };
+
+    output.push(decoded);
+    Ok(1 + ch.len_utf8())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::{
+        json::{parse, parse_with, ParseOptions},
+        Map, Value,
+    };
+
+    fn json5(input: &str) -> Value {
+        parse_with(input, &ParseOptions::relaxed()).unwrap()
+    }
+
+    fn error(input: &str) -> (ParseErrorKind, usize, usize) {
+        let err = parse_with(input, &ParseOptions::relaxed()).unwrap_err();
+        (err.kind().clone(), err.line(), err.column())
+    }
+
+    fn string(value: &str) -> Value {
+        Value::String(value.into())
+    }
+
+    fn number(value: &str) -> Number {
+        match json5(value) {
+            Value::Number(n) => n,
+            value => panic!("expected a number, found {value:?}"),
+        }
+    }
+
+    #[test]
+    fn comments() {
+        let input =
+            "// leading\n/* block\n comment */ [1, // one\n 2 /* two */, /**/3] // trailing";
+        assert_eq!(json5(input), parse("[1, 2, 3]").unwrap());
+        assert_eq!(json5("1 /* unterminated"), Value::from(1u8));
+        assert_eq!(
+            json5("\u{FEFF}\u{A0}\u{2028}true\u{3000}"),
+            Value::Bool(true)
+        );
+    }
+
+    #[test]
+    fn trailing_commas() {
+        assert_eq!(json5("[1, 2,]"), parse("[1, 2]").unwrap());
+        assert_eq!(json5("{\"a\": 1,}"), parse("{\"a\": 1}").unwrap());
+        assert_eq!(
+            error("[1,,]"),
+            (
+                ParseErrorKind::Unexpected {
+                    found: ',',
+                    expected: "a value"
+                },
+                1,
+                4
+            )
+        );
+        assert!(parse_with("[,]", &ParseOptions::relaxed()).is_err());
+    }
+
+    #[test]
+    fn keys() {
+        let value = json5("{unquoted: 1, $dollar_1: 2, 'single': 3, \"double\": 4, ünï: 5}");
+        let mut map = Map::default();
+        for (idx, key) in ["unquoted", "$dollar_1", "single", "double", "ünï"]
+            .into_iter()
+            .enumerate()
+        {
+            map.insert(key, Value::from(idx as u8 + 1));
+        }
+        assert_eq!(value, Value::Map(map));
+
+        assert_eq!(identifier("a1 "), 2);
+        assert_eq!(identifier("1a"), 0);
+        assert_eq!(
+            error("{1a: 1}"),
+            (
+                ParseErrorKind::Unexpected {
+                    found: '1',
+                    expected: "a key"
+                },
+                1,
+                2
+            )
+        );
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(json5(r#"'say "hi"'"#), string("say \"hi\""));
+        assert_eq!(json5(r#""it's""#), string("it's"));
+        assert_eq!(json5(r"'\''"), string("'"));
+        assert_eq!(json5(r"'\x41\xe9'"), string("Aé"));
+        assert_eq!(json5(r"'\v\0\a'"), string("\u{0B}\0a"));
+        assert_eq!(json5(r"'é'"), string("é"));
+        assert_eq!(json5("'line \\\ncontinued'"), string("line continued"));
+        assert_eq!(json5("'line \\\r\ncontinued'"), string("line continued"));
+        assert_eq!(
+            json5("'line \\\u{2028}continued'"),
+            string("line continued")
+        );
+    }
+
+    #[test]
+    fn string_errors() {
+        assert_eq!(error(r"'\x4'"), (ParseErrorKind::InvalidEscape, 1, 3));
+        assert_eq!(error(r"'\x4"), (ParseErrorKind::Eof, 1, 5));
+        assert_eq!(error(r"'\01'"), (ParseErrorKind::InvalidEscape, 1, 3));
+        assert_eq!(error("'a\nb'"), (ParseErrorKind::ControlCharacter, 1, 3));
+        assert_eq!(error("'open"), (ParseErrorKind::Eof, 1, 6));
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(number("0x1F"), Number::U8(31));
+        assert_eq!(number("0XfF"), Number::U8(255));
+        assert_eq!(number("-0x10"), Number::I8(-16));
+        assert_eq!(
+            number("0x10000000000000000"),
+            Number::F64(18446744073709551616.0)
+        );
+        assert_eq!(number("+1"), Number::U8(1));
+        assert_eq!(number(".5"), Number::F64(0.5));
+        assert_eq!(number("5."), Number::F64(5.0));
+        assert_eq!(number("-.5e1"), Number::F64(-5.0));
+        assert_eq!(number("Infinity"), Number::F64(f64::INFINITY));
+        assert_eq!(number("-Infinity"), Number::F64(f64::NEG_INFINITY));
+        assert_eq!(number("+Infinity"), Number::F64(f64::INFINITY));
+        assert!(matches!(number("NaN"), Number::F64(n) if n.is_nan()));
+        assert!(matches!(number("-NaN"), Number::F64(n) if n.is_nan()));
+
+        assert_eq!(error("0x"), (ParseErrorKind::InvalidNumber, 1, 3));
+        assert_eq!(error("."), (ParseErrorKind::InvalidNumber, 1, 2));
+        assert_eq!(error("[1, +]"), (ParseErrorKind::InvalidNumber, 1, 6));
+        assert_eq!(error("1e"), (ParseErrorKind::InvalidNumber, 1, 3));
+    }
+
+    #[test]
+    fn error_location() {
+        let input = "{\n  // a comment\n  key: 'value',\n  other: [1, 2,, 3],\n}";
+        assert_eq!(
+            error(input),
+            (
+                ParseErrorKind::Unexpected {
+                    found: ',',
+                    expected: "a value"
+                },
+                4,
+                16
+            )
+        );
+    }
+
+    #[test]
+    fn plain_json_rejects_json5() {
+        for input in [
+            "// comment\n1",
+            "[1,]",
+            "{a: 1}",
+            "'single'",
+            "0x10",
+            "Infinity",
+            "NaN",
+            ".5",
+            "+1",
+        ] {
+            assert!(parse(input).is_err(), "{input}");
+            assert!(
+                parse_with(input, &ParseOptions::relaxed()).is_ok(),
+                "{input}"
+            );
+        }
+
+        let mut map = Map::default();
+        map.insert("a", Value::List(vec![Value::from(1u8), string("b")].into()));
+        assert_eq!(json5("{a: [1, 'b',], /* c */}"), Value::Map(map));
+    }
+}
@@ -0,0 +1,642 @@
+//! Newline delimited JSON, one value per line.
+//!
+//! ```ignore
+//! let reader = lines::Reader::new(BufReader::new(file)).skip_invalid(true);
+//! for value in reader {
+//!     let value = value?;
+//! }
+//! ```
+use alloc::vec::Vec;
+use core::{fmt, str};
+use std::io::{self, BufRead, Write};
+
+use super::{
+    error::{ParseError, ParseErrorKind, WriteError},
+    options::ParseOptions,
+    parse::parse_with,
+    to_string::{to_writer, DisplayOptions},
+};
+use crate::Value;
+
+#[derive(Debug)]
+pub enum ErrorKind {
+    Io(io::Error),
+    /// The line is not valid JSON, the location is within the line
+    Parse(ParseError),
+}
+
+/// An error reading a line
+#[derive(Debug)]
+pub struct Error {
+    line: usize,
+    kind: ErrorKind,
+}
+
+impl Error {
+    /// The line of the error, starting at 1
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    pub fn into_kind(self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ErrorKind::Io(err) => write!(f, "line {}: {err}", self.line),
+            ErrorKind::Parse(err) => write!(
+                f,
+                "line {}: {} at column {}",
+                self.line,
+                err.kind(),
+                err.column()
+            ),
+        }
+    }
+}
+
+impl avagarden::error::Error for Error {}
+
+/// The state shared by the readers
+struct Lines {
+    options: ParseOptions,
+    skip_invalid: bool,
+    line: usize,
+    skipped: usize,
+}
+
+impl Lines {
+    fn new() -> Lines {
+        Lines {
+            options: ParseOptions::default(),
+            skip_invalid: false,
+            line: 0,
+            skipped: 0,
+        }
+    }
+
+    fn io_error(&self, error: io::Error) -> Error {
+        Error {
+            line: self.line + 1,
+            kind: ErrorKind::Io(error),
+        }
+    }
+
+    /// Parse the next line, `None` when it is blank or skipped
+    fn parse(&mut self, line: &[u8]) -> Option<Result<Value, Error>> {
+        self.line += 1;
+
+        let ret = match str::from_utf8(line) {
+            Ok(line) => {
+                let line = line.trim_end_matches(['\n', '\r']);
+                if line.trim().is_empty() {
+                    return None;
+                }
+                parse_with(line, &self.options)
+            }
+            Err(err) => {
+                let valid = str::from_utf8(&line[..err.valid_up_to()]).unwrap_or_default();
+                Err(ParseError::new(
+                    ParseErrorKind::InvalidUtf8,
+                    valid,
+                    valid.len(),
+                ))
+            }
+        };
+
+        match ret {
+            Ok(value) => Some(Ok(value)),
+            Err(_) if self.skip_invalid => {
+                self.skipped += 1;
+                None
+            }
+            Err(err) => Some(Err(Error {
+                line: self.line,
+                kind: ErrorKind::Parse(err),
+            })),
+        }
+    }
+}
+
+/// Iterates over the values of newline delimited JSON.
+/// Blank lines are ignored.
+pub struct Reader<R> {
+    reader: R,
+    lines: Lines,
+    buf: Vec<u8>,
+}
+
+impl<R: BufRead> Reader<R> {
+    pub fn new(reader: R) -> Reader<R> {
+        Reader {
+            reader,
+            lines: Lines::new(),
+            buf: Vec::new(),
+        }
+    }
+
+    /// Parse every line with `options`
+    pub fn parse_options(mut self, options: ParseOptions) -> Self {
+        self.lines.options = options;
+        self
+    }
+
+    /// Skip the lines that are not valid JSON instead of failing on them
+    pub fn skip_invalid(mut self, skip: bool) -> Self {
+        self.lines.skip_invalid = skip;
+        self
+    }
+
+    /// The number of lines read so far
+    pub fn line(&self) -> usize {
+        self.lines.line
+    }
+
+    /// The number of invalid lines skipped so far
+    pub fn skipped(&self) -> usize {
+        self.lines.skipped
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: BufRead> Iterator for Reader<R> {
+    type Item = Result<Value, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.buf.clear();
+            match self.reader.read_until(b'\n', &mut self.buf) {
+                Ok(0) => return None,
+                Ok(_) => {
+                    if let Some(ret) = self.lines.parse(&self.buf) {
+                        return Some(ret);
+                    }
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Some(Err(self.lines.io_error(err))),
+            }
+        }
+    }
+}
+
+/// Writes values as compact JSON, one per line
+pub struct Writer<W> {
+    writer: W,
+    options: DisplayOptions,
+    /// The line being encoded, so a value that fails leaves nothing behind
+    buf: Vec<u8>,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(writer: W) -> Writer<W> {
+        Writer::with_options(writer, DisplayOptions::default())
+    }
+
+    /// Write with `options`, the indentation is ignored to keep every value on one line
+    pub fn with_options(writer: W, options: DisplayOptions) -> Writer<W> {
+        Writer {
+            writer,
+            options: options.compact(),
+            buf: Vec::new(),
+        }
+    }
+
+    /// Write `value` on a line of its own. A value that cannot be written,
+    /// like NaN with `NonFinite::Error`, writes nothing.
+    pub fn write(&mut self, value: &Value) -> Result<(), WriteError> {
+        self.buf.clear();
+        to_writer(&mut self.buf, value, &self.options)?;
+        self.buf.push(b'\n');
+        self.writer.write_all(&self.buf)?;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), WriteError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(feature = "async")]
+pub use self::stream::{AsyncReader, AsyncWriter};
+
+#[cfg(feature = "async")]
+mod stream {
+    use alloc::vec::Vec;
+    use core::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+    use futures_core::{ready, Stream};
+    use futures_io::{AsyncBufRead, AsyncWrite};
+    use futures_sink::Sink;
+
+    use super::{Error, Lines};
+    use crate::{
+        json::{
+            error::WriteError,
+            options::ParseOptions,
+            to_string::{to_writer, DisplayOptions},
+        },
+        Value,
+    };
+
+    /// How much the writer buffers before it writes to the output
+    const BUFFER_SIZE: usize = 8 * 1024;
+
+    /// A `Stream` of the values of newline delimited JSON, see [`Reader`](super::Reader)
+    pub struct AsyncReader<R> {
+        reader: R,
+        lines: Lines,
+        buf: Vec<u8>,
+    }
+
+    impl<R: AsyncBufRead + Unpin> AsyncReader<R> {
+        pub fn new(reader: R) -> AsyncReader<R> {
+            AsyncReader {
+                reader,
+                lines: Lines::new(),
+                buf: Vec::new(),
+            }
+        }
+
+        /// Parse every line with `options`
+        pub fn parse_options(mut self, options: ParseOptions) -> Self {
+            self.lines.options = options;
+            self
+        }
+
+        /// Skip the lines that are not valid JSON instead of failing on them
+        pub fn skip_invalid(mut self, skip: bool) -> Self {
+            self.lines.skip_invalid = skip;
+            self
+        }
+
+        /// The number of lines read so far
+        pub fn line(&self) -> usize {
+            self.lines.line
+        }
+
+        /// The number of invalid lines skipped so far
+        pub fn skipped(&self) -> usize {
+            self.lines.skipped
+        }
+
+        pub fn into_inner(self) -> R {
+            self.reader
+        }
+    }
+
+    impl<R: AsyncBufRead + Unpin> Stream for AsyncReader<R> {
+        type Item = Result<Value, Error>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let this = self.get_mut();
+
+            loop {
+                let available = match ready!(Pin::new(&mut this.reader).poll_fill_buf(cx)) {
+                    Ok(available) => available,
+                    Err(err) => return Poll::Ready(Some(Err(this.lines.io_error(err)))),
+                };
+
+                if available.is_empty() {
+                    if this.buf.is_empty() {
+                        return Poll::Ready(None);
+                    }
+                } else {
+                    let (len, done) = match available.iter().position(|&b| b == b'\n') {
+                        Some(idx) => (idx + 1, true),
+                        None => (available.len(), false),
+                    };
+                    this.buf.extend_from_slice(&available[..len]);
+                    Pin::new(&mut this.reader).consume(len);
+
+                    if !done {
+                        continue;
+                    }
+                }
+
+                let ret = this.lines.parse(&this.buf);
+                this.buf.clear();
+                if let Some(ret) = ret {
+                    return Poll::Ready(Some(ret));
+                }
+            }
+        }
+    }
+
+    /// A `Sink` writing values as compact JSON, one per line, see [`Writer`](super::Writer)
+    pub struct AsyncWriter<W> {
+        writer: W,
+        options: DisplayOptions,
+        buf: Vec<u8>,
+        written: usize,
+    }
+
+    impl<W: AsyncWrite + Unpin> AsyncWriter<W> {
+        pub fn new(writer: W) -> AsyncWriter<W> {
+            AsyncWriter::with_options(writer, DisplayOptions::default())
+        }
+
+        /// Write with `options`, the indentation is ignored to keep every value on one line
+        pub fn with_options(writer: W, options: DisplayOptions) -> AsyncWriter<W> {
+            AsyncWriter {
+                writer,
+                options: options.compact(),
+                buf: Vec::new(),
+                written: 0,
+            }
+        }
+
+        pub fn into_inner(self) -> W {
+            self.writer
+        }
+
+        /// Append the line of `value` to the buffer, or nothing when it fails
+        fn encode(&mut self, value: &Value) -> Result<(), WriteError> {
+            let len = self.buf.len();
+            if let Err(err) = to_writer(&mut self.buf, value, &self.options) {
+                self.buf.truncate(len);
+                return Err(err);
+            }
+            self.buf.push(b'\n');
+            Ok(())
+        }
+
+        /// Write out everything buffered
+        fn poll_write_buf(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), WriteError>> {
+            while self.written < self.buf.len() {
+                let len =
+                    ready!(Pin::new(&mut self.writer).poll_write(cx, &self.buf[self.written..]))?;
+                if len == 0 {
+                    return Poll::Ready(Err(
+                        std::io::Error::from(std::io::ErrorKind::WriteZero).into()
+                    ));
+                }
+                self.written += len;
+            }
+
+            self.buf.clear();
+            self.written = 0;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl<W: AsyncWrite + Unpin> Sink<Value> for AsyncWriter<W> {
+        type Error = WriteError;
+
+        fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), WriteError>> {
+            let this = self.get_mut();
+            if this.buf.len() >= BUFFER_SIZE {
+                this.poll_write_buf(cx)
+            } else {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        fn start_send(self: Pin<&mut Self>, item: Value) -> Result<(), WriteError> {
+            self.get_mut().encode(&item)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), WriteError>> {
+            let this = self.get_mut();
+            ready!(this.poll_write_buf(cx))?;
+            Poll::Ready(Ok(ready!(Pin::new(&mut this.writer).poll_flush(cx))?))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), WriteError>> {
+            let this = self.get_mut();
+            ready!(this.poll_write_buf(cx))?;
+            Poll::Ready(Ok(ready!(Pin::new(&mut this.writer).poll_close(cx))?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec};
+    use std::string::ToString;
+
+    use super::*;
+    use crate::json::{parse, NonFinite};
+
+    const INPUT: &str = "{\"a\": 1}\r\n\n  \r\n[true]\n*\n\"last\"";
+
+    fn json(input: &str) -> Value {
+        parse(input).unwrap()
+    }
+
+    #[test]
+    fn read() {
+        let mut reader = Reader::new(INPUT.as_bytes());
+        assert_eq!(reader.next().unwrap().unwrap(), json("{\"a\": 1}"));
+        assert_eq!(reader.line(), 1);
+        // Blank lines are counted but skipped
+        assert_eq!(reader.next().unwrap().unwrap(), json("[true]"));
+        assert_eq!(reader.line(), 4);
+
+        let err = reader.next().unwrap().unwrap_err();
+        assert_eq!(err.line(), 5);
+        assert!(matches!(err.kind(), ErrorKind::Parse(err) if err.column() == 1));
+        assert_eq!(
+            err.to_string(),
+            "line 5: expected a value, found '*' at column 1"
+        );
+
+        // The reader goes on after an error
+        assert_eq!(reader.next().unwrap().unwrap(), json("\"last\""));
+        assert!(reader.next().is_none());
+        assert_eq!((reader.line(), reader.skipped()), (6, 0));
+    }
+
+    #[test]
+    fn skip_invalid() {
+        let input = "1\n[\n2\n\u{0}\n3\n";
+        let mut reader = Reader::new(input.as_bytes()).skip_invalid(true);
+        let values = reader.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(values, [json("1"), json("2"), json("3")]);
+        assert_eq!((reader.line(), reader.skipped()), (5, 2));
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let input = &b"1\n\"a\xff\"\n"[..];
+        let err = Reader::new(input).nth(1).unwrap().unwrap_err();
+        assert_eq!(err.line(), 2);
+        assert!(
+            matches!(err.kind(), ErrorKind::Parse(err) if err.kind() == &ParseErrorKind::InvalidUtf8)
+        );
+    }
+
+    #[test]
+    fn parse_options() {
+        let input = "\"2024-01-02\"\n";
+        let options = ParseOptions::default().detect_date(true);
+        let value = Reader::new(input.as_bytes())
+            .parse_options(options)
+            .next()
+            .unwrap()
+            .unwrap();
+        assert!(value.is_date());
+    }
+
+    #[test]
+    fn write() {
+        let mut writer = Writer::with_options(Vec::new(), DisplayOptions::pretty());
+        writer.write(&json("{\"a\": [1, 2]}")).unwrap();
+        writer.write(&json("null")).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.into_inner(), b"{\"a\":[1,2]}\nnull\n");
+    }
+
+    #[test]
+    fn failed_writes_leave_no_partial_line() {
+        let options = DisplayOptions::default().non_finite(NonFinite::Error);
+        let mut writer = Writer::with_options(Vec::new(), options);
+        writer.write(&json("1")).unwrap();
+
+        let value = Value::List(vec![Value::from(1), Value::from(f64::NAN)].into());
+        assert!(matches!(
+            writer.write(&value),
+            Err(WriteError::NonFinite(_))
+        ));
+
+        writer.write(&json("2")).unwrap();
+        assert_eq!(writer.into_inner(), b"1\n2\n");
+    }
+
+    #[cfg(feature = "async")]
+    mod stream {
+        use core::{
+            future::{poll_fn, Future},
+            pin::{pin, Pin},
+            task::{Context, Poll, Waker},
+        };
+        use futures_core::Stream;
+        use futures_io::{AsyncBufRead, AsyncRead};
+        use futures_sink::Sink;
+
+        use super::*;
+
+        fn block_on<F: Future>(future: F) -> F::Output {
+            let mut future = pin!(future);
+            let mut cx = Context::from_waker(Waker::noop());
+            loop {
+                if let Poll::Ready(ret) = future.as_mut().poll(&mut cx) {
+                    return ret;
+                }
+            }
+        }
+
+        /// Hands out a byte at a time and is pending every other poll
+        struct Trickle<'a> {
+            input: &'a [u8],
+            pending: bool,
+        }
+
+        impl AsyncRead for Trickle<'_> {
+            fn poll_read(
+                mut self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &mut [u8],
+            ) -> Poll<std::io::Result<usize>> {
+                let available = core::task::ready!(self.as_mut().poll_fill_buf(cx))?;
+                let len = available.len().min(buf.len());
+                buf[..len].copy_from_slice(&available[..len]);
+                self.consume(len);
+                Poll::Ready(Ok(len))
+            }
+        }
+
+        impl AsyncBufRead for Trickle<'_> {
+            fn poll_fill_buf(
+                self: Pin<&mut Self>,
+                _: &mut Context<'_>,
+            ) -> Poll<std::io::Result<&[u8]>> {
+                let this = self.get_mut();
+                this.pending = !this.pending;
+                if this.pending {
+                    return Poll::Pending;
+                }
+                Poll::Ready(Ok(&this.input[..this.input.len().min(1)]))
+            }
+
+            fn consume(self: Pin<&mut Self>, amt: usize) {
+                let this = self.get_mut();
+                this.input = &this.input[amt..];
+            }
+        }
+
+        fn collect<R: AsyncBufRead + Unpin>(
+            reader: &mut AsyncReader<R>,
+        ) -> Vec<Result<Value, String>> {
+            let mut ret = Vec::new();
+            while let Some(value) = block_on(poll_fn(|cx| Pin::new(&mut *reader).poll_next(cx))) {
+                ret.push(value.map_err(|err| err.to_string()));
+            }
+            ret
+        }
+
+        #[test]
+        fn read() {
+            let mut reader = AsyncReader::new(Trickle {
+                input: INPUT.as_bytes(),
+                pending: false,
+            });
+            assert_eq!(
+                collect(&mut reader),
+                [
+                    Ok(json("{\"a\": 1}")),
+                    Ok(json("[true]")),
+                    Err(String::from(
+                        "line 5: expected a value, found '*' at column 1"
+                    )),
+                    Ok(json("\"last\"")),
+                ]
+            );
+            assert_eq!((reader.line(), reader.skipped()), (6, 0));
+
+            let mut reader = AsyncReader::new(INPUT.as_bytes()).skip_invalid(true);
+            assert_eq!(collect(&mut reader).len(), 3);
+            assert_eq!((reader.line(), reader.skipped()), (6, 1));
+        }
+
+        fn send(writer: &mut AsyncWriter<Vec<u8>>, value: Value) -> Result<(), WriteError> {
+            block_on(poll_fn(|cx| Pin::new(&mut *writer).poll_ready(cx)))?;
+            Pin::new(&mut *writer).start_send(value)
+        }
+
+        #[test]
+        fn write() {
+            let options = DisplayOptions::pretty().non_finite(NonFinite::Error);
+            let mut writer = AsyncWriter::with_options(Vec::new(), options);
+
+            send(&mut writer, json("{\"a\": [1, 2]}")).unwrap();
+            assert!(matches!(
+                send(&mut writer, Value::from(f64::INFINITY)),
+                Err(WriteError::NonFinite(_))
+            ));
+            send(&mut writer, json("null")).unwrap();
+
+            block_on(poll_fn(|cx| Pin::new(&mut writer).poll_flush(cx))).unwrap();
+
+            send(&mut writer, json("true")).unwrap();
+            block_on(poll_fn(|cx| Pin::new(&mut writer).poll_close(cx))).unwrap();
+            assert_eq!(writer.into_inner(), b"{\"a\":[1,2]}\nnull\ntrue\n");
+        }
+    }
+}
@@ -1,8 +1,30 @@
+mod canonical;
+mod emitter;
+mod error;
+mod extended;
+mod json5;
+#[cfg(feature = "std")]
+pub mod lines;
+mod options;
 mod parse;
+mod raw;
+mod reader;
 mod to_string;
 mod value;
 pub use self::{
-    parse::parse,
-    to_string::{display, to_string},
-    value::*,
+    canonical::to_canonical_string,
+    emitter::{FmtOutput, JsonEmitter, Output},
+    error::{ParseError, ParseErrorKind, WriteError},
+    options::{Detector, ParseOptions},
+    parse::{parse, parse_with},
+    raw::RawJson,
+    reader::{ArrayValues, ChunkSource, Event, JsonReader, Source},
+    to_string::{
+        display, display_list, display_object, display_with, to_fmt_writer, to_string,
+        to_string_with, BytesEncoding, DisplayOptions, NonFinite, TemporalEncoding, ToJson,
+    },
+    value::{parse_json_value, parse_json_value_with_limits, JsonValue},
 };
+
+#[cfg(feature = "std")]
+pub use self::{emitter::IoOutput, reader::IoSource, to_string::to_writer};
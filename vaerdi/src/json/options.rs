@@ -0,0 +1,543 @@
+use alloc::{sync::Arc, vec::Vec};
+use base64::Engine;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use core::fmt;
+
+use crate::{
+    bytes::Bytes,
+    limits::Limits,
+    schema::{EnumRepr, TypeSchema, ValueSchema},
+    Number, Type, Value,
+};
+
+/// A custom detector, returns `None` to leave the string alone
+pub type Detector = Arc<dyn Fn(&str) -> Option<Value> + Send + Sync>;
+
+/// Decides which `Value` a JSON string or number becomes.
+///
+/// By default strings stay strings and numbers use the smallest variant that
+/// holds them. Every detector is opt-in:
+///
+/// ```ignore
+/// let options = ParseOptions::default().detect_uuid(true).detect_datetime(true);
+/// let value = json::parse_with(input, &options)?;
+/// ```
+///
+/// With a schema the expected type at each location decides the conversion,
+/// and the detectors only run where the schema allows any value.
+#[derive(Clone, Default)]
+pub struct ParseOptions {
+    uuid: bool,
+    datetime: bool,
+    date: bool,
+    time: bool,
+    bytes: bool,
+    relaxed: bool,
+    extended: bool,
+    detectors: Vec<Detector>,
+    schema: Option<TypeSchema>,
+    limits: Limits,
+}
+
+impl ParseOptions {
+    /// Accept JSON5, see [`json5`](ParseOptions::json5)
+    pub fn relaxed() -> ParseOptions {
+        ParseOptions::default().json5(true)
+    }
+
+    /// Accept the JSON5 grammar used by hand written config files: comments,
+    /// trailing commas, unquoted keys, single quoted strings, hexadecimal
+    /// numbers, `Infinity` and `NaN`.
+    pub fn json5(mut self, enable: bool) -> Self {
+        self.relaxed = enable;
+        self
+    }
+
+    /// Hyphenated uuids like `67e55044-10b1-426f-9247-bb680e5fe0c8`
+    pub fn detect_uuid(mut self, enable: bool) -> Self {
+        self.uuid = enable;
+        self
+    }
+
+    /// RFC 3339 datetimes, converted to UTC, and datetimes without an offset
+    pub fn detect_datetime(mut self, enable: bool) -> Self {
+        self.datetime = enable;
+        self
+    }
+
+    /// Dates like `2024-01-02`
+    pub fn detect_date(mut self, enable: bool) -> Self {
+        self.date = enable;
+        self
+    }
+
+    /// Times like `12:30:00`
+    pub fn detect_time(mut self, enable: bool) -> Self {
+        self.time = enable;
+        self
+    }
+
+    /// Padded standard base64.
+    /// Short words are valid base64 too, so this is best used with a schema.
+    pub fn detect_bytes(mut self, enable: bool) -> Self {
+        self.bytes = enable;
+        self
+    }
+
+    /// Add a custom detector, they run in order before the built in ones
+    pub fn detector<F>(mut self, detector: F) -> Self
+    where
+        F: Fn(&str) -> Option<Value> + Send + Sync + 'static,
+    {
+        self.detectors.push(Arc::new(detector));
+        self
+    }
+
+    /// Decode the tagged objects of extended JSON back into the values they
+    /// stand for, see [`DisplayOptions::extended`](super::DisplayOptions::extended).
+    /// Only [`parse_with`](super::parse_with) decodes them, `JsonValue` keeps the objects.
+    pub fn extended(mut self, enable: bool) -> Self {
+        self.extended = enable;
+        self
+    }
+
+    /// Convert the values by the types the schema expects
+    pub fn schema(mut self, schema: TypeSchema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Convert the values by the schema of `T`
+    pub fn schema_of<T: ValueSchema + ?Sized>(self) -> Self {
+        self.schema(T::schema())
+    }
+
+    /// Bound the size of the document, see [`Limits`]
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub(crate) fn get_limits(&self) -> Limits {
+        self.limits
+    }
+
+    pub(crate) fn is_relaxed(&self) -> bool {
+        self.relaxed
+    }
+
+    pub(crate) fn is_extended(&self) -> bool {
+        self.extended
+    }
+
+    /// The schema of the whole document
+    pub(crate) fn root(&self) -> Option<&TypeSchema> {
+        self.schema.as_ref()
+    }
+
+    /// Run the detectors on `value`
+    pub fn detect(&self, value: &str) -> Option<Value> {
+        if let Some(ret) = self.detectors.iter().find_map(|detector| detector(value)) {
+            return Some(ret);
+        }
+
+        let ty = self.detected_types();
+        if ty.is_empty() {
+            return None;
+        }
+
+        parse_typed(value, ty)
+    }
+
+    fn detected_types(&self) -> Type {
+        let mut ty = Type::empty();
+        ty.set(Type::Uuid, self.uuid);
+        ty.set(Type::DateTime, self.datetime);
+        ty.set(Type::Date, self.date);
+        ty.set(Type::Time, self.time);
+        ty.set(Type::Bytes, self.bytes);
+        ty
+    }
+
+    /// The value a JSON string becomes at a location expecting `schema`,
+    /// `None` to keep the string
+    pub(crate) fn convert_str(&self, value: &str, schema: Option<&TypeSchema>) -> Option<Value> {
+        match expected(schema) {
+            None => self.detect(value),
+            Some(ty) if ty.contains(Type::String) => None,
+            Some(ty) => parse_typed(value, ty),
+        }
+    }
+
+    /// The value of a JSON number at a location expecting `schema`
+    pub(crate) fn number(&self, number: Number, schema: Option<&TypeSchema>) -> Number {
+        match expected(schema) {
+            Some(ty) if !ty.contains(number.get_type()) => number_as(number, ty),
+            _ => number,
+        }
+    }
+}
+
+impl fmt::Debug for ParseOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParseOptions")
+            .field("uuid", &self.uuid)
+            .field("datetime", &self.datetime)
+            .field("date", &self.date)
+            .field("time", &self.time)
+            .field("bytes", &self.bytes)
+            .field("relaxed", &self.relaxed)
+            .field("extended", &self.extended)
+            .field("detectors", &self.detectors.len())
+            .field("schema", &self.schema)
+            .field("limits", &self.limits)
+            .finish()
+    }
+}
+
+/// The types a schema expects, `None` when it allows anything
+fn expected(schema: Option<&TypeSchema>) -> Option<Type> {
+    let ty = schema?.get_type();
+    if ty == Type::all() {
+        None
+    } else {
+        Some(ty)
+    }
+}
+
+/// The schema of the value at `key` of a map
+pub(crate) fn field_schema<'a>(
+    schema: Option<&'a TypeSchema>,
+    key: &str,
+) -> Option<&'a TypeSchema> {
+    match schema? {
+        TypeSchema::Optional(schema) => field_schema(Some(schema), key),
+        TypeSchema::Map(schema) => Some(schema),
+        TypeSchema::Struct(schema) => schema
+            .fields
+            .iter()
+            .find(|field| &*field.name == key)
+            .map(|field| &field.schema),
+        TypeSchema::Enum(schema) if schema.repr == EnumRepr::External => schema
+            .variants
+            .iter()
+            .find(|variant| &*variant.name == key)
+            .and_then(|variant| variant.content.as_ref()),
+        _ => None,
+    }
+}
+
+/// The schema of the value at `idx` of a list
+pub(crate) fn item_schema(schema: Option<&TypeSchema>, idx: usize) -> Option<&TypeSchema> {
+    match schema? {
+        TypeSchema::Optional(schema) => item_schema(Some(schema), idx),
+        TypeSchema::List(schema) => Some(schema),
+        TypeSchema::Tuple(items) => items.get(idx),
+        _ => None,
+    }
+}
+
+/// Parse `value` as the first of the types in `ty` it is valid for
+fn parse_typed(value: &str, ty: Type) -> Option<Value> {
+    if ty.contains(Type::Uuid) && value.len() == 36 {
+        if let Ok(uuid) = uuid::Uuid::parse_str(value) {
+            return Some(Value::Uuid(uuid));
+        }
+    }
+
+    if ty.contains(Type::DateTime) {
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+            return Some(Value::DateTime(datetime.naive_utc()));
+        }
+        if let Ok(datetime) = value.parse::<NaiveDateTime>() {
+            return Some(Value::DateTime(datetime));
+        }
+    }
+
+    if ty.contains(Type::Date) {
+        if let Ok(date) = value.parse::<NaiveDate>() {
+            return Some(Value::Date(date));
+        }
+    }
+
+    if ty.contains(Type::Time) {
+        if let Ok(time) = value.parse::<NaiveTime>() {
+            return Some(Value::Time(time));
+        }
+    }
+
+    if ty.contains(Type::Bytes) && !value.is_empty() {
+        if let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(value) {
+            return Some(Value::Bytes(Bytes::from(bytes)));
+        }
+    }
+
+    if ty.contains(Type::Char) {
+        let mut chars = value.chars();
+        if let (Some(ch), None) = (chars.next(), chars.next()) {
+            return Some(Value::Char(ch));
+        }
+    }
+
+    None
+}
+
+/// `number` as one of the number types in `ty`, if it fits without loss
+fn number_as(number: Number, ty: Type) -> Number {
+    macro_rules! try_int {
+        ($($ty: ident => $int: ty),*) => {
+            $(
+                if ty.contains(Type::$ty) {
+                    if let Ok(value) = <$int>::try_from(number) {
+                        return Number::$ty(value);
+                    }
+                }
+            )*
+        };
+    }
+
+    try_int!(
+        U8 => u8,
+        I8 => i8,
+        U16 => u16,
+        I16 => i16,
+        U32 => u32,
+        I32 => i32,
+        U64 => u64,
+        I64 => i64
+    );
+
+    if number.is_integer() {
+        if ty.contains(Type::F64) {
+            return Number::F64(number.as_f64());
+        }
+        if ty.contains(Type::F32) {
+            return Number::F32(number.as_f32());
+        }
+    }
+
+    number
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
+
+    use super::*;
+    use crate::{json::parse_with, schema::StructSchema, Map};
+
+    const UUID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+    fn parse_str(input: &str, options: &ParseOptions) -> Value {
+        parse_with(&alloc::format!("\"{input}\""), options).unwrap()
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn time(hour: u32, minute: u32, second: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, second).unwrap()
+    }
+
+    #[test]
+    fn strings_stay_strings() {
+        let options = ParseOptions::default();
+        for text in [
+            UUID,
+            "2024-01-02T12:30:00Z",
+            "2024-01-02",
+            "12:30:00",
+            "aGk=",
+        ] {
+            assert_eq!(parse_str(text, &options), Value::String(text.into()));
+        }
+    }
+
+    #[test]
+    fn detectors() {
+        let uuid = ParseOptions::default().detect_uuid(true);
+        assert_eq!(
+            parse_str(UUID, &uuid),
+            Value::Uuid(uuid::Uuid::parse_str(UUID).unwrap())
+        );
+        // Only the hyphenated form
+        let simple = UUID.replace('-', "");
+        assert_eq!(parse_str(&simple, &uuid), Value::String(simple.into()));
+        assert_eq!(
+            parse_str("2024-01-02", &uuid),
+            Value::String("2024-01-02".into())
+        );
+
+        let datetime = ParseOptions::default().detect_datetime(true);
+        assert_eq!(
+            parse_str("2024-01-02T12:30:00+02:00", &datetime),
+            Value::DateTime(date(2024, 1, 2).and_time(time(10, 30, 0)))
+        );
+        assert_eq!(
+            parse_str("2024-01-02T12:30:00", &datetime),
+            Value::DateTime(date(2024, 1, 2).and_time(time(12, 30, 0)))
+        );
+        assert_eq!(
+            parse_str("2024-01-02", &datetime),
+            Value::String("2024-01-02".into())
+        );
+
+        let date_only = ParseOptions::default().detect_date(true);
+        assert_eq!(
+            parse_str("2024-01-02", &date_only),
+            Value::Date(date(2024, 1, 2))
+        );
+        assert_eq!(
+            parse_str("12:30:00", &date_only),
+            Value::String("12:30:00".into())
+        );
+
+        let time_only = ParseOptions::default().detect_time(true);
+        assert_eq!(
+            parse_str("12:30:00", &time_only),
+            Value::Time(time(12, 30, 0))
+        );
+        assert_eq!(parse_str(UUID, &time_only), Value::String(UUID.into()));
+
+        let bytes = ParseOptions::default().detect_bytes(true);
+        assert_eq!(
+            parse_str("aGVsbG8=", &bytes),
+            Value::Bytes(Bytes::from(&b"hello"[..]))
+        );
+        assert_eq!(parse_str("", &bytes), Value::String("".into()));
+        assert_eq!(
+            parse_str("not base64", &bytes),
+            Value::String("not base64".into())
+        );
+    }
+
+    #[test]
+    fn custom_detectors_run_first() {
+        let options = ParseOptions::default()
+            .detect_uuid(true)
+            .detector(|text| text.starts_with("67e5").then(|| Value::Bool(true)))
+            .detector(|text| (text == "yes").then(|| Value::Bool(true)))
+            .detector(|_| Some(Value::Bool(false)));
+
+        assert_eq!(parse_str(UUID, &options), Value::Bool(true));
+        assert_eq!(parse_str("yes", &options), Value::Bool(true));
+        assert_eq!(parse_str("no", &options), Value::Bool(false));
+
+        let options = ParseOptions::default()
+            .detect_date(true)
+            .detector(|text| (text == "today").then(|| Value::Date(date(2024, 1, 2))));
+        assert_eq!(parse_str("today", &options), Value::Date(date(2024, 1, 2)));
+        assert_eq!(
+            parse_str("2024-01-03", &options),
+            Value::Date(date(2024, 1, 3))
+        );
+    }
+
+    #[test]
+    fn schema_of() {
+        let options = ParseOptions::default().schema_of::<Vec<(uuid::Uuid, NaiveDate, String)>>();
+        let value = parse_with(
+            &alloc::format!(r#"[["{UUID}", "2024-01-02", "2024-01-03"]]"#),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            Value::List(
+                vec![Value::List(
+                    vec![
+                        Value::Uuid(uuid::Uuid::parse_str(UUID).unwrap()),
+                        Value::Date(date(2024, 1, 2)),
+                        Value::String("2024-01-03".into()),
+                    ]
+                    .into()
+                )]
+                .into()
+            )
+        );
+
+        // A string the schema doesn't expect stays a string
+        let options = ParseOptions::default().schema_of::<Option<BTreeMap<String, NaiveTime>>>();
+        let value = parse_with(r#"{"a": "12:30:00", "b": "noon"}"#, &options).unwrap();
+        let mut map = Map::default();
+        map.insert("a", Value::Time(time(12, 30, 0)));
+        map.insert("b", Value::String("noon".into()));
+        assert_eq!(value, Value::Map(map));
+    }
+
+    #[test]
+    fn field_schema() {
+        let schema = StructSchema::new("Event")
+            .field("id", uuid::Uuid::schema(), true)
+            .field("label", String::schema(), true)
+            .field("data", Value::schema(), false)
+            .into();
+        let options = ParseOptions::default().detect_date(true).schema(schema);
+
+        let value = parse_with(
+            &alloc::format!(
+                r#"{{"id": "{UUID}", "label": "2024-01-02", "data": "2024-01-02", "other": "2024-01-02"}}"#
+            ),
+            &options,
+        )
+        .unwrap();
+        let map = value.as_map().unwrap();
+        assert_eq!(
+            map.get("id"),
+            Some(&Value::Uuid(uuid::Uuid::parse_str(UUID).unwrap()))
+        );
+        // The schema expects a string
+        assert_eq!(map.get("label"), Some(&Value::String("2024-01-02".into())));
+        // Any value, so the detectors run
+        assert_eq!(map.get("data"), Some(&Value::Date(date(2024, 1, 2))));
+        // Unknown to the schema, so anything goes as well
+        assert_eq!(map.get("other"), Some(&Value::Date(date(2024, 1, 2))));
+    }
+
+    #[test]
+    fn item_schema() {
+        let options = ParseOptions::default().schema_of::<(u32, char, [NaiveDate; 1])>();
+        let value = parse_with(r#"[1, "c", ["2024-01-02"]]"#, &options).unwrap();
+        assert_eq!(
+            value,
+            Value::List(
+                vec![
+                    Value::Number(Number::U32(1)),
+                    Value::Char('c'),
+                    Value::List(vec![Value::Date(date(2024, 1, 2))].into()),
+                ]
+                .into()
+            )
+        );
+    }
+
+    #[test]
+    fn number_as() {
+        let number = |input: &str, schema: TypeSchema| {
+            let options = ParseOptions::default().schema(schema);
+            match parse_with(input, &options).unwrap() {
+                Value::Number(n) => n,
+                value => panic!("expected a number, found {value:?}"),
+            }
+        };
+
+        assert_eq!(number("1", i64::schema()), Number::I64(1));
+        assert_eq!(number("1", u64::schema()), Number::U64(1));
+        assert_eq!(number("1", f64::schema()), Number::F64(1.0));
+        assert_eq!(number("1", f32::schema()), Number::F32(1.0));
+        assert_eq!(number("1.5", f32::schema()), Number::F64(1.5));
+        // Doesn't fit, so the parsed number is kept
+        assert_eq!(number("300", u8::schema()), Number::U16(300));
+        assert_eq!(number("-1", u32::schema()), Number::I8(-1));
+        // The first type of a union that holds it
+        assert_eq!(
+            number(
+                "300",
+                TypeSchema::Primitive(Type::U8 | Type::I32 | Type::I64)
+            ),
+            Number::I32(300)
+        );
+        assert_eq!(number("1", Option::<u16>::schema()), Number::U16(1));
+    }
+}
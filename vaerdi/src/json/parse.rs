@@ -1,172 +1,683 @@
-use crate::{List, Map, Value};
-use alloc::string::String;
-use chrono::DateTime;
-use udled::{
-    token::{Opt, Ws},
-    Input, Tokenizer,
+use super::{
+    error::{ParseError, ParseErrorKind},
+    extended, json5,
+    options::{field_schema, item_schema, ParseOptions},
 };
-use udled_tokenizers::{Bool, Int, Str};
+use crate::{
+    limits::{LimitError, LimitTracker},
+    schema::TypeSchema,
+    List, Map, Number, Value,
+};
+use alloc::{borrow::Cow, string::String};
+
+/// Parse a JSON document.
+///
+/// Integers become the smallest `Number` that holds them and every other
+/// number becomes a `F64`. Strings stay strings, see [`parse_with`] to detect
+/// other types.
+pub fn parse(input: &str) -> Result<Value, ParseError> {
+    parse_with(input, &ParseOptions::default())
+}
 
-const WS: Opt<Ws> = Opt(Ws);
+/// Parse a JSON document, converting strings and numbers as set by `options`
+pub fn parse_with(input: &str, options: &ParseOptions) -> Result<Value, ParseError> {
+    let mut parser = Parser::new(input, options);
 
-pub fn parse(input: &str) -> Result<Value, udled::Error> {
-    let mut input = udled::Input::new(input);
+    parser.skip_ws();
+    let start = parser.pos;
+    let value = parser.parse_value(options.root())?;
+    let value = parser.decode(value, start)?;
+    parser.skip_ws();
 
-    // input.parse(WS)?;
+    if parser.pos < parser.bytes.len() {
+        return Err(parser.error(ParseErrorKind::TrailingCharacters));
+    }
 
-    parse_value(&mut input)
+    Ok(value)
 }
 
-fn parse_value(input: &mut Input<'_>) -> Result<Value, udled::Error> {
-    let Some(ch) = input.peek_ch() else {
-        return Err(input.error("unexpected eof"));
-    };
+struct Parser<'a> {
+    options: &'a ParseOptions,
+    input: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    limits: LimitTracker,
+    relaxed: bool,
+    extended: bool,
+}
 
-    match ch {
-        "{" => parse_object(input),
-        "[" => parse_list(input),
-        "t" | "f" => {
-            let bool = input.parse(Bool)?;
-            Ok(Value::Bool(bool.value))
+impl<'a> Parser<'a> {
+    fn new(input: &'a str, options: &'a ParseOptions) -> Parser<'a> {
+        Parser {
+            options,
+            input,
+            bytes: input.as_bytes(),
+            pos: 0,
+            limits: LimitTracker::new(options.get_limits()),
+            relaxed: options.is_relaxed(),
+            extended: options.is_extended(),
         }
-        "n" => {
-            let _ = input.parse("null")?;
-            Ok(Value::Null)
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError::new(kind, self.input, self.pos)
+    }
+
+    /// Count against the limits, failing at byte `offset`
+    fn limit<F>(&mut self, offset: usize, check: F) -> Result<(), ParseError>
+    where
+        F: FnOnce(&mut LimitTracker) -> Result<(), LimitError>,
+    {
+        check(&mut self.limits)
+            .map_err(|err| ParseError::new(ParseErrorKind::Limit(err), self.input, offset))
+    }
+
+    /// An error for the character at the current position
+    fn unexpected(&self, expected: &'static str) -> ParseError {
+        match self.input[self.pos..].chars().next() {
+            Some(found) => self.error(ParseErrorKind::Unexpected { found, expected }),
+            None => self.error(ParseErrorKind::Eof),
         }
-        "\"" => input.parse(JsonStringValue),
-        _ => input.parse(JsonNumber),
     }
-}
 
-fn parse_object(input: &mut Input<'_>) -> Result<Value, udled::Error> {
-    let _ = input.parse((WS, '{'))?;
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        if self.relaxed {
+            self.pos += json5::space(&self.input[self.pos..]);
+            return;
+        }
+
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
 
-    let mut map = Map::default();
+    fn expect(&mut self, byte: u8, expected: &'static str) -> Result<(), ParseError> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.unexpected(expected))
+        }
+    }
 
-    let _ = input.parse(WS)?;
+    fn parse_value(&mut self, schema: Option<&TypeSchema>) -> Result<Value, ParseError> {
+        if self.peek().is_some() {
+            self.limit(self.pos, |limits| limits.value())?;
+        }
 
-    if input.peek('}')? {
-        input.eat('}')?;
-        return Ok(Value::Map(map));
+        match self.peek() {
+            None => Err(self.error(ParseErrorKind::Eof)),
+            Some(b'{') => self.nested(Parser::parse_object, schema),
+            Some(b'[') => self.nested(Parser::parse_list, schema),
+            Some(b'"' | b'\'') if self.relaxed => {
+                let value = self.parse_str5()?;
+                Ok(match self.options.convert_str(&value, schema) {
+                    Some(ret) => ret,
+                    None => Value::String(value.into()),
+                })
+            }
+            Some(b'+' | b'-' | b'.' | b'0'..=b'9' | b'I' | b'N') if self.relaxed => {
+                let number = self.parse_number5()?;
+                Ok(Value::Number(self.options.number(number, schema)))
+            }
+            Some(b'"') => {
+                let value = self.parse_str()?;
+                Ok(match self.options.convert_str(&value, schema) {
+                    Some(ret) => ret,
+                    None => Value::String(value.into()),
+                })
+            }
+            Some(b't') => self.parse_ident("true", Value::Bool(true)),
+            Some(b'f') => self.parse_ident("false", Value::Bool(false)),
+            Some(b'n') => self.parse_ident("null", Value::Null),
+            Some(b'-' | b'0'..=b'9') => {
+                let number = self.parse_number()?;
+                Ok(Value::Number(self.options.number(number, schema)))
+            }
+            Some(_) => Err(self.unexpected("a value")),
+        }
     }
 
-    loop {
-        if input.eos() {
-            return Err(input.error("unexpected eof"));
+    fn nested(
+        &mut self,
+        parse: fn(&mut Parser<'a>, Option<&TypeSchema>) -> Result<Value, ParseError>,
+        schema: Option<&TypeSchema>,
+    ) -> Result<Value, ParseError> {
+        self.limit(self.pos, |limits| limits.enter())?;
+        let ret = parse(self, schema);
+        self.limits.leave();
+        ret
+    }
+
+    fn parse_ident(&mut self, ident: &'static str, value: Value) -> Result<Value, ParseError> {
+        for &byte in ident.as_bytes() {
+            self.expect(byte, ident)?;
         }
+        Ok(value)
+    }
 
-        let prop = input.parse(JsonString)?;
-        let _ = input.parse((WS, ':', WS))?;
+    /// An object, its entries are decoded but the object itself is left to
+    /// the caller, since it is the content of a `$map` tag or a tag itself
+    fn parse_object(&mut self, schema: Option<&TypeSchema>) -> Result<Value, ParseError> {
+        self.expect(b'{', "'{'")?;
+        self.skip_ws();
 
-        let value = parse_value(input)?;
+        let mut map = Map::default();
+        // The start of a value that is kept as it is, when the object is a `$map` tag
+        let mut content = None;
 
-        map.insert(prop, value);
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Value::Map(map));
+        }
 
-        let _ = input.parse(WS)?;
+        loop {
+            self.limit(self.pos, |limits| limits.item(map.len()))?;
+            let key = self.parse_key()?;
+            self.skip_ws();
+            self.expect(b':', "':'")?;
+            self.skip_ws();
+
+            let start = self.pos;
+            let value = self
+                .parse_value(field_schema(schema, &key))
+                .map_err(|err| err.with_field(&key))?;
+            let value = if self.extended && map.is_empty() && key == "$map" {
+                content = Some(start);
+                value
+            } else {
+                self.decode(value, start)?
+            };
+            map.insert(key, value);
+
+            self.skip_ws();
+            match self.peek() {
+                Some(b',') => {
+                    self.pos += 1;
+                    self.skip_ws();
+                    if self.relaxed && self.peek() == Some(b'}') {
+                        self.pos += 1;
+                        break;
+                    }
+                }
+                Some(b'}') => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => return Err(self.unexpected("',' or '}'")),
+            }
+        }
 
-        if input.peek('}')? {
-            input.parse('}')?;
-            break;
+        // With more keys the object is no tag, and its `$map` is decoded like any value
+        if let Some(start) = content.filter(|_| map.len() > 1) {
+            if let Some(value) = map.remove("$map") {
+                let value = self.decode(value, start)?;
+                map.insert("$map", value);
+            }
         }
 
-        let _ = input.parse((WS, ','))?;
+        Ok(Value::Map(map))
+    }
 
-        let _ = input.parse(WS)?;
+    /// Decode `value` if it is an extended JSON tag
+    fn decode(&self, value: Value, start: usize) -> Result<Value, ParseError> {
+        match value {
+            Value::Map(map) if self.extended => {
+                extended::decode(map).map_err(|kind| ParseError::new(kind, self.input, start))
+            }
+            value => Ok(value),
+        }
     }
 
-    Ok(Value::Map(map))
-}
+    fn parse_list(&mut self, schema: Option<&TypeSchema>) -> Result<Value, ParseError> {
+        self.expect(b'[', "'['")?;
+        self.skip_ws();
 
-fn parse_list(input: &mut Input<'_>) -> Result<Value, udled::Error> {
-    let _ = input.parse((WS, '['))?;
+        let mut list = List::default();
 
-    let mut map = List::default();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Value::List(list));
+        }
 
-    loop {
-        if input.eos() {
-            return Err(input.error("unexpected eof"));
+        loop {
+            let idx = list.len();
+            self.limit(self.pos, |limits| limits.item(idx))?;
+            let start = self.pos;
+            let value = self
+                .parse_value(item_schema(schema, idx))
+                .map_err(|err| err.with_index(idx))?;
+            list.push(self.decode(value, start)?);
+
+            self.skip_ws();
+            match self.peek() {
+                Some(b',') => {
+                    self.pos += 1;
+                    self.skip_ws();
+                    if self.relaxed && self.peek() == Some(b']') {
+                        self.pos += 1;
+                        return Ok(Value::List(list));
+                    }
+                }
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Value::List(list));
+                }
+                _ => return Err(self.unexpected("',' or ']'")),
+            }
         }
+    }
 
-        let _ = input.parse(WS)?;
+    fn parse_key(&mut self) -> Result<Cow<'a, str>, ParseError> {
+        if !self.relaxed {
+            if self.peek() != Some(b'"') {
+                return Err(self.unexpected("a string key"));
+            }
+            return self.parse_str();
+        }
 
-        if input.peek(']')? {
-            input.parse(']')?;
-            break;
+        if let Some(b'"' | b'\'') = self.peek() {
+            return self.parse_str5().map(Cow::Owned);
         }
 
-        let value = parse_value(input)?;
+        let len = json5::identifier(&self.input[self.pos..]);
+        if len == 0 {
+            return Err(self.unexpected("a key"));
+        }
 
-        map.push(value);
+        self.limit(self.pos, |limits| limits.text(len))?;
+        let key = &self.input[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(Cow::Borrowed(key))
+    }
 
-        input.eat(WS)?;
+    /// A JSON5 number
+    fn parse_number5(&mut self) -> Result<Number, ParseError> {
+        match json5::number(&self.input[self.pos..]) {
+            Ok((number, len)) => {
+                self.pos += len;
+                Ok(number)
+            }
+            Err(idx) => Err(ParseError::new(
+                ParseErrorKind::InvalidNumber,
+                self.input,
+                self.pos + idx,
+            )),
+        }
+    }
 
-        if input.peek(']')? {
-            input.parse(']')?;
-            break;
+    /// A JSON5 string in single or double quotes
+    fn parse_str5(&mut self) -> Result<String, ParseError> {
+        match json5::string(&self.input[self.pos..]) {
+            Ok((value, len)) => {
+                self.limit(self.pos, |limits| limits.text(value.len()))?;
+                self.pos += len;
+                Ok(value)
+            }
+            Err((kind, idx)) => Err(ParseError::new(kind, self.input, self.pos + idx)),
         }
+    }
 
-        let _ = input.parse((WS, ','))?;
+    fn parse_number(&mut self) -> Result<Number, ParseError> {
+        match scan_number(&self.bytes[self.pos..]) {
+            Ok(len) => {
+                let text = &self.input[self.pos..self.pos + len];
+                self.pos += len;
+                Ok(number_from_text(text))
+            }
+            Err(idx) => Err(ParseError::new(
+                ParseErrorKind::InvalidNumber,
+                self.input,
+                self.pos + idx,
+            )),
+        }
     }
 
-    Ok(Value::List(map))
+    /// A string, borrowed from the input when it has no escapes
+    fn parse_str(&mut self) -> Result<Cow<'a, str>, ParseError> {
+        self.expect(b'"', "'\"'")?;
+
+        let start = self.pos;
+        let input = self.input;
+        let (end, raw) = match scan_str(&self.bytes[start..], 0) {
+            Ok(end) => (Some(end), &input[start..start + end]),
+            Err(_) => (None, &input[start..]),
+        };
+
+        let value = decode_str(raw)
+            .map_err(|(kind, idx)| ParseError::new(kind, self.input, start + idx))?;
+
+        let Some(end) = end else {
+            self.pos = self.bytes.len();
+            return Err(self.error(ParseErrorKind::Eof));
+        };
+
+        self.limit(start - 1, |limits| limits.text(value.len()))?;
+        self.pos = start + end + 1;
+        Ok(value)
+    }
 }
 
-struct JsonNumber;
+/// The length of the number at the start of `bytes`, or the index of the
+/// first invalid byte
+pub(super) fn scan_number(bytes: &[u8]) -> Result<usize, usize> {
+    let digits = |mut idx: usize| {
+        while let Some(b'0'..=b'9') = bytes.get(idx) {
+            idx += 1;
+        }
+        idx
+    };
+
+    let mut idx = 0;
+    if bytes.first() == Some(&b'-') {
+        idx += 1;
+    }
+
+    idx = match bytes.get(idx) {
+        Some(b'0') => idx + 1,
+        Some(b'1'..=b'9') => digits(idx),
+        _ => return Err(idx),
+    };
 
-impl Tokenizer for JsonNumber {
-    type Token<'a> = Value;
-    fn to_token<'a>(
-        &self,
-        reader: &mut udled::Reader<'_, 'a>,
-    ) -> Result<Self::Token<'a>, udled::Error> {
-        let int = reader.parse(Int)?;
-        Ok(Value::Number((int.value as i64).into()))
+    if bytes.get(idx) == Some(&b'.') {
+        idx += 1;
+        if !matches!(bytes.get(idx), Some(b'0'..=b'9')) {
+            return Err(idx);
+        }
+        idx = digits(idx);
     }
+
+    if let Some(b'e' | b'E') = bytes.get(idx) {
+        idx += 1;
+        if let Some(b'+' | b'-') = bytes.get(idx) {
+            idx += 1;
+        }
+        if !matches!(bytes.get(idx), Some(b'0'..=b'9')) {
+            return Err(idx);
+        }
+        idx = digits(idx);
+    }
+
+    Ok(idx)
 }
 
-struct JsonString;
+/// The number of a token accepted by `scan_number`
+pub(super) fn number_from_text(text: &str) -> Number {
+    let negative = text.starts_with('-');
+    let digits = text.strip_prefix('-').unwrap_or(text);
 
-impl Tokenizer for JsonString {
-    type Token<'a> = String;
-    // TODO: Impl json string parsing
-    fn to_token<'a>(
-        &self,
-        reader: &mut udled::Reader<'_, 'a>,
-    ) -> Result<Self::Token<'a>, udled::Error> {
-        let output = reader.parse(Str)?;
+    if let Ok(value) = digits.parse::<u64>() {
+        if !negative {
+            return unsigned(value);
+        } else if value <= i64::MAX as u64 + 1 {
+            return signed((value as i64).wrapping_neg());
+        }
+    }
 
-        Ok(output.as_str().into())
+    // Integers out of range for 64 bits lose precision like any float
+    Number::F64(text.parse().unwrap_or(f64::NAN))
+}
+
+/// The index of the quote that ends the string in `bytes`, which starts after
+/// the opening quote. Scanning starts at `from`, when the bytes run out the
+/// error is the index to resume from once there are more.
+pub(super) fn scan_str(bytes: &[u8], from: usize) -> Result<usize, usize> {
+    let mut idx = from;
+    loop {
+        let Some(byte) = bytes.get(idx) else {
+            return Err(idx);
+        };
+        match byte {
+            b'"' => return Ok(idx),
+            b'\\' => idx += 2,
+            _ => idx += 1,
+        }
     }
+}
 
-    fn peek<'a>(&self, reader: &mut udled::Reader<'_, '_>) -> Result<bool, udled::Error> {
-        reader.peek('"')
+/// Decode the escapes in the text between the quotes of a string.
+/// Errors hold the index of the offending byte.
+pub(super) fn decode_str(raw: &str) -> Result<Cow<'_, str>, (ParseErrorKind, usize)> {
+    let bytes = raw.as_bytes();
+    let mut output: Option<String> = None;
+    let mut start = 0;
+    let mut idx = 0;
+
+    while let Some(&byte) = bytes.get(idx) {
+        match byte {
+            b'\\' => {
+                let output = output.get_or_insert_with(String::new);
+                output.push_str(&raw[start..idx]);
+                let (ch, len) =
+                    decode_escape(&bytes[idx..]).map_err(|(kind, at)| (kind, idx + at))?;
+                output.push(ch);
+                idx += len;
+                start = idx;
+            }
+            0x00..=0x1F => return Err((ParseErrorKind::ControlCharacter, idx)),
+            _ => idx += 1,
+        }
     }
+
+    Ok(match output {
+        Some(mut output) => {
+            output.push_str(&raw[start..]);
+            Cow::Owned(output)
+        }
+        None => Cow::Borrowed(raw),
+    })
 }
 
-struct JsonStringValue;
+/// The character and length of the escape at the start of `bytes`,
+/// joining the surrogate pairs of `\u` escapes
+fn decode_escape(bytes: &[u8]) -> Result<(char, usize), (ParseErrorKind, usize)> {
+    let ch = match bytes.get(1) {
+        Some(b'"') => '"',
+        Some(b'\\') => '\\',
+        Some(b'/') => '/',
+        Some(b'b') => '\u{08}',
+        Some(b'f') => '\u{0C}',
+        Some(b'n') => '\n',
+        Some(b'r') => '\r',
+        Some(b't') => '\t',
+        Some(b'u') => return decode_unicode_escape(bytes),
+        Some(_) => return Err((ParseErrorKind::InvalidEscape, 1)),
+        None => return Err((ParseErrorKind::Eof, 1)),
+    };
 
-impl Tokenizer for JsonStringValue {
-    type Token<'a> = Value;
-    // TODO: Impl json string parsing
-    fn to_token<'a>(
-        &self,
-        reader: &mut udled::Reader<'_, 'a>,
-    ) -> Result<Self::Token<'a>, udled::Error> {
-        let output = reader.parse(Str)?;
+    Ok((ch, 2))
+}
 
-        // Uuid?
-        if output.value.len() == 36 {
-            if let Ok(uuid) = uuid::Uuid::parse_str(output.value) {
-                return Ok(Value::Uuid(uuid));
+pub(super) fn decode_unicode_escape(
+    bytes: &[u8],
+) -> Result<(char, usize), (ParseErrorKind, usize)> {
+    let high = hex4(bytes, 2)?;
+
+    let (code, len) = match high {
+        0xD800..=0xDBFF => {
+            if bytes.get(6..8) != Some(b"\\u") {
+                return Err((ParseErrorKind::LoneSurrogate, 0));
+            }
+
+            let low = hex4(bytes, 8)?;
+            if !(0xDC00..=0xDFFF).contains(&low) {
+                return Err((ParseErrorKind::LoneSurrogate, 0));
             }
-        } else if let Ok(date) = DateTime::parse_from_rfc3339(&output.value) {
-            return Ok(Value::DateTime(date.naive_utc()));
+
+            (0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00), 12)
+        }
+        0xDC00..=0xDFFF => return Err((ParseErrorKind::LoneSurrogate, 0)),
+        code => (code, 6),
+    };
+
+    match char::from_u32(code) {
+        Some(ch) => Ok((ch, len)),
+        None => Err((ParseErrorKind::InvalidEscape, 0)),
+    }
+}
+
+/// The four hex digits at `start` of `bytes`
+fn hex4(bytes: &[u8], start: usize) -> Result<u32, (ParseErrorKind, usize)> {
+    let mut code = 0;
+    for idx in start..start + 4 {
+        let digit = match bytes.get(idx) {
+            Some(byte @ b'0'..=b'9') => byte - b'0',
+            Some(byte @ b'a'..=b'f') => byte - b'a' + 10,
+            Some(byte @ b'A'..=b'F') => byte - b'A' + 10,
+            Some(_) => return Err((ParseErrorKind::InvalidEscape, idx)),
+            None => return Err((ParseErrorKind::Eof, idx)),
+        };
+        code = code * 16 + digit as u32;
+    }
+    Ok(code)
+}
+
+/// The smallest unsigned variant that holds `value`
+pub(super) fn unsigned(value: u64) -> Number {
+    if let Ok(value) = u8::try_from(value) {
+        Number::U8(value)
+    } else if let Ok(value) = u16::try_from(value) {
+        Number::U16(value)
+    } else if let Ok(value) = u32::try_from(value) {
+        Number::U32(value)
+    } else {
+        Number::U64(value)
+    }
+}
+
+/// The smallest signed variant that holds `value`
+pub(super) fn signed(value: i64) -> Number {
+    if let Ok(value) = i8::try_from(value) {
+        Number::I8(value)
+    } else if let Ok(value) = i16::try_from(value) {
+        Number::I16(value)
+    } else if let Ok(value) = i32::try_from(value) {
+        Number::I32(value)
+    } else {
+        Number::I64(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use super::*;
+    use crate::limits::{Limit, Limits};
+
+    fn error(input: &str) -> (ParseErrorKind, usize, usize) {
+        let err = parse(input).unwrap_err();
+        (err.kind().clone(), err.line(), err.column())
+    }
+
+    #[test]
+    fn syntax_errors() {
+        assert_eq!(error(""), (ParseErrorKind::Eof, 1, 1));
+        assert_eq!(error("[1,"), (ParseErrorKind::Eof, 1, 4));
+        assert_eq!(
+            error("[1,]"),
+            (
+                ParseErrorKind::Unexpected {
+                    found: ']',
+                    expected: "a value"
+                },
+                1,
+                4
+            )
+        );
+        assert_eq!(
+            error("{\"a\" 1}"),
+            (
+                ParseErrorKind::Unexpected {
+                    found: '1',
+                    expected: "':'"
+                },
+                1,
+                6
+            )
+        );
+        assert_eq!(error("[1.]"), (ParseErrorKind::InvalidNumber, 1, 4));
+        assert_eq!(error("\"\\x\""), (ParseErrorKind::InvalidEscape, 1, 3));
+        assert_eq!(error("\"\\ud800\""), (ParseErrorKind::LoneSurrogate, 1, 2));
+        assert_eq!(error("\"a\nb\""), (ParseErrorKind::ControlCharacter, 1, 3));
+        assert_eq!(error("1 2"), (ParseErrorKind::TrailingCharacters, 1, 3));
+    }
+
+    fn number(input: &str) -> Number {
+        match parse(input).unwrap() {
+            Value::Number(n) => n,
+            value => panic!("expected a number, found {value:?}"),
         }
+    }
 
-        Ok(output.as_str().into())
+    #[test]
+    fn smallest_number() {
+        assert_eq!(number("0"), Number::U8(0));
+        assert_eq!(number("255"), Number::U8(255));
+        assert_eq!(number("256"), Number::U16(256));
+        assert_eq!(number("65536"), Number::U32(65536));
+        assert_eq!(number("4294967296"), Number::U64(4294967296));
+        assert_eq!(number("18446744073709551615"), Number::U64(u64::MAX));
+        assert_eq!(number("-1"), Number::I8(-1));
+        assert_eq!(number("-129"), Number::I16(-129));
+        assert_eq!(number("-32769"), Number::I32(-32769));
+        assert_eq!(number("-2147483649"), Number::I64(-2147483649));
+        assert_eq!(number("-9223372036854775808"), Number::I64(i64::MIN));
+        assert_eq!(number("-0"), Number::I8(0));
     }
 
-    fn peek<'a>(&self, reader: &mut udled::Reader<'_, '_>) -> Result<bool, udled::Error> {
-        reader.peek('"')
+    #[test]
+    fn numbers_beyond_64_bits_are_floats() {
+        assert_eq!(
+            number("18446744073709551616"),
+            Number::F64(18446744073709551616.0)
+        );
+        assert_eq!(
+            number("-9223372036854775809"),
+            Number::F64(-9223372036854775809.0)
+        );
+        assert_eq!(number("1.5"), Number::F64(1.5));
+        assert_eq!(number("1e2"), Number::F64(100.0));
+        assert_eq!(number("1e400"), Number::F64(f64::INFINITY));
+        assert_eq!(number("-1e400"), Number::F64(f64::NEG_INFINITY));
+    }
+
+    #[test]
+    fn error_location() {
+        let err = parse("{\n  \"a\": [1,\n    tru]\n}").unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &ParseErrorKind::Unexpected {
+                found: ']',
+                expected: "true"
+            }
+        );
+        assert_eq!((err.line(), err.column(), err.offset()), (3, 8, 20));
+    }
+
+    fn nested(depth: usize) -> String {
+        "[".repeat(depth) + &"]".repeat(depth)
+    }
+
+    #[test]
+    fn depth_follows_the_limits() {
+        let options = ParseOptions::default().limits(Limits::none().max_depth(300));
+        assert!(parse_with(&nested(300), &options).is_ok());
+
+        let err = parse_with(&nested(301), &options).unwrap_err();
+        match err.kind() {
+            ParseErrorKind::Limit(err) => {
+                assert_eq!(err.limit(), Limit::Depth);
+                assert_eq!(err.max(), 300);
+            }
+            kind => panic!("expected a limit error, found {kind}"),
+        }
+
+        let options = ParseOptions::default().limits(Limits::none());
+        assert!(parse_with(&nested(300), &options).is_ok());
     }
 }
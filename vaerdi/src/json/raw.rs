@@ -0,0 +1,137 @@
+use alloc::string::String;
+use core::{cell::OnceCell, fmt};
+
+use super::{
+    error::{ParseError, ParseErrorKind},
+    options::ParseOptions,
+    parse::parse_with,
+    reader::JsonReader,
+    to_string::to_string,
+};
+use crate::{limits::Limits, Value};
+
+/// Validated JSON text that is parsed into a `Value` the first time it is accessed.
+///
+/// The JSON writers copy the text as it is, so subtrees that are only passed on
+/// are never parsed. Put it in a [`JsonValue`](super::JsonValue) to carry it
+/// inside a larger document.
+///
+/// ```ignore
+/// let payload = RawJson::new(body)?;
+/// let mut doc = JsonValue::Object(Map::default());
+/// doc.insert("payload", payload);
+/// let json = json::to_string(&doc);
+/// ```
+#[derive(Clone)]
+pub struct RawJson {
+    text: String,
+    value: OnceCell<Value>,
+}
+
+impl RawJson {
+    /// Validate `text` as a single JSON document
+    pub fn new(text: impl Into<String>) -> Result<RawJson, ParseError> {
+        RawJson::with_limits(text, Limits::default())
+    }
+
+    /// Validate `text` as a single JSON document within `limits`
+    pub fn with_limits(text: impl Into<String>, limits: Limits) -> Result<RawJson, ParseError> {
+        let text = text.into();
+        validate(&text, limits)?;
+        Ok(RawJson {
+            text,
+            value: OnceCell::new(),
+        })
+    }
+
+    /// The JSON text of `value`
+    pub fn from_value(value: Value) -> RawJson {
+        RawJson {
+            text: to_string(&value),
+            value: OnceCell::from(value),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn into_string(self) -> String {
+        self.text
+    }
+
+    /// Whether the text has been parsed
+    pub fn is_parsed(&self) -> bool {
+        self.value.get().is_some()
+    }
+
+    /// The parsed value, parsing the text on first access
+    pub fn value(&self) -> &Value {
+        self.value.get_or_init(|| parse_validated(&self.text))
+    }
+
+    pub fn into_value(self) -> Value {
+        match self.value.into_inner() {
+            Some(value) => value,
+            None => parse_validated(&self.text),
+        }
+    }
+}
+
+/// Parse text that was validated within the limits it was created with
+fn parse_validated(text: &str) -> Value {
+    let options = ParseOptions::default().limits(Limits::none());
+    parse_with(text, &options).expect("validated json")
+}
+
+/// Check that `text` is one JSON value without building it
+fn validate(text: &str, limits: Limits) -> Result<(), ParseError> {
+    let mut reader = JsonReader::from_slice(text.as_bytes()).limits(limits);
+    if !reader.skip_value()? {
+        return Err(ParseError::new(ParseErrorKind::Eof, text, text.len()));
+    }
+    // Fails on anything but whitespace after the value
+    reader.next_event()?;
+    Ok(())
+}
+
+impl fmt::Debug for RawJson {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RawJson").field(&self.text).finish()
+    }
+}
+
+impl fmt::Display for RawJson {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Compares the text, two documents that only differ in whitespace are not equal
+impl PartialEq for RawJson {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl Eq for RawJson {}
+
+impl core::str::FromStr for RawJson {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RawJson::new(s)
+    }
+}
+
+impl From<RawJson> for Value {
+    fn from(value: RawJson) -> Self {
+        value.into_value()
+    }
+}
+
+impl<'a> From<&'a RawJson> for Value {
+    fn from(value: &'a RawJson) -> Self {
+        value.value().clone()
+    }
+}
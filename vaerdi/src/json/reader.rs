@@ -0,0 +1,921 @@
+use alloc::{string::String, vec::Vec};
+use core::{slice, str};
+
+use super::{
+    error::{ParseError, ParseErrorKind},
+    parse::{decode_str, number_from_text, scan_number, scan_str},
+};
+use crate::{
+    limits::{LimitError, LimitTracker, Limits},
+    List, Map, Number, Value, ValueRef,
+};
+
+/// How much of a slice the reader buffers at a time
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Where a `JsonReader` gets its input from
+pub trait Source {
+    /// Append more input to `buf`, returns `false` once the input is exhausted
+    fn fill(&mut self, buf: &mut Vec<u8>) -> Result<bool, ParseErrorKind>;
+}
+
+/// Input from an iterator of byte chunks
+#[derive(Debug, Clone)]
+pub struct ChunkSource<I>(pub I);
+
+impl<I> Source for ChunkSource<I>
+where
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
+{
+    fn fill(&mut self, buf: &mut Vec<u8>) -> Result<bool, ParseErrorKind> {
+        match self.0.next() {
+            Some(chunk) => {
+                buf.extend_from_slice(chunk.as_ref());
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// Input from a `std::io::Read`
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct IoSource<R>(pub R);
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> Source for IoSource<R> {
+    fn fill(&mut self, buf: &mut Vec<u8>) -> Result<bool, ParseErrorKind> {
+        let mut chunk = [0; CHUNK_SIZE];
+        loop {
+            match self.0.read(&mut chunk) {
+                Ok(0) => return Ok(false),
+                Ok(len) => {
+                    buf.extend_from_slice(&chunk[..len]);
+                    return Ok(true);
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(ParseErrorKind::Io(err.kind())),
+            }
+        }
+    }
+}
+
+/// A piece of a JSON document read by `JsonReader`.
+///
+/// Values are only ever strings, numbers, bools or null, arrays and objects
+/// come as their start and end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event<'a> {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    Key(&'a str),
+    Value(ValueRef<'a>),
+}
+
+/// An event without the borrow of the string
+#[derive(Debug, Clone, Copy)]
+enum Token {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    Key,
+    String,
+    Number(Number),
+    Bool(bool),
+    Null,
+}
+
+/// An open array or object
+struct Frame {
+    object: bool,
+    /// The number of items that have begun
+    len: usize,
+    /// The last key of an object
+    key: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Value,
+    ArrayFirst,
+    ArrayNext,
+    ObjectFirst,
+    ObjectNext,
+    Done,
+}
+
+/// A pull parser that reads a JSON document as a sequence of events, without
+/// holding more of the input in memory than the token it is on.
+///
+/// ```ignore
+/// let mut reader = JsonReader::from_reader(file);
+/// for value in reader.array_values() {
+///     let value = value?;
+/// }
+/// ```
+///
+/// The document is read within `Limits::default()` unless other [`limits`](Self::limits)
+/// are given. After an error the reader is in an undefined state and should be dropped.
+pub struct JsonReader<S> {
+    source: S,
+    exhausted: bool,
+    buf: Vec<u8>,
+    pos: usize,
+    offset: usize,
+    line: usize,
+    column: usize,
+    stack: Vec<Frame>,
+    state: State,
+    limits: LimitTracker,
+    /// The text of the last string or key
+    scratch: String,
+    /// Offset, line and column of the last token
+    token: (usize, usize, usize),
+}
+
+impl<'a> JsonReader<ChunkSource<slice::Chunks<'a, u8>>> {
+    pub fn from_slice(input: &'a [u8]) -> Self {
+        JsonReader::new(ChunkSource(input.chunks(CHUNK_SIZE)))
+    }
+}
+
+impl<I> JsonReader<ChunkSource<I>>
+where
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
+{
+    /// Read a document split into chunks, tokens may cross the chunks
+    pub fn from_chunks<C>(chunks: C) -> Self
+    where
+        C: IntoIterator<IntoIter = I>,
+    {
+        JsonReader::new(ChunkSource(chunks.into_iter()))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> JsonReader<IoSource<R>> {
+    pub fn from_reader(reader: R) -> Self {
+        JsonReader::new(IoSource(reader))
+    }
+}
+
+impl<S: Source> JsonReader<S> {
+    pub fn new(source: S) -> JsonReader<S> {
+        JsonReader {
+            source,
+            exhausted: false,
+            buf: Vec::new(),
+            pos: 0,
+            offset: 0,
+            line: 1,
+            column: 1,
+            stack: Vec::new(),
+            state: State::Value,
+            limits: LimitTracker::new(Limits::default()),
+            scratch: String::new(),
+            token: (0, 1, 1),
+        }
+    }
+
+    /// Bound the size of the document, see [`Limits`]
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = LimitTracker::new(limits);
+        self
+    }
+
+    /// The next event, `None` at the end of the document
+    pub fn next_event(&mut self) -> Result<Option<Event<'_>>, ParseError> {
+        let Some(token) = self.read_token()? else {
+            return Ok(None);
+        };
+
+        Ok(Some(match token {
+            Token::StartObject => Event::StartObject,
+            Token::EndObject => Event::EndObject,
+            Token::StartArray => Event::StartArray,
+            Token::EndArray => Event::EndArray,
+            Token::Key => Event::Key(&self.scratch),
+            Token::String => Event::Value(ValueRef::String(&self.scratch)),
+            Token::Number(number) => Event::Value(ValueRef::Number(number)),
+            Token::Bool(value) => Event::Value(ValueRef::Bool(value)),
+            Token::Null => Event::Value(ValueRef::Null),
+        }))
+    }
+
+    /// Read the next value whole.
+    /// Returns `None` at the end of the enclosing array or object, or of the document.
+    pub fn next_value(&mut self) -> Result<Option<Value>, ParseError> {
+        enum Partial {
+            List(List),
+            Map(Map, String),
+        }
+
+        let mut stack = Vec::new();
+
+        loop {
+            let Some(token) = self.read_token()? else {
+                return Ok(None);
+            };
+
+            let value = match token {
+                Token::StartObject => {
+                    stack.push(Partial::Map(Map::default(), String::new()));
+                    continue;
+                }
+                Token::StartArray => {
+                    stack.push(Partial::List(List::default()));
+                    continue;
+                }
+                Token::Key => match stack.last_mut() {
+                    Some(Partial::Map(_, key)) => {
+                        key.clone_from(&self.scratch);
+                        continue;
+                    }
+                    _ => return Err(self.mismatch("a value")),
+                },
+                Token::EndObject | Token::EndArray => match stack.pop() {
+                    Some(Partial::Map(map, _)) => Value::Map(map),
+                    Some(Partial::List(list)) => Value::List(list),
+                    None => return Ok(None),
+                },
+                Token::String => Value::String(self.scratch.as_str().into()),
+                Token::Number(number) => Value::Number(number),
+                Token::Bool(value) => Value::Bool(value),
+                Token::Null => Value::Null,
+            };
+
+            match stack.last_mut() {
+                None => return Ok(Some(value)),
+                Some(Partial::List(list)) => list.push(value),
+                Some(Partial::Map(map, key)) => {
+                    map.insert(key.as_str(), value);
+                }
+            }
+        }
+    }
+
+    /// Read the next key and its value whole, `None` at the end of the object
+    pub fn next_entry(&mut self) -> Result<Option<(String, Value)>, ParseError> {
+        match self.read_token()? {
+            Some(Token::Key) => {}
+            Some(Token::EndObject) => return Ok(None),
+            _ => return Err(self.mismatch("a key")),
+        }
+
+        let key = core::mem::take(&mut self.scratch);
+        match self.next_value()? {
+            Some(value) => Ok(Some((key, value))),
+            None => Err(self.mismatch("a value")),
+        }
+    }
+
+    /// Skip over the next value.
+    /// Returns `false` at the end of the enclosing array or object, or of the document.
+    pub fn skip_value(&mut self) -> Result<bool, ParseError> {
+        let mut depth = 0usize;
+
+        loop {
+            match self.read_token()? {
+                None => return Ok(false),
+                Some(Token::StartObject | Token::StartArray) => {
+                    depth += 1;
+                    continue;
+                }
+                Some(Token::EndObject | Token::EndArray) => {
+                    if depth == 0 {
+                        return Ok(false);
+                    }
+                    depth -= 1;
+                }
+                Some(Token::Key) if depth == 0 => return Err(self.mismatch("a value")),
+                Some(Token::Key) => continue,
+                Some(_) => {}
+            }
+
+            if depth == 0 {
+                return Ok(true);
+            }
+        }
+    }
+
+    /// Iterate over the elements of the array the reader is in, or of the
+    /// array that comes next when it is not in one
+    pub fn array_values(&mut self) -> ArrayValues<'_, S> {
+        ArrayValues {
+            reader: self,
+            started: false,
+            done: false,
+        }
+    }
+
+    /// The byte offset of the reader in the input
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    fn read_token(&mut self) -> Result<Option<Token>, ParseError> {
+        self.skip_ws()?;
+        self.mark();
+
+        match self.state {
+            State::Value => {}
+            State::Done => {
+                return match self.peek_at(0)? {
+                    None => Ok(None),
+                    Some(_) => Err(self.error(ParseErrorKind::TrailingCharacters, 0)),
+                }
+            }
+            State::ArrayFirst => {
+                if self.peek_at(0)? == Some(b']') {
+                    return Ok(Some(self.close(Token::EndArray)));
+                }
+            }
+            State::ArrayNext => match self.peek_at(0)? {
+                Some(b',') => {
+                    self.advance(1);
+                    self.skip_ws()?;
+                    self.mark();
+                }
+                Some(b']') => return Ok(Some(self.close(Token::EndArray))),
+                _ => return Err(self.unexpected("',' or ']'")),
+            },
+            State::ObjectFirst => {
+                if self.peek_at(0)? == Some(b'}') {
+                    return Ok(Some(self.close(Token::EndObject)));
+                }
+                return self.read_key().map(Some);
+            }
+            State::ObjectNext => match self.peek_at(0)? {
+                Some(b',') => {
+                    self.advance(1);
+                    self.skip_ws()?;
+                    self.mark();
+                    return self.read_key().map(Some);
+                }
+                Some(b'}') => return Ok(Some(self.close(Token::EndObject))),
+                _ => return Err(self.unexpected("',' or '}'")),
+            },
+        }
+
+        if matches!(self.state, State::ArrayFirst | State::ArrayNext) {
+            self.begin_item()?;
+        }
+        self.limit(true, LimitTracker::value)?;
+
+        let token = match self.peek_at(0)? {
+            None => return Err(self.error(ParseErrorKind::Eof, 0)),
+            Some(b'{') => return self.open(true).map(Some),
+            Some(b'[') => return self.open(false).map(Some),
+            Some(b'"') => {
+                self.read_str(true)?;
+                Token::String
+            }
+            Some(b't') => self.read_ident("true", Token::Bool(true))?,
+            Some(b'f') => self.read_ident("false", Token::Bool(false))?,
+            Some(b'n') => self.read_ident("null", Token::Null)?,
+            Some(b'-' | b'0'..=b'9') => Token::Number(self.read_number()?),
+            Some(_) => return Err(self.unexpected("a value")),
+        };
+
+        self.after_value();
+        Ok(Some(token))
+    }
+
+    fn read_key(&mut self) -> Result<Token, ParseError> {
+        if self.peek_at(0)? != Some(b'"') {
+            return Err(self.unexpected("a string key"));
+        }
+
+        self.begin_item()?;
+        self.read_str(false)?;
+        if let Some(frame) = self.stack.last_mut() {
+            frame.key.clone_from(&self.scratch);
+        }
+        self.skip_ws()?;
+        if self.peek_at(0)? != Some(b':') {
+            return Err(self.unexpected("':'"));
+        }
+        self.advance(1);
+
+        self.state = State::Value;
+        Ok(Token::Key)
+    }
+
+    /// Count the item that begins in the current array or object
+    fn begin_item(&mut self) -> Result<(), ParseError> {
+        let len = self.stack.last().map_or(0, |frame| frame.len);
+        self.limit(false, |limits| limits.item(len))?;
+        if let Some(frame) = self.stack.last_mut() {
+            frame.len += 1;
+        }
+        Ok(())
+    }
+
+    /// Count against the limits. The path of an error leads to the current item
+    /// when `in_item`, or else to the array or object it is in
+    fn limit<F>(&mut self, in_item: bool, check: F) -> Result<(), ParseError>
+    where
+        F: FnOnce(&mut LimitTracker) -> Result<(), LimitError>,
+    {
+        check(&mut self.limits).map_err(|err| {
+            let frames = match in_item {
+                true => &self.stack[..],
+                false => &self.stack[..self.stack.len().saturating_sub(1)],
+            };
+            let err = frames.iter().rev().fold(err, |err, frame| match frame.len {
+                0 => err,
+                _ if frame.object => err.with_field(frame.key.as_str()),
+                len => err.with_index(len - 1),
+            });
+            self.error(ParseErrorKind::Limit(err), 0)
+        })
+    }
+
+    fn open(&mut self, object: bool) -> Result<Token, ParseError> {
+        self.limit(true, LimitTracker::enter)?;
+
+        self.advance(1);
+        self.stack.push(Frame {
+            object,
+            len: 0,
+            key: String::new(),
+        });
+
+        Ok(if object {
+            self.state = State::ObjectFirst;
+            Token::StartObject
+        } else {
+            self.state = State::ArrayFirst;
+            Token::StartArray
+        })
+    }
+
+    fn close(&mut self, token: Token) -> Token {
+        self.advance(1);
+        self.stack.pop();
+        self.limits.leave();
+        self.after_value();
+        token
+    }
+
+    fn after_value(&mut self) {
+        self.state = match self.stack.last() {
+            None => State::Done,
+            Some(frame) if frame.object => State::ObjectNext,
+            Some(_) => State::ArrayNext,
+        };
+    }
+
+    fn read_ident(&mut self, ident: &'static str, token: Token) -> Result<Token, ParseError> {
+        for &byte in ident.as_bytes() {
+            if self.peek_at(0)? != Some(byte) {
+                return Err(self.unexpected(ident));
+            }
+            self.advance(1);
+        }
+        Ok(token)
+    }
+
+    fn read_number(&mut self) -> Result<Number, ParseError> {
+        // Buffer every byte that can be part of a number before scanning it
+        let mut len = 0;
+        while let Some(b'0'..=b'9' | b'+' | b'-' | b'.' | b'e' | b'E') = self.peek_at(len)? {
+            len += 1;
+        }
+
+        let bytes = &self.buf[self.pos..self.pos + len];
+        match scan_number(bytes) {
+            Ok(len) => {
+                let text = str::from_utf8(&bytes[..len]).expect("numbers are ASCII");
+                let number = number_from_text(text);
+                self.advance(len);
+                Ok(number)
+            }
+            Err(idx) => Err(self.error(ParseErrorKind::InvalidNumber, idx)),
+        }
+    }
+
+    /// Read a string into `scratch`, `value` is false for a key
+    fn read_str(&mut self, value: bool) -> Result<(), ParseError> {
+        let mut from = 0;
+        let end = loop {
+            match scan_str(&self.buf[self.pos + 1..], from) {
+                Ok(end) => break Some(end),
+                Err(idx) => {
+                    from = idx;
+                    if !self.fill()? {
+                        break None;
+                    }
+                }
+            }
+        };
+
+        let start = self.pos + 1;
+        let raw_end = end.map_or(self.buf.len(), |end| start + end);
+        let raw = match str::from_utf8(&self.buf[start..raw_end]) {
+            Ok(raw) => raw,
+            Err(err) => return Err(self.error(ParseErrorKind::InvalidUtf8, 1 + err.valid_up_to())),
+        };
+
+        match decode_str(raw) {
+            Ok(value) => {
+                self.scratch.clear();
+                self.scratch.push_str(&value);
+            }
+            Err((kind, idx)) => return Err(self.error(kind, 1 + idx)),
+        }
+
+        match end {
+            Some(end) => {
+                let len = self.scratch.len();
+                self.limit(value, |limits| limits.text(len))?;
+                self.advance(end + 2);
+                Ok(())
+            }
+            None => Err(self.error(ParseErrorKind::Eof, raw_end - self.pos)),
+        }
+    }
+
+    fn skip_ws(&mut self) -> Result<(), ParseError> {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek_at(0)? {
+            self.advance(1);
+        }
+        Ok(())
+    }
+
+    /// The byte `idx` bytes past the position, reading more input as needed
+    fn peek_at(&mut self, idx: usize) -> Result<Option<u8>, ParseError> {
+        while self.buf.len() - self.pos <= idx {
+            if !self.fill()? {
+                return Ok(None);
+            }
+        }
+        Ok(Some(self.buf[self.pos + idx]))
+    }
+
+    /// Read more input, dropping what has been consumed
+    fn fill(&mut self) -> Result<bool, ParseError> {
+        if self.exhausted {
+            return Ok(false);
+        }
+
+        if self.pos > 0 {
+            self.buf.drain(..self.pos);
+            self.pos = 0;
+        }
+
+        match self.source.fill(&mut self.buf) {
+            Ok(more) => {
+                self.exhausted = !more;
+                Ok(more)
+            }
+            Err(kind) => Err(self.error(kind, self.buf.len())),
+        }
+    }
+
+    fn advance(&mut self, len: usize) {
+        (self.line, self.column) = self.location(len);
+        self.pos += len;
+        self.offset += len;
+    }
+
+    /// Line and column `len` bytes past the position
+    fn location(&self, len: usize) -> (usize, usize) {
+        let end = (self.pos + len).min(self.buf.len());
+        self.buf[self.pos..end]
+            .iter()
+            .fold((self.line, self.column), |(line, column), &byte| {
+                if byte == b'\n' {
+                    (line + 1, 1)
+                } else if byte & 0xC0 != 0x80 {
+                    (line, column + 1)
+                } else {
+                    (line, column)
+                }
+            })
+    }
+
+    /// Remember the start of the token for `mismatch`
+    fn mark(&mut self) {
+        self.token = (self.offset, self.line, self.column);
+    }
+
+    /// An error `ahead` bytes past the position
+    fn error(&self, kind: ParseErrorKind, ahead: usize) -> ParseError {
+        let (line, column) = self.location(ahead);
+        let ahead = ahead.min(self.buf.len() - self.pos);
+        ParseError::at(kind, self.offset + ahead, line, column)
+    }
+
+    /// An error for the character at the position
+    fn unexpected(&mut self, expected: &'static str) -> ParseError {
+        // A character is at most four bytes
+        if let Err(err) = self.peek_at(3) {
+            return err;
+        }
+
+        let bytes = &self.buf[self.pos..(self.pos + 4).min(self.buf.len())];
+        let valid = match str::from_utf8(bytes) {
+            Ok(valid) => valid,
+            Err(err) => str::from_utf8(&bytes[..err.valid_up_to()]).unwrap_or_default(),
+        };
+
+        match valid.chars().next() {
+            Some(found) => self.error(ParseErrorKind::Unexpected { found, expected }, 0),
+            None if bytes.is_empty() => self.error(ParseErrorKind::Eof, 0),
+            None => self.error(ParseErrorKind::InvalidUtf8, 0),
+        }
+    }
+
+    /// An error for a token that is valid JSON, but not what was asked for
+    fn mismatch(&self, expected: &'static str) -> ParseError {
+        let (offset, line, column) = self.token;
+        ParseError::at(ParseErrorKind::Mismatch { expected }, offset, line, column)
+    }
+}
+
+/// The values of an array, see [`JsonReader::array_values`]
+pub struct ArrayValues<'a, S> {
+    reader: &'a mut JsonReader<S>,
+    started: bool,
+    done: bool,
+}
+
+impl<'a, S: Source> ArrayValues<'a, S> {
+    fn read(&mut self) -> Result<Option<Value>, ParseError> {
+        if !self.started {
+            self.started = true;
+            if !matches!(self.reader.state, State::ArrayFirst | State::ArrayNext) {
+                match self.reader.read_token()? {
+                    Some(Token::StartArray) => {}
+                    _ => return Err(self.reader.mismatch("an array")),
+                }
+            }
+        }
+
+        self.reader.next_value()
+    }
+}
+
+impl<'a, S: Source> Iterator for ArrayValues<'a, S> {
+    type Item = Result<Value, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let ret = self.read().transpose();
+        if !matches!(ret, Some(Ok(_))) {
+            self.done = true;
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, string::ToString, vec};
+
+    use super::*;
+    use crate::json::{parse, to_string};
+
+    /// The events in a compact form, values as JSON
+    fn events<S: Source>(reader: &mut JsonReader<S>) -> Result<Vec<String>, ParseError> {
+        let mut ret = Vec::new();
+        while let Some(event) = reader.next_event()? {
+            ret.push(match event {
+                Event::StartObject => "{".to_string(),
+                Event::EndObject => "}".to_string(),
+                Event::StartArray => "[".to_string(),
+                Event::EndArray => "]".to_string(),
+                Event::Key(key) => format!("{key}:"),
+                Event::Value(value) => to_string(&Value::from(value)),
+            });
+        }
+        Ok(ret)
+    }
+
+    #[test]
+    fn event_sequence() {
+        let mut reader =
+            JsonReader::from_slice(br#" {"a": [1, "x", true, null], "b": {}, "c": -1.5} "#);
+        assert_eq!(
+            events(&mut reader).unwrap(),
+            [
+                "{", "a:", "[", "1", "\"x\"", "true", "null", "]", "b:", "{", "}", "c:", "-1.5",
+                "}"
+            ]
+        );
+        assert_eq!(reader.next_event().unwrap(), None);
+
+        let mut reader = JsonReader::from_slice(b"\"top\"");
+        assert_eq!(events(&mut reader).unwrap(), ["\"top\""]);
+    }
+
+    #[test]
+    fn split_tokens() {
+        let input = r#"{"key \"quoted\"": ["héllo 😀 wörld", -12.5e3, 18446744073709551615, true, false, null]}"#;
+        let expected = parse(input).unwrap();
+
+        for size in 1..=input.len() {
+            let mut reader = JsonReader::from_chunks(input.as_bytes().chunks(size));
+            assert_eq!(
+                reader.next_value().unwrap().as_ref(),
+                Some(&expected),
+                "chunks of {size}"
+            );
+            assert_eq!(reader.next_value().unwrap(), None);
+        }
+
+        for split in 0..=input.len() {
+            let (a, b) = input.as_bytes().split_at(split);
+            let mut reader = JsonReader::from_chunks([a, b]);
+            assert_eq!(
+                reader.next_value().unwrap().as_ref(),
+                Some(&expected),
+                "split at {split}"
+            );
+        }
+    }
+
+    #[test]
+    fn split_numbers_end_with_the_input() {
+        for input in ["-0", "12", "1.5", "1e5", "-2.5E-3"] {
+            let expected = parse(input).unwrap();
+            let chunks = input.as_bytes().chunks(1);
+            let mut reader = JsonReader::from_chunks(chunks);
+            assert_eq!(reader.next_value().unwrap(), Some(expected), "{input}");
+        }
+
+        let mut reader = JsonReader::from_chunks([&b"[1."[..], b"]"]);
+        assert_eq!(reader.next_event().unwrap(), Some(Event::StartArray));
+        let err = reader.next_event().unwrap_err();
+        assert_eq!(err.kind(), &ParseErrorKind::InvalidNumber);
+    }
+
+    #[test]
+    fn next_entry() {
+        let mut reader = JsonReader::from_slice(br#"{"a": 1, "b": {"c": [true]}, "d": null}"#);
+        assert_eq!(reader.next_event().unwrap(), Some(Event::StartObject));
+
+        let mut entries = Vec::new();
+        while let Some((key, value)) = reader.next_entry().unwrap() {
+            entries.push(format!("{key}={}", to_string(&value)));
+        }
+        assert_eq!(entries, ["a=1", r#"b={"c":[true]}"#, "d=null"]);
+        assert_eq!(reader.next_event().unwrap(), None);
+
+        let mut reader = JsonReader::from_slice(b"[1]");
+        reader.next_event().unwrap();
+        assert!(reader.next_entry().is_err());
+    }
+
+    #[test]
+    fn skip_value() {
+        let mut reader = JsonReader::from_slice(br#"[{"a": [1, {"b": 2}]}, [], 3, "4"]"#);
+        assert_eq!(reader.next_event().unwrap(), Some(Event::StartArray));
+        assert!(reader.skip_value().unwrap());
+        assert!(reader.skip_value().unwrap());
+        assert_eq!(reader.next_value().unwrap(), Some(Value::from(3u8)));
+        assert!(reader.skip_value().unwrap());
+        assert!(!reader.skip_value().unwrap());
+        assert_eq!(reader.next_event().unwrap(), None);
+
+        let mut reader = JsonReader::from_slice(br#"{"skip": {"x": 1}, "keep": 2}"#);
+        reader.next_event().unwrap();
+        assert_eq!(reader.next_event().unwrap(), Some(Event::Key("skip")));
+        assert!(reader.skip_value().unwrap());
+        assert_eq!(
+            reader.next_entry().unwrap(),
+            Some(("keep".into(), Value::from(2u8)))
+        );
+    }
+
+    #[test]
+    fn array_values() {
+        let values = |input: &[u8]| {
+            JsonReader::from_slice(input)
+                .array_values()
+                .map(|value| value.map(|value| to_string(&value)))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        assert_eq!(
+            values(br#"[1, [2], {"a": 3}]"#).unwrap(),
+            ["1", "[2]", r#"{"a":3}"#]
+        );
+        assert_eq!(values(b"[]").unwrap(), Vec::<String>::new());
+        assert!(values(b"{}").is_err());
+        assert!(values(b"[1,]").is_err());
+
+        // Inside of a document
+        let mut reader = JsonReader::from_slice(br#"{"rows": [1, 2], "after": true}"#);
+        reader.next_event().unwrap();
+        reader.next_event().unwrap();
+        let rows = reader
+            .array_values()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(rows, [Value::from(1u8), Value::from(2u8)]);
+        assert_eq!(
+            reader.next_entry().unwrap(),
+            Some(("after".into(), Value::Bool(true)))
+        );
+
+        // Continues in an array the reader is in
+        let mut reader = JsonReader::from_slice(b"[1, 2, 3]");
+        reader.next_event().unwrap();
+        reader.skip_value().unwrap();
+        assert_eq!(reader.array_values().count(), 2);
+    }
+
+    #[test]
+    fn trailing_characters() {
+        let mut reader = JsonReader::from_slice(b"1 \n ");
+        assert_eq!(reader.next_value().unwrap(), Some(Value::from(1u8)));
+        assert_eq!(reader.next_value().unwrap(), None);
+
+        let mut reader = JsonReader::from_slice(b"{} []");
+        assert_eq!(
+            reader.next_value().unwrap(),
+            Some(Value::Map(Map::default()))
+        );
+        let err = reader.next_value().unwrap_err();
+        assert_eq!(err.kind(), &ParseErrorKind::TrailingCharacters);
+        assert_eq!((err.line(), err.column()), (1, 4));
+    }
+
+    #[test]
+    fn error_location() {
+        let mut reader = JsonReader::from_chunks(["{\n  \"a\": ", "[1,\n    tru]\n}"]);
+        let err = events(&mut reader).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &ParseErrorKind::Unexpected {
+                found: ']',
+                expected: "true"
+            }
+        );
+        assert_eq!((err.line(), err.column(), err.offset()), (3, 8, 20));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_source() {
+        /// Hands out one byte per read, and fails after `fail_at` bytes
+        struct Trickle<'a> {
+            input: &'a [u8],
+            fail_at: Option<usize>,
+            read: usize,
+        }
+
+        impl std::io::Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                if self.fail_at == Some(self.read) {
+                    return Err(std::io::ErrorKind::BrokenPipe.into());
+                }
+                let Some((first, rest)) = self.input.split_first() else {
+                    return Ok(0);
+                };
+                buf[0] = *first;
+                self.input = rest;
+                self.read += 1;
+                Ok(1)
+            }
+        }
+
+        let input = r#"{"a": ["b", 1.5, {"c": "é"}]}"#.as_bytes();
+        let mut reader = JsonReader::from_reader(Trickle {
+            input,
+            fail_at: None,
+            read: 0,
+        });
+        assert_eq!(
+            reader.next_value().unwrap(),
+            Some(parse(core::str::from_utf8(input).unwrap()).unwrap())
+        );
+
+        let mut reader = JsonReader::from_reader(std::io::Cursor::new(vec![b'['; 3]));
+        assert_eq!(
+            events(&mut reader).unwrap_err().kind(),
+            &ParseErrorKind::Eof
+        );
+
+        let mut reader = JsonReader::from_reader(Trickle {
+            input,
+            fail_at: Some(5),
+            read: 0,
+        });
+        assert_eq!(
+            reader.next_value().unwrap_err().kind(),
+            &ParseErrorKind::Io(std::io::ErrorKind::BrokenPipe)
+        );
+    }
+}
@@ -1,69 +1,432 @@
 use alloc::string::String;
-use core::fmt::{Result, Write};
+use core::fmt::{self, Write};
 
+use super::{
+    emitter::{JsonEmitter, Output},
+    error::WriteError,
+    raw::RawJson,
+    value::JsonValue,
+};
 use crate::{List, Map, Value};
 
-pub fn to_string(value: &Value) -> String {
-    let mut out = String::new();
-    display(value, &mut out).expect("should not fail");
-    out
+/// What to write for NaN and infinite floats, which JSON cannot represent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFinite {
+    /// Fail with `WriteError::NonFinite`
+    Error,
+    /// Write `null`
+    #[default]
+    Null,
+    /// Write the strings `"NaN"`, `"Infinity"` and `"-Infinity"`
+    String,
 }
 
-pub fn display<W: Write>(value: &Value, output: &mut W) -> Result {
-    match value {
-        Value::Bool(b) => write!(output, "{b}"),
-        Value::String(s) => write!(output, "\"{s}\""),
-        Value::Map(m) => display_object(m, output),
-        Value::List(m) => display_list(m, output),
-        Value::Bytes(_) => todo!(),
-        Value::Date(_) => todo!(),
-        Value::DateTime(time) => {
-            write!(output, "\"{}\"", time.and_utc().to_rfc3339())
-        }
-        Value::Time(_) => todo!(),
-        Value::Uuid(id) => {
-            write!(output, "\"{}\"", id.as_hyphenated())
-        }
-        Value::Number(n) => {
-            write!(output, "{n}")
-        }
-        Value::Char(c) => write!(output, "\"{c}\""),
-        Value::Null => output.write_str("null"),
-    }
+/// How `Value::Bytes` is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BytesEncoding {
+    /// A padded standard base64 string
+    #[default]
+    Base64,
+    /// A string of lowercase hex digits
+    Hex,
+    /// A list of numbers
+    Array,
+}
+
+/// How `Value::Date`, `Value::Time` and `Value::DateTime` are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TemporalEncoding {
+    /// ISO 8601 strings, datetimes in UTC like `2024-01-02T12:30:00Z`
+    #[default]
+    Rfc3339,
+    /// ISO 8601 strings, datetimes without an offset like `2024-01-02T12:30:00`
+    Naive,
+    /// Seconds since the Unix epoch, dates at midnight and times since midnight
+    Timestamp,
+    /// Like `Timestamp` in milliseconds
+    TimestampMillis,
+}
+
+/// Controls how values are written as JSON.
+///
+/// The default writes compact JSON in the order of the maps.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DisplayOptions {
+    pub(super) indent: Option<String>,
+    pub(super) sort_keys: bool,
+    pub(super) ascii: bool,
+    pub(super) non_finite: NonFinite,
+    pub(super) bytes: BytesEncoding,
+    pub(super) temporal: TemporalEncoding,
+    pub(super) extended: bool,
+    pub(super) canonical: bool,
 }
 
-pub fn display_list<W: Write>(value: &List, output: &mut W) -> Result {
-    output.write_char('[')?;
+impl DisplayOptions {
+    /// Pretty printed with an indentation of two spaces
+    pub fn pretty() -> DisplayOptions {
+        DisplayOptions::default().indent("  ")
+    }
 
-    for (idx, key) in value.iter().enumerate() {
-        if idx > 0 {
-            output.write_char(',')?;
+    /// Canonical JSON as specified by RFC 8785, see [`to_canonical_string`](super::to_canonical_string).
+    ///
+    /// Keys are sorted by UTF-16 code units and numbers are formatted like ECMAScript does.
+    /// Changing the other options afterwards gives output that is no longer canonical.
+    pub fn canonical() -> DisplayOptions {
+        DisplayOptions {
+            indent: None,
+            sort_keys: true,
+            ascii: false,
+            non_finite: NonFinite::Error,
+            bytes: BytesEncoding::Base64,
+            temporal: TemporalEncoding::Rfc3339,
+            extended: false,
+            canonical: true,
         }
+    }
+
+    /// Put every item on a line of its own, indented by `indent` per level
+    pub fn indent(mut self, indent: impl Into<String>) -> Self {
+        self.indent = Some(indent.into());
+        self
+    }
+
+    /// Write everything on one line without whitespace
+    pub fn compact(mut self) -> Self {
+        self.indent = None;
+        self
+    }
+
+    /// Write the keys of maps in sorted order
+    pub fn sort_keys(mut self, sort: bool) -> Self {
+        self.sort_keys = sort;
+        self
+    }
+
+    /// Escape every character outside of ASCII
+    pub fn ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
+    pub fn non_finite(mut self, non_finite: NonFinite) -> Self {
+        self.non_finite = non_finite;
+        self
+    }
 
-        display(key, output)?
+    pub fn bytes(mut self, bytes: BytesEncoding) -> Self {
+        self.bytes = bytes;
+        self
     }
 
-    output.write_char(']')?;
+    pub fn temporal(mut self, temporal: TemporalEncoding) -> Self {
+        self.temporal = temporal;
+        self
+    }
 
-    Ok(())
+    /// Write extended JSON, which keeps the exact type of every value.
+    /// Read it back with [`ParseOptions::extended`](super::ParseOptions::extended).
+    ///
+    /// This takes precedence over the `non_finite`, `bytes` and `temporal` encodings.
+    pub fn extended(mut self, extended: bool) -> Self {
+        self.extended = extended;
+        self
+    }
 }
 
-pub fn display_object<W: Write>(value: &Map, output: &mut W) -> Result {
-    output.write_char('{')?;
+/// A value the JSON writers can write
+pub trait ToJson {
+    fn write_json<O: Output>(&self, emitter: &mut JsonEmitter<O>) -> Result<(), WriteError>;
+}
 
-    for (idx, (key, value)) in value.iter().enumerate() {
-        if idx > 0 {
-            output.write_char(',')?;
-        }
+impl ToJson for Value {
+    fn write_json<O: Output>(&self, emitter: &mut JsonEmitter<O>) -> Result<(), WriteError> {
+        emitter.value(self)
+    }
+}
+
+impl ToJson for Map {
+    fn write_json<O: Output>(&self, emitter: &mut JsonEmitter<O>) -> Result<(), WriteError> {
+        emitter.object(self)
+    }
+}
+
+impl ToJson for List {
+    fn write_json<O: Output>(&self, emitter: &mut JsonEmitter<O>) -> Result<(), WriteError> {
+        emitter.list(self)
+    }
+}
 
-        write!(output, "\"{key}\":")?;
+impl ToJson for JsonValue {
+    fn write_json<O: Output>(&self, emitter: &mut JsonEmitter<O>) -> Result<(), WriteError> {
+        emitter.json_value(self)
+    }
+}
 
-        display(value, output)?
+impl ToJson for RawJson {
+    fn write_json<O: Output>(&self, emitter: &mut JsonEmitter<O>) -> Result<(), WriteError> {
+        emitter.raw(self)
     }
+}
+
+/// Write `value` as compact JSON
+pub fn to_string<T: ToJson + ?Sized>(value: &T) -> String {
+    let mut out = String::new();
+    display(value, &mut out).expect("should not fail");
+    out
+}
+
+/// Write `value` as JSON formatted by `options`
+pub fn to_string_with<T: ToJson + ?Sized>(
+    value: &T,
+    options: &DisplayOptions,
+) -> Result<String, WriteError> {
+    let mut out = String::new();
+    display_with(value, &mut out, options)?;
+    Ok(out)
+}
+
+/// Write `value` to `writer` as it goes, without building the document in memory
+#[cfg(feature = "std")]
+pub fn to_writer<W: std::io::Write, T: ToJson + ?Sized>(
+    writer: W,
+    value: &T,
+    options: &DisplayOptions,
+) -> Result<(), WriteError> {
+    value.write_json(&mut JsonEmitter::from_writer(writer, options.clone()))
+}
+
+/// Write `value` to `writer` as it goes, without building the document in memory
+pub fn to_fmt_writer<W: Write, T: ToJson + ?Sized>(
+    writer: W,
+    value: &T,
+    options: &DisplayOptions,
+) -> Result<(), WriteError> {
+    value.write_json(&mut JsonEmitter::new(writer, options.clone()))
+}
 
-    output.write_char('}')?;
+pub fn display<W: Write, T: ToJson + ?Sized>(value: &T, output: &mut W) -> fmt::Result {
+    display_with(value, output, &DisplayOptions::default()).map_err(|_| fmt::Error)
+}
+
+pub fn display_list<W: Write>(value: &List, output: &mut W) -> fmt::Result {
+    display(value, output)
+}
+
+pub fn display_object<W: Write>(value: &Map, output: &mut W) -> fmt::Result {
+    display(value, output)
+}
 
-    Ok(())
+pub fn display_with<W: Write, T: ToJson + ?Sized>(
+    value: &T,
+    output: &mut W,
+    options: &DisplayOptions,
+) -> Result<(), WriteError> {
+    to_fmt_writer(output, value, options)
 }
 
-pub struct DisplayOptions {}
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec, vec::Vec};
+    use chrono::{NaiveDate, NaiveTime};
+
+    use super::*;
+    use crate::{bytes::Bytes, json::parse, Number};
+
+    fn json(input: &str) -> Value {
+        parse(input).unwrap()
+    }
+
+    fn list(values: Vec<Value>) -> Value {
+        Value::List(values.into())
+    }
+
+    fn write(value: &Value, options: &DisplayOptions) -> String {
+        to_string_with(value, options).unwrap()
+    }
+
+    #[test]
+    fn escapes() {
+        let value = Value::from("quote \" backslash \\ slash / \n\r\t\u{08}\u{0C}\u{01}\u{1f}");
+        assert_eq!(
+            to_string(&value),
+            r#""quote \" backslash \\ slash / \n\r\t\b\f\u0001\u001f""#
+        );
+
+        let value = Value::from("æ€𝄞\u{7f}");
+        assert_eq!(to_string(&value), "\"æ€𝄞\u{7f}\"");
+        assert_eq!(
+            write(&value, &DisplayOptions::default().ascii(true)),
+            "\"\\u00e6\\u20ac\\ud834\\udd1e\u{7f}\""
+        );
+        assert_eq!(to_string(&Value::Char('"')), r#""\"""#);
+    }
+
+    #[test]
+    fn non_finite() {
+        let value = list(vec![
+            Value::from(1.5),
+            Value::from(f64::NAN),
+            Value::from(f64::INFINITY),
+            Value::from(f32::NEG_INFINITY),
+        ]);
+
+        assert_eq!(to_string(&value), "[1.5,null,null,null]");
+        assert_eq!(
+            write(
+                &value,
+                &DisplayOptions::default().non_finite(NonFinite::Null)
+            ),
+            "[1.5,null,null,null]"
+        );
+        assert_eq!(
+            write(
+                &value,
+                &DisplayOptions::default().non_finite(NonFinite::String)
+            ),
+            r#"[1.5,"NaN","Infinity","-Infinity"]"#
+        );
+
+        let error = DisplayOptions::default().non_finite(NonFinite::Error);
+        let err = to_string_with(&value, &error).unwrap_err();
+        assert!(matches!(err, WriteError::NonFinite(n) if n.is_nan()));
+        assert_eq!(
+            to_string_with(&Value::from(f64::INFINITY), &error)
+                .unwrap_err()
+                .to_string(),
+            "inf cannot be written as JSON"
+        );
+        assert_eq!(write(&Value::from(1.5), &error), "1.5");
+    }
+
+    #[test]
+    fn numbers() {
+        let value = list(vec![
+            Value::from(1u8),
+            Value::from(-1i64),
+            Value::from(u64::MAX),
+            Value::from(1.0),
+            Value::from(0.5f32),
+            Value::from(1e300),
+            Value::Number(Number::F64(-0.0)),
+        ]);
+        assert_eq!(
+            to_string(&value),
+            "[1,-1,18446744073709551615,1.0,0.5,1e300,-0.0]"
+        );
+    }
+
+    #[test]
+    fn bytes() {
+        let value = Value::Bytes(Bytes::from(&b"\x00hi\xff"[..]));
+
+        assert_eq!(to_string(&value), r#""AGhp/w==""#);
+        assert_eq!(
+            write(
+                &value,
+                &DisplayOptions::default().bytes(BytesEncoding::Base64)
+            ),
+            r#""AGhp/w==""#
+        );
+        assert_eq!(
+            write(&value, &DisplayOptions::default().bytes(BytesEncoding::Hex)),
+            r#""006869ff""#
+        );
+        assert_eq!(
+            write(
+                &value,
+                &DisplayOptions::default().bytes(BytesEncoding::Array)
+            ),
+            "[0,104,105,255]"
+        );
+        assert_eq!(
+            write(
+                &Value::Bytes(Bytes::from(&b""[..])),
+                &DisplayOptions::default().bytes(BytesEncoding::Array)
+            ),
+            "[]"
+        );
+    }
+
+    #[test]
+    fn temporal() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let time = NaiveTime::from_hms_milli_opt(12, 30, 0, 250).unwrap();
+        let value = list(vec![
+            Value::Date(date),
+            Value::Time(time),
+            Value::DateTime(date.and_time(time)),
+        ]);
+        let with = |temporal| write(&value, &DisplayOptions::default().temporal(temporal));
+
+        assert_eq!(
+            to_string(&value),
+            r#"["2024-01-02","12:30:00.250","2024-01-02T12:30:00.250Z"]"#
+        );
+        assert_eq!(
+            with(TemporalEncoding::Rfc3339),
+            r#"["2024-01-02","12:30:00.250","2024-01-02T12:30:00.250Z"]"#
+        );
+        assert_eq!(
+            with(TemporalEncoding::Naive),
+            r#"["2024-01-02","12:30:00.250","2024-01-02T12:30:00.250"]"#
+        );
+        assert_eq!(
+            with(TemporalEncoding::Timestamp),
+            "[1704153600,45000.25,1704198600]"
+        );
+        assert_eq!(
+            with(TemporalEncoding::TimestampMillis),
+            "[1704153600000,45000250,1704198600250]"
+        );
+
+        let midnight = Value::Time(NaiveTime::MIN);
+        assert_eq!(
+            write(
+                &midnight,
+                &DisplayOptions::default().temporal(TemporalEncoding::Timestamp)
+            ),
+            "0"
+        );
+    }
+
+    #[test]
+    fn sort_keys() {
+        let value = json(r#"{"b": 1, "a": {"d": 2, "c": 3}, "B": 4}"#);
+
+        assert_eq!(
+            write(&value, &DisplayOptions::default().sort_keys(true)),
+            r#"{"B":4,"a":{"c":3,"d":2},"b":1}"#
+        );
+    }
+
+    #[test]
+    fn pretty() {
+        let value = json(r#"{"a": [1, {}, []], "b": {"c": null}}"#);
+
+        assert_eq!(
+            write(&value, &DisplayOptions::pretty().sort_keys(true)),
+            "{\n  \"a\": [\n    1,\n    {},\n    []\n  ],\n  \"b\": {\n    \"c\": null\n  }\n}"
+        );
+        assert_eq!(
+            write(&json("[1, 2]"), &DisplayOptions::default().indent("\t")),
+            "[\n\t1,\n\t2\n]"
+        );
+        assert_eq!(
+            write(&value, &DisplayOptions::pretty().sort_keys(true).compact()),
+            r#"{"a":[1,{},[]],"b":{"c":null}}"#
+        );
+    }
+
+    #[test]
+    fn display_functions() {
+        let value = json(r#"{"a": [1, "b"]}"#);
+        // A single key, maps are not ordered
+
+        let mut out = String::new();
+        super::display(&value, &mut out).unwrap();
+        display_list(json("[true]").as_list().unwrap(), &mut out).unwrap();
+        display_object(value.as_map().unwrap(), &mut out).unwrap();
+        assert_eq!(out, r#"{"a":[1,"b"]}[true]{"a":[1,"b"]}"#);
+    }
+}
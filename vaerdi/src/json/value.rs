@@ -1,8 +1,23 @@
-use alloc::string::ToString;
+use alloc::{borrow::Cow, string::ToString, vec::Vec};
 use base64::Engine;
+use core::fmt;
 
-use crate::{List, Map, Number, String, Value};
+use super::{
+    emitter::JsonEmitter,
+    error::{ParseError, ParseErrorKind},
+    options::{field_schema, item_schema, ParseOptions},
+    raw::RawJson,
+    reader::{Event, JsonReader},
+    to_string::DisplayOptions,
+};
+use crate::{limits::Limits, schema::TypeSchema, List, Map, Number, String, Value, ValueRef};
 
+/// A value of the JSON data model, without the types `Value` has on top of it.
+///
+/// `Raw` holds a subtree that is written as it is and only parsed when it is
+/// converted to a `Value`. Path access does not look into it.
+///
+/// `Display` writes it as compact JSON, or pretty printed with `{:#}`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum JsonValue {
     Object(Map<JsonValue>),
@@ -11,6 +26,256 @@ pub enum JsonValue {
     Bool(bool),
     Number(Number),
     Null,
+    Raw(RawJson),
+}
+
+macro_rules! is_method {
+    ($check: ident, $ty: ident) => {
+        pub fn $check(&self) -> bool {
+            matches!(self, JsonValue::$ty(_))
+        }
+    };
+}
+
+macro_rules! into_method {
+    ($into: ident, $ty: ident, $oty: ty) => {
+        pub fn $into(self) -> Result<$oty, JsonValue> {
+            match self {
+                JsonValue::$ty(v) => Ok(v),
+                _ => Err(self),
+            }
+        }
+    };
+}
+
+macro_rules! as_method {
+    ($as: ident, $as_mut: ident, $ty: ident, $oty: ty) => {
+        pub fn $as(&self) -> Option<&$oty> {
+            match self {
+                JsonValue::$ty(v) => Some(v),
+                _ => None,
+            }
+        }
+
+        pub fn $as_mut(&mut self) -> Option<&mut $oty> {
+            match self {
+                JsonValue::$ty(v) => Some(v),
+                _ => None,
+            }
+        }
+    };
+}
+
+impl JsonValue {
+    pub fn is_null(&self) -> bool {
+        matches!(self, JsonValue::Null)
+    }
+
+    is_method!(is_object, Object);
+    is_method!(is_list, List);
+    is_method!(is_string, String);
+    is_method!(is_bool, Bool);
+    is_method!(is_number, Number);
+    is_method!(is_raw, Raw);
+
+    as_method!(as_object, as_object_mut, Object, Map<JsonValue>);
+    as_method!(as_list, as_list_mut, List, List<JsonValue>);
+    as_method!(as_string, as_string_mut, String, String);
+    as_method!(as_bool, as_bool_mut, Bool, bool);
+    as_method!(as_number, as_number_mut, Number, Number);
+    as_method!(as_raw, as_raw_mut, Raw, RawJson);
+
+    into_method!(into_object, Object, Map<JsonValue>);
+    into_method!(into_list, List, List<JsonValue>);
+    into_method!(into_string, String, String);
+    into_method!(into_bool, Bool, bool);
+    into_method!(into_number, Number, Number);
+    into_method!(into_raw, Raw, RawJson);
+
+    pub fn as_str(&self) -> Option<&str> {
+        self.as_string().map(|s| s.as_str())
+    }
+
+    pub fn get<S: AsRef<str>>(&self, field: S) -> Option<&JsonValue> {
+        self.as_object()?.get(field)
+    }
+
+    pub fn get_mut<S: AsRef<str>>(&mut self, field: S) -> Option<&mut JsonValue> {
+        self.as_object_mut()?.get_mut(field)
+    }
+
+    pub fn remove<S: AsRef<str>>(&mut self, field: S) -> Option<JsonValue> {
+        self.as_object_mut()?.remove(field)
+    }
+
+    pub fn insert<S: AsRef<str>, V: Into<JsonValue>>(
+        &mut self,
+        field: S,
+        value: V,
+    ) -> Option<JsonValue> {
+        self.as_object_mut()?.insert(field.as_ref(), value.into())
+    }
+
+    /// The value at a JSON Pointer (RFC 6901) like `/items/0/name`.
+    /// The empty pointer is the value itself.
+    pub fn pointer(&self, pointer: &str) -> Option<&JsonValue> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+
+        pointer
+            .strip_prefix('/')?
+            .split('/')
+            .try_fold(self, |value, token| match value {
+                JsonValue::Object(map) => map.get(unescape_token(token)),
+                JsonValue::List(list) => list.get(list_index(token)?),
+                _ => None,
+            })
+    }
+
+    /// The value at a JSON Pointer, see [`pointer`](Self::pointer)
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut JsonValue> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+
+        pointer
+            .strip_prefix('/')?
+            .split('/')
+            .try_fold(self, |value, token| match value {
+                JsonValue::Object(map) => map.get_mut(unescape_token(token)),
+                JsonValue::List(list) => list.get_mut(list_index(token)?),
+                _ => None,
+            })
+    }
+
+    /// Convert to a `Value`, turning strings and numbers into the types
+    /// detected by `options` like [`parse_with`](super::parse_with) does
+    pub fn into_value_with(self, options: &ParseOptions) -> Value {
+        self.into_value_at(options, options.root())
+    }
+
+    fn into_value_at(self, options: &ParseOptions, schema: Option<&TypeSchema>) -> Value {
+        match self {
+            JsonValue::Object(o) => Value::Map(
+                o.into_iter()
+                    .map(|(k, v)| {
+                        let v = v.into_value_at(options, field_schema(schema, &k));
+                        (k, v)
+                    })
+                    .collect(),
+            ),
+            JsonValue::List(l) => Value::List(
+                l.into_iter()
+                    .enumerate()
+                    .map(|(idx, v)| v.into_value_at(options, item_schema(schema, idx)))
+                    .collect(),
+            ),
+            JsonValue::String(s) => match options.convert_str(&s, schema) {
+                Some(ret) => ret,
+                None => Value::String(s),
+            },
+            JsonValue::Number(n) => Value::Number(options.number(n, schema)),
+            JsonValue::Bool(b) => Value::Bool(b),
+            JsonValue::Null => Value::Null,
+            JsonValue::Raw(raw) => JsonValue::from(raw.into_value()).into_value_at(options, schema),
+        }
+    }
+}
+
+/// A reference token of a JSON Pointer with `~1` and `~0` decoded
+fn unescape_token(token: &str) -> Cow<'_, str> {
+    if token.contains('~') {
+        Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+    } else {
+        Cow::Borrowed(token)
+    }
+}
+
+/// An array index of a JSON Pointer, which has no sign or leading zeros
+fn list_index(token: &str) -> Option<usize> {
+    if token.is_empty()
+        || !token.bytes().all(|byte| byte.is_ascii_digit())
+        || (token.len() > 1 && token.starts_with('0'))
+    {
+        return None;
+    }
+    token.parse().ok()
+}
+
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = if f.alternate() {
+            DisplayOptions::pretty()
+        } else {
+            DisplayOptions::default()
+        };
+        JsonEmitter::new(f, options)
+            .json_value(self)
+            .map_err(|_| fmt::Error)
+    }
+}
+
+/// Parse `input` into a `JsonValue`, leaving strings and numbers as they are
+pub fn parse_json_value(input: &str) -> Result<JsonValue, ParseError> {
+    parse_json_value_with_limits(input, Limits::default())
+}
+
+/// Parse `input` into a `JsonValue` within `limits`
+pub fn parse_json_value_with_limits(input: &str, limits: Limits) -> Result<JsonValue, ParseError> {
+    enum Partial {
+        List(List<JsonValue>),
+        Object(Map<JsonValue>, alloc::string::String),
+    }
+
+    let mut reader = JsonReader::from_slice(input.as_bytes()).limits(limits);
+    let mut stack = Vec::new();
+
+    let value = loop {
+        let Some(event) = reader.next_event()? else {
+            return Err(ParseError::new(ParseErrorKind::Eof, input, input.len()));
+        };
+
+        let value = match event {
+            Event::StartObject => {
+                stack.push(Partial::Object(Map::default(), Default::default()));
+                continue;
+            }
+            Event::StartArray => {
+                stack.push(Partial::List(List::default()));
+                continue;
+            }
+            Event::Key(name) => {
+                if let Some(Partial::Object(_, key)) = stack.last_mut() {
+                    key.clear();
+                    key.push_str(name);
+                }
+                continue;
+            }
+            Event::EndObject | Event::EndArray => match stack.pop() {
+                Some(Partial::Object(map, _)) => JsonValue::Object(map),
+                Some(Partial::List(list)) => JsonValue::List(list),
+                None => continue,
+            },
+            Event::Value(ValueRef::String(s)) => JsonValue::String(s.into()),
+            Event::Value(ValueRef::Number(n)) => JsonValue::Number(n),
+            Event::Value(ValueRef::Bool(b)) => JsonValue::Bool(b),
+            Event::Value(_) => JsonValue::Null,
+        };
+
+        match stack.last_mut() {
+            None => break value,
+            Some(Partial::List(list)) => list.push(value),
+            Some(Partial::Object(map, key)) => {
+                map.insert(key.as_str(), value);
+            }
+        }
+    };
+
+    // Fails on anything but whitespace after the value
+    reader.next_event()?;
+
+    Ok(value)
 }
 
 impl From<Value> for JsonValue {
@@ -53,21 +318,37 @@ impl From<JsonValue> for Value {
         match value {
             JsonValue::Object(o) => Value::Map(o.into()),
             JsonValue::List(l) => Value::List(l.into()),
-            JsonValue::String(s) => {
-                if s.len() == 36 {
-                    if let Ok(id) = uuid::Uuid::parse_str(&s) {
-                        return Value::Uuid(id);
-                    }
-                }
-                Value::String(s)
-            }
+            JsonValue::String(s) => Value::String(s),
             JsonValue::Bool(b) => Value::Bool(b),
             JsonValue::Number(n) => Value::Number(n),
             JsonValue::Null => Value::Null,
+            JsonValue::Raw(raw) => raw.into_value(),
         }
     }
 }
 
+impl<'a> From<&'a JsonValue> for Value {
+    fn from(value: &'a JsonValue) -> Self {
+        match value {
+            JsonValue::Object(o) => {
+                Value::Map(o.iter().map(|(k, v)| (k.clone(), v.into())).collect())
+            }
+            JsonValue::List(l) => Value::List(l.iter().map(Into::into).collect()),
+            JsonValue::String(s) => Value::String(s.clone()),
+            JsonValue::Bool(b) => Value::Bool(*b),
+            JsonValue::Number(n) => Value::Number(*n),
+            JsonValue::Null => Value::Null,
+            JsonValue::Raw(raw) => raw.into(),
+        }
+    }
+}
+
+impl From<RawJson> for JsonValue {
+    fn from(value: RawJson) -> Self {
+        JsonValue::Raw(value)
+    }
+}
+
 impl From<List<JsonValue>> for List<Value> {
     fn from(value: List<JsonValue>) -> Self {
         value.into_iter().map(Into::into).collect()
@@ -91,3 +372,290 @@ impl From<Map<Value>> for Map<JsonValue> {
         value.into_iter().map(|(k, v)| (k, v.into())).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, string::ToString, vec};
+
+    use super::*;
+    use crate::limits::Limit;
+
+    /// The document of RFC 6901 section 5, and a key with an escaped `~1`
+    const POINTER_DOCUMENT: &str = r#"{
+        "foo": ["bar", "baz"],
+        "": 0,
+        "a/b": 1,
+        "c%d": 2,
+        "e^f": 3,
+        "g|h": 4,
+        "i\\j": 5,
+        "k\"l": 6,
+        " ": 7,
+        "m~n": 8,
+        "~1": 9
+    }"#;
+
+    fn json(input: &str) -> JsonValue {
+        parse_json_value(input).unwrap()
+    }
+
+    fn string(s: &str) -> JsonValue {
+        JsonValue::String(s.into())
+    }
+
+    #[test]
+    fn accessors() {
+        let mut value = json(r#"{"list": [1, "two", true, null], "raw": 1}"#);
+        assert!(value.is_object());
+        assert!(!value.is_list() && !value.is_null() && !value.is_raw());
+        assert_eq!(value.as_object().map(Map::len), Some(2));
+        assert_eq!(value.as_list(), None);
+        assert_eq!(value.as_str(), None);
+
+        let list = value.get("list").and_then(JsonValue::as_list).unwrap();
+        assert_eq!(list.len(), 4);
+        assert!(
+            list.get(0).unwrap().is_number()
+                && list.get(1).unwrap().is_string()
+                && list.get(2).unwrap().is_bool()
+                && list.get(3).unwrap().is_null()
+        );
+        assert_eq!(list.get(1).unwrap().as_str(), Some("two"));
+        assert_eq!(list.get(2).unwrap().as_bool(), Some(&true));
+        assert_eq!(list.get(0).unwrap().as_string(), None);
+
+        *value
+            .get_mut("list")
+            .unwrap()
+            .as_list_mut()
+            .unwrap()
+            .get_mut(2)
+            .unwrap()
+            .as_bool_mut()
+            .unwrap() = false;
+        assert_eq!(value.pointer("/list/2"), Some(&JsonValue::Bool(false)));
+
+        assert_eq!(
+            value.insert("raw", RawJson::new("[1, 2]").unwrap()),
+            Some(json("1"))
+        );
+        assert!(value.get("raw").unwrap().is_raw());
+        assert_eq!(
+            value
+                .get("raw")
+                .and_then(JsonValue::as_raw)
+                .map(RawJson::as_str),
+            Some("[1, 2]")
+        );
+        assert_eq!(value.insert("new", string("value")), None);
+        assert_eq!(value.remove("new"), Some(string("value")));
+        assert_eq!(value.remove("new"), None);
+        assert_eq!(value.get("missing"), None);
+
+        // Field access on anything but an object does nothing
+        let mut list = json("[1]");
+        assert_eq!(list.get("0"), None);
+        assert_eq!(list.insert("key", JsonValue::Bool(true)), None);
+        assert_eq!(list.remove("key"), None);
+        assert_eq!(list, json("[1]"));
+
+        assert_eq!(string("s").into_string(), Ok("s".into()));
+        assert_eq!(string("s").into_bool(), Err(string("s")));
+        assert_eq!(json("true").into_bool(), Ok(true));
+        assert_eq!(json("[]").into_list(), Ok(List::default()));
+        assert_eq!(json("{}").into_object(), Ok(Map::default()));
+        assert_eq!(json("null").into_number(), Err(JsonValue::Null));
+        assert!(json("1").into_number().is_ok());
+        assert!(JsonValue::from(RawJson::new("1").unwrap())
+            .into_raw()
+            .is_ok());
+    }
+
+    #[test]
+    fn pointer() {
+        let value = json(POINTER_DOCUMENT);
+
+        assert_eq!(value.pointer(""), Some(&value));
+        assert_eq!(value.pointer("/foo"), Some(&json(r#"["bar", "baz"]"#)));
+        assert_eq!(value.pointer("/foo/0"), Some(&string("bar")));
+        assert_eq!(value.pointer("/foo/1"), Some(&string("baz")));
+
+        let expected = [
+            ("/", 0),
+            ("/a~1b", 1),
+            ("/c%d", 2),
+            ("/e^f", 3),
+            ("/g|h", 4),
+            ("/i\\j", 5),
+            ("/k\"l", 6),
+            ("/ ", 7),
+            ("/m~0n", 8),
+            // `~01` is `~1`, not `/`
+            ("/~01", 9),
+        ];
+        for (pointer, n) in expected {
+            assert_eq!(
+                value.pointer(pointer),
+                Some(&json(&n.to_string())),
+                "{pointer}"
+            );
+        }
+
+        let missing = [
+            "foo",
+            "/bar",
+            "/a/b",
+            "/foo/2",
+            "/foo/-",
+            "/foo/01",
+            "/foo/+1",
+            "/foo/-1",
+            "/foo/",
+            "/foo/0/bar",
+            "/m~n/x",
+        ];
+        for pointer in missing {
+            assert_eq!(value.pointer(pointer), None, "{pointer}");
+        }
+
+        // A raw subtree is not looked into
+        let raw = JsonValue::Raw(RawJson::new(r#"{"a": 1}"#).unwrap());
+        assert_eq!(raw.pointer("/a"), None);
+        assert_eq!(raw.pointer(""), Some(&raw));
+    }
+
+    #[test]
+    fn pointer_mut() {
+        let mut value = json(POINTER_DOCUMENT);
+
+        *value.pointer_mut("/foo/1").unwrap() = JsonValue::Null;
+        *value.pointer_mut("/a~1b").unwrap() = string("slash");
+        *value.pointer_mut("/m~0n").unwrap() = string("tilde");
+        assert_eq!(value.pointer("/foo"), Some(&json(r#"["bar", null]"#)));
+        assert_eq!(value.pointer("/a~1b"), Some(&string("slash")));
+        assert_eq!(value.pointer("/m~0n"), Some(&string("tilde")));
+
+        assert!(value.pointer_mut("/foo/01").is_none());
+        assert!(value.pointer_mut("/foo/2").is_none());
+        assert!(value.pointer_mut("foo").is_none());
+
+        *value.pointer_mut("").unwrap() = JsonValue::Bool(true);
+        assert_eq!(value, JsonValue::Bool(true));
+    }
+
+    #[test]
+    fn display() {
+        let value = json(r#"{"list": [1, "two\n", true, null, {}, []]}"#);
+        assert_eq!(value.to_string(), r#"{"list":[1,"two\n",true,null,{},[]]}"#);
+        assert_eq!(
+            format!("{value:#}"),
+            "{\n  \"list\": [\n    1,\n    \"two\\n\",\n    true,\n    null,\n    {},\n    []\n  ]\n}"
+        );
+
+        // Raw text is written as it is
+        let raw =
+            JsonValue::List(vec![JsonValue::Raw(RawJson::new("{ \"a\" : 1 }").unwrap())].into());
+        assert_eq!(raw.to_string(), r#"[{ "a" : 1 }]"#);
+    }
+
+    #[test]
+    fn parse() {
+        // Strings are not detected as other types
+        let value = json(r#"["2024-01-02", "67e55044-10b1-426f-9247-bb680e5fe0c8", 1.5, -1]"#);
+        let list = value.as_list().unwrap();
+        assert_eq!(list.get(0), Some(&string("2024-01-02")));
+        assert!(list.get(1).unwrap().is_string());
+        assert_eq!(list.get(2), Some(&JsonValue::Number(Number::F64(1.5))));
+        assert_eq!(list.get(3).unwrap().to_string(), "-1");
+
+        // Duplicate keys keep the last value
+        assert_eq!(
+            json(r#"{"a": 1, "a": true}"#).get("a"),
+            Some(&JsonValue::Bool(true))
+        );
+
+        let cases = [
+            ("", ParseErrorKind::Eof),
+            ("[1,", ParseErrorKind::Eof),
+            ("[1] 2", ParseErrorKind::TrailingCharacters),
+            ("{} {}", ParseErrorKind::TrailingCharacters),
+        ];
+        for (input, kind) in cases {
+            assert_eq!(
+                parse_json_value(input).unwrap_err().kind(),
+                &kind,
+                "{input:?}"
+            );
+        }
+        assert!(matches!(
+            parse_json_value("[nul]").unwrap_err().kind(),
+            ParseErrorKind::Unexpected { .. }
+        ));
+
+        let limits = Limits::default().max_depth(2);
+        assert!(parse_json_value_with_limits("[[1]]", limits).is_ok());
+        let err = parse_json_value_with_limits("[[[1]]]", limits).unwrap_err();
+        assert!(matches!(err.kind(), ParseErrorKind::Limit(err) if err.limit() == Limit::Depth));
+        assert_eq!(err.column(), 3);
+    }
+
+    #[test]
+    fn values() {
+        let value = json(r#"{"a": [1, "b", null]}"#);
+        let converted = Value::from(value.clone());
+        assert_eq!(Value::from(&value), converted);
+        assert_eq!(JsonValue::from(converted), value);
+
+        // Types JSON lacks become strings
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        assert_eq!(JsonValue::from(Value::Date(date)), string("2024-01-02"));
+        assert_eq!(JsonValue::from(Value::Char('c')), string("c"));
+        assert_eq!(
+            JsonValue::from(Value::Bytes(b"hi".to_vec().into())),
+            string("aGk=")
+        );
+
+        // Raw text is parsed on conversion
+        let raw = JsonValue::Raw(RawJson::new("[1, 2]").unwrap());
+        assert_eq!(Value::from(&raw), Value::from(json("[1, 2]")));
+        assert_eq!(Value::from(raw), Value::from(json("[1, 2]")));
+
+        let options = ParseOptions::default();
+        assert_eq!(
+            json(r#"["2024-01-02"]"#).into_value_with(&options),
+            crate::json::parse(r#"["2024-01-02"]"#).unwrap()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        use alloc::{collections::BTreeMap, vec::Vec};
+        use serde::Deserialize;
+
+        use crate::de::{from_value, DeserializerError, JsonDeserializer};
+
+        let value = json(r#"{"a": [1, 2], "b": []}"#);
+        let serialized = crate::ser::to_value(&value).unwrap();
+        assert_eq!(serialized, Value::from(&value));
+        assert_eq!(from_value::<JsonValue>(serialized).unwrap(), value);
+
+        let map: BTreeMap<alloc::string::String, Vec<u8>> =
+            Deserialize::deserialize(JsonDeserializer::<DeserializerError>::new(value)).unwrap();
+        assert_eq!(map["a"], [1, 2]);
+        assert!(map["b"].is_empty());
+
+        // Raw text serializes as the value it holds
+        let raw = JsonValue::Raw(RawJson::new(r#"{"a": 1}"#).unwrap());
+        assert_eq!(crate::ser::to_value(&raw).unwrap(), Value::from(&raw));
+        let list: Vec<BTreeMap<alloc::string::String, u8>> = Deserialize::deserialize(
+            JsonDeserializer::<DeserializerError>::new(JsonValue::List(vec![raw].into())),
+        )
+        .unwrap();
+        assert_eq!(list.first().unwrap()["a"], 1);
+
+        // Types JSON lacks deserialize like `JsonValue::from` converts them
+        let value = from_value::<JsonValue>(Value::Char('c')).unwrap();
+        assert_eq!(value, string("c"));
+    }
+}
@@ -14,12 +14,15 @@ mod floating;
 #[cfg(feature = "json")]
 pub mod json;
 pub mod kow;
+pub mod limits;
 mod list;
 mod map;
 mod merge;
 mod number;
+pub mod schema;
 mod string;
 mod r#type;
+pub mod validate;
 mod value;
 mod value_ref;
 
@@ -36,6 +39,9 @@ pub use ::{
     uuid::{self, Uuid},
 };
 
+#[cfg(feature = "gerning")]
+pub use gerning;
+
 #[cfg(feature = "macros")]
 pub use vaerdi_macros::*;
 
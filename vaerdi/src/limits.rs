@@ -0,0 +1,392 @@
+//! Bounds on the values built from untrusted input.
+//!
+//! Every conversion into a `Value` that walks foreign data takes a [`Limits`]:
+//! the JSON parser through `ParseOptions::limits`, the streaming `JsonReader`
+//! and everything built on it through `JsonReader::limits`, serde through
+//! `de::deserialize_with_limits` and the Lua and QuickJS bindings through their
+//! `_with_limits` functions. They count what they build with a [`LimitTracker`]
+//! and fail with a [`LimitError`] that says where in the value the limit was hit.
+//! Without explicit limits they use `Limits::default()`.
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::convert::Path;
+
+/// The most a value built from untrusted input may hold.
+///
+/// The default only limits the depth, to 128 nested lists and maps,
+/// which keeps the recursive conversions from overflowing the stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    depth: usize,
+    string_len: usize,
+    collection_len: usize,
+    nodes: usize,
+    bytes: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits::none().max_depth(128)
+    }
+}
+
+impl Limits {
+    /// No limits at all, only for input that is trusted
+    pub const fn none() -> Limits {
+        Limits {
+            depth: usize::MAX,
+            string_len: usize::MAX,
+            collection_len: usize::MAX,
+            nodes: usize::MAX,
+            bytes: usize::MAX,
+        }
+    }
+
+    /// The number of lists and maps nested inside each other
+    pub const fn max_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// The length in bytes of a single string, key or byte string
+    pub const fn max_string_len(mut self, len: usize) -> Self {
+        self.string_len = len;
+        self
+    }
+
+    /// The number of items of a single list or map
+    pub const fn max_collection_len(mut self, len: usize) -> Self {
+        self.collection_len = len;
+        self
+    }
+
+    /// The number of values in total, every list, map and scalar counts as one
+    pub const fn max_nodes(mut self, nodes: usize) -> Self {
+        self.nodes = nodes;
+        self
+    }
+
+    /// The length of every string, key and byte string together
+    pub const fn max_bytes(mut self, bytes: usize) -> Self {
+        self.bytes = bytes;
+        self
+    }
+}
+
+/// The limit that was exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Limit {
+    Depth,
+    StringLength,
+    CollectionLength,
+    Nodes,
+    Bytes,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Limit::Depth => f.write_str("nesting depth"),
+            Limit::StringLength => f.write_str("string length"),
+            Limit::CollectionLength => f.write_str("collection length"),
+            Limit::Nodes => f.write_str("number of values"),
+            Limit::Bytes => f.write_str("total size"),
+        }
+    }
+}
+
+/// A value exceeded one of its `Limits`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitError {
+    limit: Limit,
+    max: usize,
+    path: Vec<Path>,
+}
+
+impl LimitError {
+    pub fn new(limit: Limit, max: usize) -> LimitError {
+        LimitError {
+            limit,
+            max,
+            path: Vec::new(),
+        }
+    }
+
+    pub fn limit(&self) -> Limit {
+        self.limit
+    }
+
+    /// The value of the limit that was exceeded
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Where the limit was exceeded, outermost segment first
+    pub fn path(&self) -> &[Path] {
+        &self.path
+    }
+
+    /// Mark the error as happening inside the field `name`
+    pub fn with_field(mut self, name: impl Into<crate::String>) -> LimitError {
+        self.path.insert(0, Path::Field(name.into()));
+        self
+    }
+
+    /// Mark the error as happening at index `idx` of a list
+    pub fn with_index(mut self, idx: usize) -> LimitError {
+        self.path.insert(0, Path::Index(idx));
+        self
+    }
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            for (idx, segment) in self.path.iter().enumerate() {
+                if idx > 0 && matches!(segment, Path::Field(_)) {
+                    f.write_str(".")?;
+                }
+                segment.fmt(f)?;
+            }
+            f.write_str(": ")?;
+        }
+
+        write!(f, "{} exceeds the limit of {}", self.limit, self.max)
+    }
+}
+
+impl avagarden::error::Error for LimitError {}
+
+/// Counts the values of a conversion against its `Limits`.
+///
+/// A conversion calls [`value`](Self::value) for every value it builds,
+/// [`enter`](Self::enter) and [`leave`](Self::leave) around the items of a list or map,
+/// [`item`](Self::item) for each of those items and [`text`](Self::text) for every
+/// string, key and byte string, which [`text_at_least`](Self::text_at_least) can check
+/// before it is built. The errors have an empty path, which the conversion fills in
+/// with [`LimitError::with_field`] and [`LimitError::with_index`] as it returns.
+#[derive(Debug, Clone)]
+pub struct LimitTracker {
+    limits: Limits,
+    depth: usize,
+    nodes: usize,
+    bytes: usize,
+}
+
+impl LimitTracker {
+    pub fn new(limits: Limits) -> LimitTracker {
+        LimitTracker {
+            limits,
+            depth: 0,
+            nodes: 0,
+            bytes: 0,
+        }
+    }
+
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    /// One more value
+    pub fn value(&mut self) -> Result<(), LimitError> {
+        self.nodes += 1;
+        check(Limit::Nodes, self.nodes, self.limits.nodes)
+    }
+
+    /// A list or map begins
+    pub fn enter(&mut self) -> Result<(), LimitError> {
+        self.depth += 1;
+        check(Limit::Depth, self.depth, self.limits.depth)
+    }
+
+    /// The list or map that was entered last ends
+    pub fn leave(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    /// The item at `idx` of the current list or map
+    pub fn item(&mut self, idx: usize) -> Result<(), LimitError> {
+        check(
+            Limit::CollectionLength,
+            idx.saturating_add(1),
+            self.limits.collection_len,
+        )
+    }
+
+    /// A string, key or byte string of `len` bytes
+    pub fn text(&mut self, len: usize) -> Result<(), LimitError> {
+        check(Limit::StringLength, len, self.limits.string_len)?;
+        self.bytes = self.bytes.saturating_add(len);
+        check(Limit::Bytes, self.bytes, self.limits.bytes)
+    }
+
+    /// A string, key or byte string of at least `len` bytes is about to be built.
+    /// Fails when it cannot fit without counting it, call [`text`](Self::text)
+    /// with the real length once it is built.
+    pub fn text_at_least(&self, len: usize) -> Result<(), LimitError> {
+        check(Limit::StringLength, len, self.limits.string_len)?;
+        check(
+            Limit::Bytes,
+            self.bytes.saturating_add(len),
+            self.limits.bytes,
+        )
+    }
+}
+
+fn check(limit: Limit, value: usize, max: usize) -> Result<(), LimitError> {
+    if value > max {
+        Err(LimitError::new(limit, max))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use alloc::{
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    use super::*;
+    use crate::json::{
+        parse_json_value_with_limits, parse_with, JsonReader, ParseError, ParseErrorKind,
+        ParseOptions, RawJson,
+    };
+
+    /// A document, the limits it exceeds and the error every ingress path gives
+    const CASES: &[(&str, Limits, &str)] = &[
+        (
+            r#"{"a":[1,2,{"b":[[1]]}]}"#,
+            Limits::none().max_depth(3),
+            "a[2].b: nesting depth exceeds the limit of 3",
+        ),
+        (
+            r#"{"a":["xy","long"]}"#,
+            Limits::none().max_string_len(3),
+            "a[1]: string length exceeds the limit of 3",
+        ),
+        (
+            r#"{"a":{"long":1}}"#,
+            Limits::none().max_string_len(3),
+            "a: string length exceeds the limit of 3",
+        ),
+        (
+            r#"{"a":[1,2,3,4]}"#,
+            Limits::none().max_collection_len(3),
+            "a: collection length exceeds the limit of 3",
+        ),
+        (
+            r#"{"a":1,"b":2}"#,
+            Limits::none().max_collection_len(1),
+            "collection length exceeds the limit of 1",
+        ),
+        (
+            r#"{"a":[1,2,3,4]}"#,
+            Limits::none().max_nodes(4),
+            "a[2]: number of values exceeds the limit of 4",
+        ),
+        (
+            r#"{"ab":["cd","ef"]}"#,
+            Limits::none().max_bytes(5),
+            "ab[1]: total size exceeds the limit of 5",
+        ),
+    ];
+
+    fn limit_error(err: ParseError) -> String {
+        match err.kind() {
+            ParseErrorKind::Limit(err) => err.to_string(),
+            _ => panic!("not a limit error: {err}"),
+        }
+    }
+
+    /// The error of every JSON ingress path
+    fn json_errors(input: &str, limits: Limits) -> Vec<(&'static str, String)> {
+        let parse = parse_with(input, &ParseOptions::default().limits(limits)).unwrap_err();
+
+        let mut reader = JsonReader::from_slice(input.as_bytes()).limits(limits);
+        let next_value = reader.next_value().unwrap_err();
+
+        let mut reader = JsonReader::from_slice(input.as_bytes()).limits(limits);
+        let skip_value = reader.skip_value().unwrap_err();
+
+        let json_value = parse_json_value_with_limits(input, limits).unwrap_err();
+        let raw = RawJson::with_limits(input, limits).unwrap_err();
+
+        vec![
+            ("parse_with", limit_error(parse)),
+            ("next_value", limit_error(next_value)),
+            ("skip_value", limit_error(skip_value)),
+            ("parse_json_value", limit_error(json_value)),
+            ("RawJson", limit_error(raw)),
+        ]
+    }
+
+    #[test]
+    fn json_ingress() {
+        for &(input, limits, expected) in CASES {
+            for (path, err) in json_errors(input, limits) {
+                assert_eq!(err, expected, "{path}: {input}");
+            }
+
+            assert!(parse_with(input, &ParseOptions::default()).is_ok());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_ingress() {
+        use crate::de::deserialize_with_limits;
+
+        for &(input, limits, expected) in CASES {
+            let value = crate::json::parse(input).unwrap();
+            let err = deserialize_with_limits(value.clone(), limits).unwrap_err();
+            assert_eq!(err.to_string(), expected, "{input}");
+
+            let output = deserialize_with_limits(value.clone(), Limits::none()).unwrap();
+            assert_eq!(output, value);
+        }
+    }
+
+    #[test]
+    fn array_values() {
+        let limits = Limits::none().max_string_len(3);
+        let mut reader = JsonReader::from_slice(br#"["ab", ["abcd"]]"#).limits(limits);
+        let mut values = reader.array_values();
+        assert!(values.next().unwrap().is_ok());
+        let err = values.next().unwrap().unwrap_err();
+        assert_eq!(
+            limit_error(err),
+            "[1][0]: string length exceeds the limit of 3"
+        );
+    }
+
+    #[test]
+    fn text_at_least() {
+        let mut tracker = LimitTracker::new(Limits::none().max_string_len(4).max_bytes(6));
+        assert!(tracker.text_at_least(4).is_ok());
+        assert_eq!(
+            tracker.text_at_least(5).unwrap_err().limit(),
+            Limit::StringLength
+        );
+
+        // Only `text` counts
+        tracker.text(3).unwrap();
+        tracker.text_at_least(3).unwrap();
+        assert_eq!(tracker.text_at_least(4).unwrap_err().limit(), Limit::Bytes);
+        tracker.text(3).unwrap();
+        assert!(tracker.text(1).is_err());
+    }
+
+    #[test]
+    fn default_depth() {
+        let nested = |depth| "[".repeat(depth) + &"]".repeat(depth);
+        let options = ParseOptions::default();
+
+        assert!(parse_with(&nested(128), &options).is_ok());
+        let err = parse_with(&nested(129), &options).unwrap_err();
+        assert!(limit_error(err).ends_with("nesting depth exceeds the limit of 128"));
+    }
+}
@@ -1,3 +1,7 @@
+/// Build a [`Value`](crate::Value) with JSON-like syntax.
+///
+/// Literals keep their type and `uuid!(..)`, `date!(..)`, `time!(..)` and
+/// `datetime!(..)` are checked at compile time, which needs the `macros` feature.
 #[macro_export(local_inner_macros)]
 macro_rules! value {
     // Hide distracting implementation details from the generated rustdoc.
@@ -58,6 +62,16 @@ macro_rules! value_internal {
         value_internal!(@array [$($elems,)* value_internal!({$($map)*})] $($rest)*)
     };
 
+    // Next element is a literal.
+    (@array [$($elems:expr,)*] $next:literal $(, $($rest:tt)*)?) => {
+        value_internal!(@array [$($elems,)* value_internal!(@literal $next)] $(, $($rest)*)?)
+    };
+
+    // Next element is a typed literal like `uuid!("...")`.
+    (@array [$($elems:expr,)*] $mac:ident ! ($($args:tt)*) $(, $($rest:tt)*)?) => {
+        value_internal!(@array [$($elems,)* value_internal!(@typed $mac ($($args)*))] $(, $($rest)*)?)
+    };
+
     // Next element is an expression followed by comma.
     (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
         value_internal!(@array [$($elems,)* value_internal!($next),] $($rest)*)
@@ -132,6 +146,16 @@ macro_rules! value_internal {
         value_internal!(@object $object [$($key)+] (value_internal!({$($map)*})) $($rest)*);
     };
 
+    // Next value is a literal.
+    (@object $object:ident ($($key:tt)+) (: $value:literal $(, $($rest:tt)*)?) $copy:tt) => {
+        value_internal!(@object $object [$($key)+] (value_internal!(@literal $value)) $(, $($rest)*)?);
+    };
+
+    // Next value is a typed literal like `uuid!("...")`.
+    (@object $object:ident ($($key:tt)+) (: $mac:ident ! ($($args:tt)*) $(, $($rest:tt)*)?) $copy:tt) => {
+        value_internal!(@object $object [$($key)+] (value_internal!(@typed $mac ($($args)*))) $(, $($rest)*)?);
+    };
+
     // Next value is an expression followed by comma.
     (@object $object:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
         value_internal!(@object $object [$($key)+] (value_internal!($value)) , $($rest)*);
@@ -183,6 +207,40 @@ macro_rules! value_internal {
         value_internal!(@object $object ($($key)* $tt) ($($rest)*) ($($rest)*));
     };
 
+    //////////////////////////////////////////////////////////////////////////
+    // Literals keep their type: `b"..."` is bytes, `'c'` is a char and
+    // suffixed numbers keep their width. The typed literal macros are checked
+    // at compile time by `vaerdi-macros` and need the `macros` feature.
+    //////////////////////////////////////////////////////////////////////////
+
+    (@literal $lit:literal) => {
+        value_internal_literal!($lit)
+    };
+
+    (@typed uuid ($($args:tt)*)) => {
+        value_internal_typed!(Uuid uuid ($($args)*))
+    };
+
+    (@typed date ($($args:tt)*)) => {
+        value_internal_typed!(Date date ($($args)*))
+    };
+
+    (@typed time ($($args:tt)*)) => {
+        value_internal_typed!(Time time ($($args)*))
+    };
+
+    (@typed datetime ($($args:tt)*)) => {
+        value_internal_typed!(DateTime datetime ($($args)*))
+    };
+
+    // Any other macro is an expression.
+    (@typed $mac:ident ($($args:tt)*)) => {
+        {
+            let value: $crate::Value = $mac!($($args)*).into();
+            value
+        }
+    };
+
     //////////////////////////////////////////////////////////////////////////
     // The main implementation.
     //
@@ -202,7 +260,7 @@ macro_rules! value_internal {
     };
 
     ([]) => {
-        $crate::Value::List(value_internal_vec![])
+        $crate::Value::List($crate::List::default())
     };
 
     ([ $($tt:tt)+ ]) => {
@@ -210,7 +268,7 @@ macro_rules! value_internal {
     };
 
     ({}) => {
-        $crate::Value::Object($crate::Map::new())
+        $crate::Value::Map($crate::Map::default())
     };
 
     ({ $($tt:tt)+ }) => {
@@ -221,7 +279,15 @@ macro_rules! value_internal {
         })
     };
 
-    // Any Serialize type: numbers, strings, struct literals, variables etc.
+    ($lit:literal) => {
+        value_internal!(@literal $lit)
+    };
+
+    ($mac:ident ! ($($args:tt)*)) => {
+        value_internal!(@typed $mac ($($args)*))
+    };
+
+    // Any type with `Into<Value>`: numbers, strings, struct literals, variables etc.
     // Must be below every other rule.
     ($other:expr) => {
         {
@@ -231,6 +297,16 @@ macro_rules! value_internal {
     };
 }
 
+/// Build a [`JsonValue`](crate::json::JsonValue) with the syntax of [`value!`].
+/// Values JSON has no type for, like `uuid!(..)`, are converted like `JsonValue::from` does.
+#[cfg(feature = "json")]
+#[macro_export]
+macro_rules! json_value {
+    ($($json:tt)+) => {
+        $crate::json::JsonValue::from($crate::value!($($json)+))
+    };
+}
+
 // The value_internal macro above cannot invoke vec directly because it uses
 // local_inner_macros. A vec invocation there would resolve to $crate::vec.
 // Instead invoke vec here outside of local_inner_macros.
@@ -242,6 +318,50 @@ macro_rules! value_internal_vec {
     };
 }
 
+// Without the proc macros a literal is converted like any other expression.
+#[cfg(feature = "macros")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! value_internal_literal {
+    ($lit:literal) => {
+        $crate::value_literal!($lit)
+    };
+}
+
+#[cfg(not(feature = "macros"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! value_internal_literal {
+    ($lit:literal) => {{
+        let value: $crate::Value = $lit.into();
+        value
+    }};
+}
+
+// The typed literal macros are proc macros, without them `uuid!` and the
+// others are an error instead of some other macro with the same name.
+#[cfg(feature = "macros")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! value_internal_typed {
+    ($variant:ident $mac:ident ($($args:tt)*)) => {
+        $crate::Value::$variant($crate::$mac!($($args)*))
+    };
+}
+
+#[cfg(not(feature = "macros"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! value_internal_typed {
+    ($variant:ident $mac:ident ($($args:tt)*)) => {
+        compile_error!(concat!(
+            "`",
+            stringify!($mac),
+            "!` in `value!` needs the `macros` feature of vaerdi"
+        ))
+    };
+}
+
 #[macro_export]
 #[doc(hidden)]
 macro_rules! value_unexpected {
@@ -0,0 +1,668 @@
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    vec,
+    vec::Vec,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use core::fmt;
+use hashbrown::{HashMap, HashSet};
+use uuid::Uuid;
+
+#[cfg(feature = "std")]
+use std::collections::{HashMap as StdHashMap, HashSet as StdHashSet};
+
+use crate::{bytes::Bytes, List, Map, Number, String, Type, Value};
+
+#[cfg(feature = "macros")]
+pub use vaerdi_macros::ValueSchema;
+
+/// Describes the shape of the `Value` a Rust type converts to and from
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeSchema {
+    /// Any value
+    Any,
+    Null,
+    /// A single value of one of the types
+    Primitive(Type),
+    /// The schema or null
+    Optional(Box<TypeSchema>),
+    List(Box<TypeSchema>),
+    /// A map with values of the schema
+    Map(Box<TypeSchema>),
+    /// A list of fixed length
+    Tuple(Vec<TypeSchema>),
+    Struct(StructSchema),
+    Enum(EnumSchema),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructSchema {
+    pub name: String,
+    pub fields: Vec<FieldSchema>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldSchema {
+    pub name: String,
+    pub schema: TypeSchema,
+    /// False if the field can be left out
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumSchema {
+    pub name: String,
+    pub repr: EnumRepr,
+    pub variants: Vec<VariantSchema>,
+}
+
+/// How the variants of an enum are represented, see the derive macros
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnumRepr {
+    /// Unit variants stored as their name
+    Label,
+    /// Unit variants stored as their discriminant
+    Number,
+    Adjacent {
+        tag: String,
+        content: String,
+    },
+    Internal {
+        tag: String,
+    },
+    External,
+    Untagged,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariantSchema {
+    pub name: String,
+    /// The discriminant of `EnumRepr::Number` variants
+    pub discriminant: Option<i64>,
+    /// The content of the variant, `None` for unit variants
+    pub content: Option<TypeSchema>,
+}
+
+impl StructSchema {
+    pub fn new(name: impl Into<String>) -> StructSchema {
+        StructSchema {
+            name: name.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn field(mut self, name: impl Into<String>, schema: TypeSchema, required: bool) -> Self {
+        self.fields.push(FieldSchema {
+            name: name.into(),
+            schema,
+            required,
+        });
+        self
+    }
+
+    /// Add the fields of a flattened struct
+    pub fn flatten(mut self, schema: TypeSchema) -> Self {
+        schema.flatten_into(&mut self.fields);
+        self
+    }
+}
+
+impl EnumSchema {
+    pub fn new(name: impl Into<String>, repr: EnumRepr) -> EnumSchema {
+        EnumSchema {
+            name: name.into(),
+            repr,
+            variants: Vec::new(),
+        }
+    }
+
+    pub fn variant(
+        mut self,
+        name: impl Into<String>,
+        discriminant: Option<i64>,
+        content: Option<TypeSchema>,
+    ) -> Self {
+        self.variants.push(VariantSchema {
+            name: name.into(),
+            discriminant,
+            content,
+        });
+        self
+    }
+}
+
+impl From<StructSchema> for TypeSchema {
+    fn from(value: StructSchema) -> Self {
+        TypeSchema::Struct(value)
+    }
+}
+
+impl From<EnumSchema> for TypeSchema {
+    fn from(value: EnumSchema) -> Self {
+        TypeSchema::Enum(value)
+    }
+}
+
+impl TypeSchema {
+    pub fn tuple(items: impl IntoIterator<Item = TypeSchema>) -> TypeSchema {
+        TypeSchema::Tuple(items.into_iter().collect())
+    }
+
+    pub fn list(schema: TypeSchema) -> TypeSchema {
+        TypeSchema::List(Box::new(schema))
+    }
+
+    pub fn map(schema: TypeSchema) -> TypeSchema {
+        TypeSchema::Map(Box::new(schema))
+    }
+
+    pub fn optional(schema: TypeSchema) -> TypeSchema {
+        match schema {
+            TypeSchema::Optional(_) | TypeSchema::Any | TypeSchema::Null => schema,
+            schema => TypeSchema::Optional(Box::new(schema)),
+        }
+    }
+
+    pub fn is_optional(&self) -> bool {
+        matches!(
+            self,
+            TypeSchema::Optional(_) | TypeSchema::Any | TypeSchema::Null
+        )
+    }
+
+    /// The value types matching the schema
+    pub fn get_type(&self) -> Type {
+        match self {
+            TypeSchema::Any | TypeSchema::Null => Type::all(),
+            TypeSchema::Primitive(ty) => *ty,
+            TypeSchema::Optional(schema) => schema.get_type(),
+            TypeSchema::List(_) | TypeSchema::Tuple(_) => Type::List,
+            TypeSchema::Map(_) | TypeSchema::Struct(_) => Type::Map,
+            TypeSchema::Enum(schema) => schema.get_type(),
+        }
+    }
+
+    /// The fields of a struct, or nothing for other schemas
+    pub fn fields(&self) -> &[FieldSchema] {
+        match self {
+            TypeSchema::Struct(schema) => &schema.fields,
+            _ => &[],
+        }
+    }
+
+    /// Add the fields of a flattened struct to `fields`
+    pub fn flatten_into(self, fields: &mut Vec<FieldSchema>) {
+        if let TypeSchema::Struct(schema) = self {
+            fields.extend(schema.fields);
+        }
+    }
+}
+
+impl EnumSchema {
+    /// The value types matching the schema
+    pub fn get_type(&self) -> Type {
+        match self.repr {
+            EnumRepr::Label => Type::String,
+            EnumRepr::Number => Type::int(),
+            EnumRepr::Adjacent { .. } | EnumRepr::Internal { .. } => Type::Map,
+            EnumRepr::External => {
+                if self.variants.iter().any(|m| m.content.is_none()) {
+                    Type::String | Type::Map
+                } else {
+                    Type::Map
+                }
+            }
+            EnumRepr::Untagged => self
+                .variants
+                .iter()
+                .filter_map(|m| m.content.as_ref())
+                .fold(Type::empty(), |ty, content| ty | content.get_type()),
+        }
+    }
+}
+
+impl fmt::Display for TypeSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeSchema::Any => f.write_str("any"),
+            TypeSchema::Null => f.write_str("null"),
+            TypeSchema::Primitive(ty) => ty.fmt(f),
+            TypeSchema::Optional(schema) => write!(f, "{schema}?"),
+            TypeSchema::List(schema) => write!(f, "[{schema}]"),
+            TypeSchema::Map(schema) => write!(f, "{{string: {schema}}}"),
+            TypeSchema::Tuple(items) => {
+                f.write_str("(")?;
+                for (idx, item) in items.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(", ")?;
+                    }
+                    item.fmt(f)?;
+                }
+                f.write_str(")")
+            }
+            TypeSchema::Struct(schema) => schema.fmt(f),
+            TypeSchema::Enum(schema) => schema.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for StructSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("{")?;
+        for (idx, field) in self.fields.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(",")?;
+            }
+            let optional = if field.required { "" } else { "?" };
+            write!(f, " {}{optional}: {}", field.name, field.schema)?;
+        }
+        f.write_str(" }")
+    }
+}
+
+impl fmt::Display for EnumSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, variant) in self.variants.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(" | ")?;
+            }
+
+            let name = &variant.name;
+
+            match (&self.repr, &variant.content) {
+                (EnumRepr::Number, _) => match variant.discriminant {
+                    Some(discriminant) => write!(f, "{discriminant}")?,
+                    None => write!(f, "\"{name}\"")?,
+                },
+                (EnumRepr::Label | EnumRepr::External, None) => write!(f, "\"{name}\"")?,
+                (EnumRepr::Adjacent { tag, .. } | EnumRepr::Internal { tag }, None) => {
+                    write!(f, "{{ {tag}: \"{name}\" }}")?
+                }
+                (EnumRepr::Adjacent { tag, content: key }, Some(content)) => {
+                    write!(f, "{{ {tag}: \"{name}\", {key}: {content} }}")?
+                }
+                (EnumRepr::Internal { tag }, Some(content)) => {
+                    write!(f, "{{ {tag}: \"{name}\" }} & {content}")?
+                }
+                (EnumRepr::External | EnumRepr::Label, Some(content)) => {
+                    write!(f, "{{ \"{name}\": {content} }}")?
+                }
+                (EnumRepr::Untagged, None) => f.write_str("null")?,
+                (EnumRepr::Untagged, Some(content)) => content.fmt(f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Types that can describe the shape of their `Value`.
+///
+/// Can be derived with `#[derive(ValueSchema)]` which follows the same
+/// attributes as the `IntoValue` and `FromValue` derives.
+pub trait ValueSchema {
+    fn schema() -> TypeSchema;
+}
+
+macro_rules! primitive_schema {
+    ($($ty: ty => $variant: ident),*) => {
+        $(
+            impl ValueSchema for $ty {
+                fn schema() -> TypeSchema {
+                    TypeSchema::Primitive(Type::$variant)
+                }
+            }
+        )*
+    };
+}
+
+primitive_schema!(
+    bool => Bool,
+    char => Char,
+    u8 => U8,
+    i8 => I8,
+    u16 => U16,
+    i16 => I16,
+    u32 => U32,
+    i32 => I32,
+    u64 => U64,
+    i64 => I64,
+    f32 => F32,
+    f64 => F64,
+    String => String,
+    alloc::string::String => String,
+    str => String,
+    Bytes => Bytes,
+    NaiveDate => Date,
+    NaiveDateTime => DateTime,
+    NaiveTime => Time,
+    Uuid => Uuid
+);
+
+impl<Tz: TimeZone> ValueSchema for DateTime<Tz> {
+    fn schema() -> TypeSchema {
+        TypeSchema::Primitive(Type::DateTime)
+    }
+}
+
+impl ValueSchema for usize {
+    fn schema() -> TypeSchema {
+        u64::schema()
+    }
+}
+
+impl ValueSchema for isize {
+    fn schema() -> TypeSchema {
+        i64::schema()
+    }
+}
+
+impl ValueSchema for Number {
+    fn schema() -> TypeSchema {
+        TypeSchema::Primitive(Type::number())
+    }
+}
+
+impl ValueSchema for Value {
+    fn schema() -> TypeSchema {
+        TypeSchema::Any
+    }
+}
+
+/// Any value converts, the types JSON lacks become strings
+#[cfg(feature = "json")]
+impl ValueSchema for crate::json::JsonValue {
+    fn schema() -> TypeSchema {
+        TypeSchema::Any
+    }
+}
+
+#[cfg(feature = "json")]
+impl ValueSchema for crate::json::RawJson {
+    fn schema() -> TypeSchema {
+        TypeSchema::Any
+    }
+}
+
+impl ValueSchema for () {
+    fn schema() -> TypeSchema {
+        TypeSchema::Null
+    }
+}
+
+impl<T: ValueSchema + ?Sized> ValueSchema for &T {
+    fn schema() -> TypeSchema {
+        T::schema()
+    }
+}
+
+impl<'a, T> ValueSchema for crate::kow::Kow<'a, T>
+where
+    T: ValueSchema + crate::kow::ToKowned + ?Sized,
+{
+    fn schema() -> TypeSchema {
+        T::schema()
+    }
+}
+
+impl<T: ValueSchema + ?Sized> ValueSchema for Box<T> {
+    fn schema() -> TypeSchema {
+        T::schema()
+    }
+}
+
+impl<T: ValueSchema> ValueSchema for Option<T> {
+    fn schema() -> TypeSchema {
+        TypeSchema::optional(T::schema())
+    }
+}
+
+impl<V: ValueSchema> ValueSchema for Map<V> {
+    fn schema() -> TypeSchema {
+        TypeSchema::map(V::schema())
+    }
+}
+
+impl<V: ValueSchema> ValueSchema for List<V> {
+    fn schema() -> TypeSchema {
+        TypeSchema::list(V::schema())
+    }
+}
+
+impl<T: ValueSchema> ValueSchema for [T] {
+    fn schema() -> TypeSchema {
+        TypeSchema::list(T::schema())
+    }
+}
+
+impl<T: ValueSchema, const N: usize> ValueSchema for [T; N] {
+    fn schema() -> TypeSchema {
+        TypeSchema::Tuple(vec![T::schema(); N])
+    }
+}
+
+macro_rules! list_schema {
+    ($($list: ident $(, $hasher: ident)?);*) => {
+        $(
+            impl<T: ValueSchema $(, $hasher)?> ValueSchema for $list<T $(, $hasher)?> {
+                fn schema() -> TypeSchema {
+                    TypeSchema::list(T::schema())
+                }
+            }
+        )*
+    };
+}
+
+list_schema!(Vec; VecDeque; BTreeSet; HashSet, S);
+
+macro_rules! map_schema {
+    ($($map: ident $(, $hasher: ident)?);*) => {
+        $(
+            impl<K, V: ValueSchema $(, $hasher)?> ValueSchema for $map<K, V $(, $hasher)?> {
+                fn schema() -> TypeSchema {
+                    TypeSchema::map(V::schema())
+                }
+            }
+        )*
+    };
+}
+
+map_schema!(BTreeMap; HashMap, S);
+
+#[cfg(feature = "std")]
+list_schema!(StdHashSet, S);
+
+#[cfg(feature = "std")]
+map_schema!(StdHashMap, S);
+
+macro_rules! tuple_schema {
+    ($(($($name: ident),+)),*) => {
+        $(
+            impl<$($name: ValueSchema),+> ValueSchema for ($($name,)+) {
+                fn schema() -> TypeSchema {
+                    TypeSchema::Tuple(vec![$($name::schema()),+])
+                }
+            }
+        )*
+    };
+}
+
+tuple_schema!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+    (A, B, C, D, E, F, G, H, I),
+    (A, B, C, D, E, F, G, H, I, J),
+    (A, B, C, D, E, F, G, H, I, J, K),
+    (A, B, C, D, E, F, G, H, I, J, K, L)
+);
+
+/// Used by `#[derive(ValueSchema)]` to implement `gerning::Typed` when the
+/// `gerning` feature of this crate is enabled
+#[cfg(feature = "gerning")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __typed_impl {
+    ([$($imp: tt)*] $ty: ty, [$($wh: tt)*]) => {
+        impl $($imp)* $crate::gerning::Typed<$crate::Value> for $ty $($wh)* {
+            fn get_type() -> $crate::Type {
+                <$ty as $crate::schema::ValueSchema>::schema().get_type()
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "gerning"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __typed_impl {
+    ($($tt: tt)*) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use chrono::{FixedOffset, Utc};
+
+    use super::*;
+
+    fn primitive(ty: Type) -> TypeSchema {
+        TypeSchema::Primitive(ty)
+    }
+
+    #[test]
+    fn impls() {
+        assert_eq!(u8::schema(), primitive(Type::U8));
+        assert_eq!(usize::schema(), primitive(Type::U64));
+        assert_eq!(<&str>::schema(), primitive(Type::String));
+        assert_eq!(Box::<f32>::schema(), primitive(Type::F32));
+        assert_eq!(Number::schema(), primitive(Type::number()));
+        assert_eq!(DateTime::<Utc>::schema(), primitive(Type::DateTime));
+        assert_eq!(DateTime::<FixedOffset>::schema(), primitive(Type::DateTime));
+        assert_eq!(Value::schema(), TypeSchema::Any);
+        assert_eq!(<()>::schema(), TypeSchema::Null);
+
+        assert_eq!(
+            Option::<Vec<u8>>::schema(),
+            TypeSchema::optional(TypeSchema::list(primitive(Type::U8)))
+        );
+        assert_eq!(
+            BTreeMap::<String, bool>::schema(),
+            TypeSchema::map(primitive(Type::Bool))
+        );
+        assert_eq!(
+            HashSet::<Uuid>::schema(),
+            TypeSchema::list(primitive(Type::Uuid))
+        );
+        assert_eq!(
+            <[u8; 2]>::schema(),
+            TypeSchema::tuple([primitive(Type::U8), primitive(Type::U8)])
+        );
+        assert_eq!(
+            <(u8, String)>::schema(),
+            TypeSchema::tuple([primitive(Type::U8), primitive(Type::String)])
+        );
+    }
+
+    #[test]
+    fn optional() {
+        let schema = TypeSchema::optional(primitive(Type::U8));
+        assert!(schema.is_optional());
+        assert_eq!(TypeSchema::optional(schema.clone()), schema);
+        assert_eq!(TypeSchema::optional(TypeSchema::Any), TypeSchema::Any);
+        assert_eq!(TypeSchema::optional(TypeSchema::Null), TypeSchema::Null);
+        assert!(!primitive(Type::U8).is_optional());
+        assert_eq!(Option::<Option<u8>>::schema(), schema);
+    }
+
+    fn point() -> StructSchema {
+        StructSchema::new("Point")
+            .field("x", primitive(Type::I32), true)
+            .field("y", TypeSchema::optional(primitive(Type::I32)), false)
+    }
+
+    #[test]
+    fn structs() {
+        let schema = TypeSchema::from(point());
+        assert_eq!(schema.get_type(), Type::Map);
+        assert_eq!(schema.fields().len(), 2);
+        assert_eq!(schema.fields()[1].name, "y");
+        assert!(!schema.fields()[1].required);
+        assert_eq!(schema.to_string(), "{ x: i32, y?: i32? }");
+
+        let labeled = StructSchema::new("Labeled")
+            .field("label", primitive(Type::String), true)
+            .flatten(schema)
+            // Only the fields of a struct can be flattened
+            .flatten(primitive(Type::Bool));
+        assert_eq!(
+            labeled.fields.iter().map(|m| &*m.name).collect::<Vec<_>>(),
+            ["label", "x", "y"]
+        );
+
+        assert!(primitive(Type::U8).fields().is_empty());
+        assert_eq!(
+            TypeSchema::map(primitive(Type::U8)).to_string(),
+            "{string: u8}"
+        );
+        assert_eq!(
+            TypeSchema::tuple([primitive(Type::U8), TypeSchema::Any]).to_string(),
+            "(u8, any)"
+        );
+    }
+
+    fn shape(repr: EnumRepr) -> EnumSchema {
+        EnumSchema::new("Shape", repr)
+            .variant("Empty", None, None)
+            .variant("Point", None, Some(point().into()))
+            .variant("Radius", None, Some(primitive(Type::F64)))
+    }
+
+    #[test]
+    fn enums() {
+        let adjacent = shape(EnumRepr::Adjacent {
+            tag: "type".into(),
+            content: "content".into(),
+        });
+        assert_eq!(adjacent.get_type(), Type::Map);
+        assert_eq!(
+            adjacent.to_string(),
+            r#"{ type: "Empty" } | { type: "Point", content: { x: i32, y?: i32? } } | { type: "Radius", content: f64 }"#
+        );
+
+        let internal = shape(EnumRepr::Internal { tag: "kind".into() });
+        assert_eq!(internal.get_type(), Type::Map);
+        assert_eq!(
+            internal.to_string(),
+            r#"{ kind: "Empty" } | { kind: "Point" } & { x: i32, y?: i32? } | { kind: "Radius" } & f64"#
+        );
+
+        let external = shape(EnumRepr::External);
+        assert_eq!(external.get_type(), Type::String | Type::Map);
+        assert_eq!(
+            external.to_string(),
+            r#""Empty" | { "Point": { x: i32, y?: i32? } } | { "Radius": f64 }"#
+        );
+
+        let untagged = shape(EnumRepr::Untagged);
+        assert_eq!(untagged.get_type(), Type::Map | Type::F64);
+        assert_eq!(untagged.to_string(), "null | { x: i32, y?: i32? } | f64");
+
+        let labels = EnumSchema::new("Color", EnumRepr::Label)
+            .variant("Red", None, None)
+            .variant("Blue", None, None);
+        assert_eq!(labels.get_type(), Type::String);
+        assert_eq!(labels.to_string(), r#""Red" | "Blue""#);
+
+        let numbers = EnumSchema::new("Level", EnumRepr::Number)
+            .variant("Low", Some(1), None)
+            .variant("High", Some(10), None);
+        assert_eq!(TypeSchema::from(numbers.clone()).get_type(), Type::int());
+        assert_eq!(numbers.to_string(), "1 | 10");
+    }
+}
@@ -52,18 +52,28 @@ impl ser::Serialize for Value {
     }
 }
 
-// impl ser::Serialize for JsonValue {
-//     fn serialize<S: ser::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
-//         match self {
-//             Self::Bool(b) => b.serialize(s),
-//             Self::List(l) => l.serialize(s),
-//             Self::Null => s.serialize_none(),
-//             Self::Number(n) => n.serialize(s),
-//             Self::Object(m) => m.serialize(s),
-//             Self::String(m) => m.serialize(s),
-//         }
-//     }
-// }
+#[cfg(feature = "json")]
+impl ser::Serialize for crate::json::JsonValue {
+    fn serialize<S: ser::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Bool(b) => b.serialize(s),
+            Self::List(l) => l.serialize(s),
+            Self::Null => s.serialize_none(),
+            Self::Number(n) => n.serialize(s),
+            Self::Object(m) => m.serialize(s),
+            Self::String(m) => m.serialize(s),
+            Self::Raw(raw) => raw.value().serialize(s),
+        }
+    }
+}
+
+/// Serializes the parsed value, serializers have no way to take the text as it is
+#[cfg(feature = "json")]
+impl ser::Serialize for crate::json::RawJson {
+    fn serialize<S: ser::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        self.value().serialize(s)
+    }
+}
 
 impl ser::Serialize for Bytes {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -57,6 +57,11 @@ impl Type {
     pub fn is_number(&self) -> bool {
         Type::number().contains(*self)
     }
+
+    /// Returns true if more than one type is set
+    pub fn is_union(&self) -> bool {
+        self.bits().count_ones() > 1
+    }
 }
 
 impl fmt::Display for Type {
@@ -81,7 +86,7 @@ impl fmt::Display for Type {
             Type::U16 => f.write_str("u16"),
             Type::U32 => f.write_str("u32"),
             Type::U64 => f.write_str("u64"),
-            Type::U8 => f.write_str("i8"),
+            Type::U8 => f.write_str("u8"),
             Type::Uuid => f.write_str("uuid"),
             v => {
                 for (idx, t) in v.iter().enumerate() {
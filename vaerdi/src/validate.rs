@@ -0,0 +1,438 @@
+//! Validation rules used by `#[vaerdi(validate(...))]`.
+//!
+//! The derived `FromValue` implementations run the rules of every field after
+//! it has been converted, and `#[derive(Validate)]` runs the same rules on a
+//! value that already exists.
+//!
+//! The rules are applied through the [`Numeric`], [`HasLength`] and [`AsStr`]
+//! traits. An `Option` that is `None` passes every rule.
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    string::String as AllocString,
+    vec::Vec,
+};
+use core::{cmp::Ordering, fmt};
+use hashbrown::{HashMap, HashSet};
+
+use crate::{bytes::Bytes, convert::ConvertError, List, Map, Number, String};
+
+#[cfg(feature = "macros")]
+pub use vaerdi_macros::Validate;
+
+/// Types that can check their own validation rules
+pub trait Validate {
+    fn validate(&self) -> Result<(), ConvertError>;
+}
+
+impl<T: Validate + ?Sized> Validate for &T {
+    fn validate(&self) -> Result<(), ConvertError> {
+        (**self).validate()
+    }
+}
+
+impl<T: Validate + ?Sized> Validate for Box<T> {
+    fn validate(&self) -> Result<(), ConvertError> {
+        (**self).validate()
+    }
+}
+
+impl<T: Validate> Validate for Option<T> {
+    fn validate(&self) -> Result<(), ConvertError> {
+        match self {
+            Some(value) => value.validate(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: Validate> Validate for [T] {
+    fn validate(&self) -> Result<(), ConvertError> {
+        let mut errors = crate::convert::ErrorCollector::new();
+        for (idx, value) in self.iter().enumerate() {
+            errors.index(idx, value.validate());
+        }
+        errors.finish()
+    }
+}
+
+impl<T: Validate> Validate for Vec<T> {
+    fn validate(&self) -> Result<(), ConvertError> {
+        self.as_slice().validate()
+    }
+}
+
+/// Values checked by `min` and `max`
+pub trait Numeric {
+    /// The type the bounds are given in, the field's own type for the numbers,
+    /// so integers beyond the precision of a float compare exactly
+    type Bound: Bound;
+
+    /// The value to compare, `None` to skip the check
+    fn to_bound(&self) -> Option<Self::Bound>;
+}
+
+/// The types `min` and `max` compare in
+pub trait Bound: PartialOrd + fmt::Display {
+    /// Compare with an integer literal given as the bound, which fits
+    /// whatever the type of the field is
+    fn cmp_int(&self, bound: i128) -> Option<Ordering>;
+}
+
+/// Values checked by `len`
+pub trait HasLength {
+    /// The length, `None` to skip the check
+    fn length(&self) -> Option<usize>;
+}
+
+/// Values checked by `pattern` and `email`
+pub trait AsStr {
+    /// The text, `None` to skip the check
+    fn as_text(&self) -> Option<&str>;
+}
+
+macro_rules! numeric {
+    ($($ty: ty),*) => {
+        $(
+            impl Numeric for $ty {
+                type Bound = $ty;
+                fn to_bound(&self) -> Option<$ty> {
+                    Some(*self)
+                }
+            }
+        )*
+    };
+}
+
+numeric!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, f32, f64);
+
+macro_rules! int_bound {
+    ($($ty: ty),*) => {
+        $(
+            impl Bound for $ty {
+                fn cmp_int(&self, bound: i128) -> Option<Ordering> {
+                    match i128::try_from(*self) {
+                        Ok(value) => Some(value.cmp(&bound)),
+                        // Only a u128 beyond i128::MAX
+                        Err(_) => Some(Ordering::Greater),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+int_bound!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize);
+
+macro_rules! float_bound {
+    ($($ty: ty),*) => {
+        $(
+            impl Bound for $ty {
+                fn cmp_int(&self, bound: i128) -> Option<Ordering> {
+                    (*self as f64).partial_cmp(&(bound as f64))
+                }
+            }
+        )*
+    };
+}
+
+float_bound!(f32, f64);
+
+/// Compared as a float, the bounds of a field that can hold any number are floats
+impl Numeric for Number {
+    type Bound = f64;
+    fn to_bound(&self) -> Option<f64> {
+        Some(self.as_f64())
+    }
+}
+
+impl HasLength for str {
+    fn length(&self) -> Option<usize> {
+        Some(self.chars().count())
+    }
+}
+
+impl AsStr for str {
+    fn as_text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl HasLength for AllocString {
+    fn length(&self) -> Option<usize> {
+        self.as_str().length()
+    }
+}
+
+impl AsStr for AllocString {
+    fn as_text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl HasLength for String {
+    fn length(&self) -> Option<usize> {
+        (**self).length()
+    }
+}
+
+impl AsStr for String {
+    fn as_text(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl HasLength for Bytes {
+    fn length(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T> HasLength for [T] {
+    fn length(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T, const N: usize> HasLength for [T; N] {
+    fn length(&self) -> Option<usize> {
+        Some(N)
+    }
+}
+
+macro_rules! has_length {
+    ($($ty: ty => [$($param: ident),*]),*) => {
+        $(
+            impl<$($param),*> HasLength for $ty {
+                fn length(&self) -> Option<usize> {
+                    Some(self.len())
+                }
+            }
+        )*
+    };
+}
+
+has_length!(
+    Vec<T> => [T],
+    VecDeque<T> => [T],
+    BTreeSet<T> => [T],
+    BTreeMap<K, V> => [K, V],
+    HashSet<T, S> => [T, S],
+    HashMap<K, V, S> => [K, V, S],
+    Map<V> => [V],
+    List<V> => [V]
+);
+
+#[cfg(feature = "std")]
+has_length!(
+    std::collections::HashSet<T, S> => [T, S],
+    std::collections::HashMap<K, V, S> => [K, V, S]
+);
+
+macro_rules! forward {
+    ($($wrapper: ty => [$($bound: tt)*]),*) => {
+        $(
+            impl<T: Numeric $($bound)*> Numeric for $wrapper {
+                type Bound = T::Bound;
+                fn to_bound(&self) -> Option<T::Bound> {
+                    (**self).to_bound()
+                }
+            }
+
+            impl<T: HasLength $($bound)*> HasLength for $wrapper {
+                fn length(&self) -> Option<usize> {
+                    (**self).length()
+                }
+            }
+
+            impl<T: AsStr $($bound)*> AsStr for $wrapper {
+                fn as_text(&self) -> Option<&str> {
+                    (**self).as_text()
+                }
+            }
+        )*
+    };
+}
+
+forward!(&T => [+ ?Sized], Box<T> => [+ ?Sized]);
+
+impl<T: Numeric> Numeric for Option<T> {
+    type Bound = T::Bound;
+    fn to_bound(&self) -> Option<T::Bound> {
+        self.as_ref().and_then(T::to_bound)
+    }
+}
+
+impl<T: HasLength> HasLength for Option<T> {
+    fn length(&self) -> Option<usize> {
+        self.as_ref().and_then(T::length)
+    }
+}
+
+impl<T: AsStr> AsStr for Option<T> {
+    fn as_text(&self) -> Option<&str> {
+        self.as_ref().and_then(T::as_text)
+    }
+}
+
+/// `value` must be at least `min`
+pub fn min<T: Numeric + ?Sized>(value: &T, min: T::Bound) -> Result<(), ConvertError> {
+    match value.to_bound() {
+        Some(value) if value < min => Err(ConvertError::validation(alloc::format!(
+            "must be at least {min}, found {value}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// `value` must be at most `max`
+pub fn max<T: Numeric + ?Sized>(value: &T, max: T::Bound) -> Result<(), ConvertError> {
+    match value.to_bound() {
+        Some(value) if value > max => Err(ConvertError::validation(alloc::format!(
+            "must be at most {max}, found {value}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// `value` must be at least the integer literal `min`, whatever the type of
+/// `value` is
+pub fn min_int<T: Numeric + ?Sized>(value: &T, min: i128) -> Result<(), ConvertError> {
+    match value.to_bound() {
+        Some(value) if value.cmp_int(min) == Some(Ordering::Less) => Err(ConvertError::validation(
+            alloc::format!("must be at least {min}, found {value}"),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// `value` must be at most the integer literal `max`, whatever the type of
+/// `value` is
+pub fn max_int<T: Numeric + ?Sized>(value: &T, max: i128) -> Result<(), ConvertError> {
+    match value.to_bound() {
+        Some(value) if value.cmp_int(max) == Some(Ordering::Greater) => Err(
+            ConvertError::validation(alloc::format!("must be at most {max}, found {value}")),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// The length of `value` must be within `min` and `max`.
+/// Strings are measured in characters.
+pub fn length<T: HasLength + ?Sized>(
+    value: &T,
+    min: Option<usize>,
+    max: Option<usize>,
+) -> Result<(), ConvertError> {
+    let Some(len) = value.length() else {
+        return Ok(());
+    };
+
+    match (min, max) {
+        (Some(min), _) if len < min => Err(ConvertError::validation(alloc::format!(
+            "length must be at least {min}, found {len}"
+        ))),
+        (_, Some(max)) if len > max => Err(ConvertError::validation(alloc::format!(
+            "length must be at most {max}, found {len}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// `value` must look like an email address: a single `@` between a local part
+/// and a domain with at least one dot, and no whitespace
+pub fn email<T: AsStr + ?Sized>(value: &T) -> Result<(), ConvertError> {
+    let Some(text) = value.as_text() else {
+        return Ok(());
+    };
+
+    if is_email(text) {
+        Ok(())
+    } else {
+        Err(ConvertError::validation("not a valid email address"))
+    }
+}
+
+fn is_email(text: &str) -> bool {
+    if text.chars().any(char::is_whitespace) {
+        return false;
+    }
+
+    let Some((local, domain)) = text.split_once('@') else {
+        return false;
+    };
+
+    !local.is_empty()
+        && !domain.contains('@')
+        && domain.split('.').count() > 1
+        && domain.split('.').all(|part| !part.is_empty())
+}
+
+/// A regular expression compiled on first use.
+///
+/// The derive macros put one in a `static` for every `pattern` rule.
+#[cfg(feature = "regex")]
+pub struct Pattern {
+    source: &'static str,
+    regex: std::sync::OnceLock<Result<regex::Regex, regex::Error>>,
+}
+
+#[cfg(feature = "regex")]
+impl Pattern {
+    pub const fn new(source: &'static str) -> Pattern {
+        Pattern {
+            source,
+            regex: std::sync::OnceLock::new(),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.source
+    }
+
+    /// Fails if the pattern is not a valid regular expression
+    pub fn is_match(&self, text: &str) -> Result<bool, ConvertError> {
+        match self.regex.get_or_init(|| regex::Regex::new(self.source)) {
+            Ok(regex) => Ok(regex.is_match(text)),
+            Err(err) => Err(ConvertError::validation(alloc::format!(
+                "invalid pattern {:?}: {err}",
+                self.source
+            ))),
+        }
+    }
+}
+
+/// `value` must match `pattern`
+#[cfg(feature = "regex")]
+pub fn pattern<T: AsStr + ?Sized>(value: &T, pattern: &Pattern) -> Result<(), ConvertError> {
+    match value.as_text() {
+        Some(text) if !pattern.is_match(text)? => Err(ConvertError::validation(alloc::format!(
+            "does not match pattern {:?}",
+            pattern.as_str()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// The `pattern` rule of the derive macros
+#[cfg(feature = "regex")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __validate_pattern {
+    ($value: expr, $pattern: literal) => {{
+        static __PATTERN: $crate::validate::Pattern = $crate::validate::Pattern::new($pattern);
+        $crate::validate::pattern($value, &__PATTERN)
+    }};
+}
+
+#[cfg(not(feature = "regex"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __validate_pattern {
+    ($value: expr, $pattern: literal) => {
+        ::core::compile_error!(
+            "the `pattern` validation rule requires the `regex` feature of vaerdi"
+        )
+    };
+}
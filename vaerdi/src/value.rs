@@ -135,8 +135,21 @@ impl Value {
         self.into()
     }
 
-    pub fn convert_to(self, ty: Type) -> Result<Value, ConvertError> {
-        crate::convert::convert(self, ty)
+    /// Build `T` from a borrow of this value, see [`FromValueRef`](crate::convert::FromValueRef)
+    pub fn borrow_as<'a, T>(&'a self) -> Result<T, T::Error>
+    where
+        T: crate::convert::FromValueRef<'a>,
+    {
+        T::from_value_ref(self.as_ref())
+    }
+
+    /// Convert to a type or to a named target of the global converter
+    /// [`registry`](crate::convert::registry)
+    pub fn convert_to(
+        self,
+        to: impl Into<crate::convert::ConvertTarget>,
+    ) -> Result<Value, ConvertError> {
+        crate::convert::registry::convert_to(self, to)
     }
 
     pub fn remove<S: AsRef<str>>(&mut self, field: S) -> Option<Value> {
@@ -3,7 +3,7 @@ use core::fmt;
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use uuid::Uuid;
 
-use crate::{bytes::Bytes, List, Map, Number, Value};
+use crate::{bytes::Bytes, List, Map, Number, Type, Value};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ValueRef<'a> {
@@ -62,12 +62,8 @@ impl<'a> From<ValueRef<'a>> for Value {
     }
 }
 
-#[cfg(feature = "gerning")]
-impl<'a> gerning::Value for ValueRef<'a> {
-    type Type = crate::Type;
-
-    fn get_type(&self) -> Self::Type {
-        use crate::Type;
+impl<'a> ValueRef<'a> {
+    pub fn get_type(&self) -> Type {
         match self {
             ValueRef::Bool(_) => Type::Bool,
             ValueRef::String(_) => Type::String,
@@ -78,12 +74,25 @@ impl<'a> gerning::Value for ValueRef<'a> {
             ValueRef::DateTime(_) => Type::DateTime,
             ValueRef::Time(_) => Type::Time,
             ValueRef::Uuid(_) => Type::Uuid,
-            ValueRef::Number(_) => todo!(),
+            ValueRef::Number(n) => n.get_type(),
             ValueRef::Char(_) => Type::Char,
             // ValueRef::Json(_) => Type::Json,
             ValueRef::Null => Type::all(),
         }
     }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, ValueRef::Null)
+    }
+}
+
+#[cfg(feature = "gerning")]
+impl<'a> gerning::Value for ValueRef<'a> {
+    type Type = crate::Type;
+
+    fn get_type(&self) -> Self::Type {
+        ValueRef::get_type(self)
+    }
 }
 
 impl<'a> fmt::Display for ValueRef<'a> {
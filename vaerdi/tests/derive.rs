@@ -0,0 +1,638 @@
+use core::fmt::Debug;
+use std::collections::BTreeMap;
+
+use vaerdi::{
+    convert::FromValue as _, value, ConvertError, FromValue, FromValueRef, IntoValue, Value,
+};
+
+fn round_trip<T>(input: T, expected: Value)
+where
+    T: vaerdi::convert::FromValue<Error = ConvertError> + Into<Value> + Clone + PartialEq + Debug,
+{
+    let value: Value = input.clone().into();
+    assert_eq!(value, expected);
+    assert_eq!(T::from_value(value).unwrap(), input);
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+enum Adjacent {
+    Unit,
+    NewType(i32),
+    Tuple(i32, bool),
+    Struct { name: String },
+}
+
+#[test]
+fn adjacent_is_the_default() {
+    round_trip(Adjacent::Unit, value!({"type": "Unit"}));
+    round_trip(
+        Adjacent::NewType(1),
+        value!({"type": "NewType", "content": 1}),
+    );
+    round_trip(
+        Adjacent::Tuple(1, true),
+        value!({"type": "Tuple", "content": [1, true]}),
+    );
+    round_trip(
+        Adjacent::Struct {
+            name: "vaerdi".into(),
+        },
+        value!({"type": "Struct", "content": {"name": "vaerdi"}}),
+    );
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+#[vaerdi(tag = "kind")]
+enum Internal {
+    Unit,
+    NewType(Point),
+    Struct { name: String },
+}
+
+#[test]
+fn internal() {
+    round_trip(Internal::Unit, value!({"kind": "Unit"}));
+    round_trip(
+        Internal::NewType(Point { x: 1, y: 2 }),
+        value!({"kind": "NewType", "x": 1, "y": 2}),
+    );
+    round_trip(
+        Internal::Struct {
+            name: "vaerdi".into(),
+        },
+        value!({"kind": "Struct", "name": "vaerdi"}),
+    );
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+struct Id(u32);
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+struct Content {
+    content: String,
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+#[vaerdi(tag = "kind")]
+enum Event {
+    Created(Id),
+    Renamed(Content),
+    Raw(Value),
+}
+
+#[test]
+fn internal_newtype_without_a_map() {
+    round_trip(
+        Event::Created(Id(7)),
+        value!({"kind": "Created", "content": 7u32}),
+    );
+    round_trip(
+        Event::Renamed(Content {
+            content: "name".into(),
+        }),
+        value!({"kind": "Renamed", "content": "name"}),
+    );
+    round_trip(
+        Event::Raw(value!([1, 2])),
+        value!({"kind": "Raw", "content": [1, 2]}),
+    );
+    round_trip(
+        Event::Raw(value!({"a": 1})),
+        value!({"kind": "Raw", "a": 1}),
+    );
+
+    let err = Event::from_value(value!({"kind": "Created", "content": "seven"})).unwrap_err();
+    assert_eq!(err.to_string(), "expected: u32, found: string");
+    let err = Event::from_value(value!({"kind": "Created"})).unwrap_err();
+    assert_eq!(err.to_string(), "expected: u32, found: map");
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+#[vaerdi(external)]
+enum External {
+    Unit,
+    NewType(i32),
+    Tuple(i32, bool),
+}
+
+#[test]
+fn external() {
+    round_trip(External::Unit, value!("Unit"));
+    round_trip(External::NewType(1), value!({"NewType": 1}));
+    round_trip(External::Tuple(1, true), value!({"Tuple": [1, true]}));
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+#[vaerdi(untagged)]
+enum Untagged {
+    Point(Point),
+    Number(i32),
+}
+
+#[test]
+fn untagged() {
+    round_trip(
+        Untagged::Point(Point { x: 1, y: 2 }),
+        value!({"x": 1, "y": 2}),
+    );
+    round_trip(Untagged::Number(1), value!(1));
+}
+
+#[test]
+fn unknown_variant() {
+    let err = Adjacent::from_value(value!({"type": "Other"})).unwrap_err();
+    assert_eq!(err.to_string(), "unknown variant: Other");
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+struct Unit;
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+struct Meters(f64);
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+struct Pair(i32, String);
+
+#[test]
+fn structs_without_named_fields() {
+    round_trip(Unit, value!(null));
+    round_trip(Meters(1.5), value!(1.5));
+    round_trip(Pair(1, "one".into()), value!([1, "one"]));
+}
+
+#[test]
+fn tuple_struct_length() {
+    let err = Pair::from_value(value!([1])).unwrap_err();
+    assert_eq!(err.to_string(), "expected length: 2, found: 1");
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+struct Wrap<T> {
+    x: T,
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+struct Tagged<T>(String, T);
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+#[vaerdi(external)]
+enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+#[test]
+fn generics() {
+    round_trip(Wrap { x: 1u8 }, value!({"x": 1u8}));
+    round_trip(Tagged("id".to_string(), vec![1, 2]), value!(["id", [1, 2]]));
+    round_trip(Either::<i32, bool>::Right(true), value!({"Right": true}));
+}
+
+#[test]
+fn borrowed_generics() {
+    let wrap = Wrap {
+        x: Pair(1, "one".into()),
+    };
+    assert_eq!(Value::from(&wrap), value!({"x": [1, "one"]}));
+    assert_eq!(Value::from(&wrap), Value::from(wrap));
+
+    let either = Either::<String, i32>::Left("left".into());
+    assert_eq!(Value::from(&either), value!({"Left": "left"}));
+}
+
+#[derive(Debug, PartialEq, FromValueRef)]
+struct Borrowed<'a> {
+    name: &'a str,
+    tags: Vec<&'a str>,
+    data: &'a [u8],
+    count: u32,
+    note: Option<&'a str>,
+}
+
+#[derive(Debug, PartialEq, FromValueRef)]
+struct Owned {
+    name: String,
+    point: Vec<i32>,
+}
+
+#[test]
+fn from_value_ref() {
+    let value = value!({
+        "name": "vaerdi",
+        "tags": ["a", "b"],
+        "data": b"\x01\x02",
+        "count": 2u32,
+    });
+
+    let borrowed = value.borrow_as::<Borrowed>().unwrap();
+    assert_eq!(
+        borrowed,
+        Borrowed {
+            name: "vaerdi",
+            tags: vec!["a", "b"],
+            data: &[1, 2],
+            count: 2,
+            note: None,
+        }
+    );
+    assert!(std::ptr::eq(
+        borrowed.name,
+        &**value
+            .as_map()
+            .unwrap()
+            .get("name")
+            .unwrap()
+            .as_string()
+            .unwrap()
+    ));
+
+    let value = value!({"name": "p", "point": [1, 2]});
+    assert_eq!(
+        value.borrow_as::<Owned>().unwrap(),
+        Owned {
+            name: "p".into(),
+            point: vec![1, 2]
+        }
+    );
+
+    let err = value!({"name": 1, "tags": ["a", 2], "data": b"", "count": 1u32})
+        .borrow_as::<Borrowed>()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "2 errors: name: expected: string, found: i32; tags[1]: expected: string, found: i32"
+    );
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+#[vaerdi(rename_all = "camelCase")]
+struct Renamed {
+    first_name: String,
+    #[vaerdi(rename = "surname")]
+    last_name: String,
+}
+
+#[test]
+fn rename() {
+    round_trip(
+        Renamed {
+            first_name: "Ada".into(),
+            last_name: "Lovelace".into(),
+        },
+        value!({"firstName": "Ada", "surname": "Lovelace"}),
+    );
+
+    let err =
+        Renamed::from_value(value!({"first_name": "Ada", "lastName": "Lovelace"})).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "2 errors: firstName: missing field: firstName; surname: missing field: surname"
+    );
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+#[vaerdi(rename_all = "SCREAMING-KEBAB-CASE", external)]
+enum Level {
+    VeryHigh,
+    Low(u8),
+}
+
+#[test]
+fn rename_all_variants() {
+    round_trip(Level::VeryHigh, value!("VERY-HIGH"));
+    round_trip(Level::Low(1), value!({"LOW": 1u8}));
+
+    let err = Level::from_value(value!("VeryHigh")).unwrap_err();
+    assert_eq!(err.to_string(), "unknown variant: VeryHigh");
+}
+
+fn default_port() -> u16 {
+    8080
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+struct Server {
+    host: String,
+    #[vaerdi(default = "default_port")]
+    port: u16,
+    #[vaerdi(default)]
+    retries: u8,
+}
+
+#[test]
+fn default() {
+    round_trip(
+        Server {
+            host: "localhost".into(),
+            port: 1,
+            retries: 2,
+        },
+        value!({"host": "localhost", "port": 1u16, "retries": 2u8}),
+    );
+
+    assert_eq!(
+        Server::from_value(value!({"host": "localhost"})).unwrap(),
+        Server {
+            host: "localhost".into(),
+            port: 8080,
+            retries: 0,
+        }
+    );
+
+    // A default only covers a missing field, not one of the wrong type
+    let err = Server::from_value(value!({"host": "localhost", "port": "80"})).unwrap_err();
+    assert_eq!(err.to_string(), "port: expected: u16, found: string");
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+struct Cached {
+    key: String,
+    #[vaerdi(skip)]
+    hits: u32,
+    #[vaerdi(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+}
+
+#[test]
+fn skip() {
+    let cached = Cached {
+        key: "k".into(),
+        hits: 3,
+        tags: vec![],
+    };
+    assert_eq!(Value::from(&cached), value!({"key": "k"}));
+    assert_eq!(Value::from(cached), value!({"key": "k"}));
+
+    // Skipped fields are never read and start out at their default
+    assert_eq!(
+        Cached::from_value(value!({"key": "k", "hits": "not a number"})).unwrap(),
+        Cached {
+            key: "k".into(),
+            hits: 0,
+            tags: vec![],
+        }
+    );
+
+    round_trip(
+        Cached {
+            key: "k".into(),
+            hits: 0,
+            tags: vec!["a".into()],
+        },
+        value!({"key": "k", "tags": ["a"]}),
+    );
+
+    let err = Cached::from_value(value!({"key": "k", "tags": "a"})).unwrap_err();
+    assert_eq!(err.to_string(), "tags: expected: list, found: string");
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+struct Flattened {
+    id: u32,
+    #[vaerdi(flatten)]
+    point: Point,
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+struct Extra {
+    id: u32,
+    #[vaerdi(flatten)]
+    rest: BTreeMap<String, Value>,
+}
+
+#[test]
+fn flatten() {
+    round_trip(
+        Flattened {
+            id: 1,
+            point: Point { x: 2, y: 3 },
+        },
+        value!({"id": 1u32, "x": 2, "y": 3}),
+    );
+    round_trip(
+        Extra {
+            id: 1,
+            rest: BTreeMap::from([("z".to_string(), value!(4))]),
+        },
+        value!({"id": 1u32, "z": 4}),
+    );
+
+    let err = Flattened::from_value(value!({"id": 1u32, "x": 2})).unwrap_err();
+    assert_eq!(err.to_string(), "y: missing field: y");
+
+    let err = Extra::from_value(value!({"z": 4})).unwrap_err();
+    assert_eq!(err.to_string(), "id: missing field: id");
+}
+
+#[derive(Debug, Clone, PartialEq, IntoValue)]
+struct FlattenedValue {
+    #[vaerdi(flatten)]
+    inner: Value,
+}
+
+#[test]
+fn flatten_null() {
+    assert_eq!(
+        Value::from(FlattenedValue { inner: Value::Null }),
+        value!({})
+    );
+}
+
+#[test]
+#[should_panic(expected = "the flattened field `inner` must convert to a map, found string")]
+fn flatten_non_map() {
+    let _ = Value::from(FlattenedValue {
+        inner: value!("text"),
+    });
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+struct Aliased {
+    #[vaerdi(alias = "colour", alias = "farve")]
+    color: String,
+}
+
+#[test]
+fn alias() {
+    round_trip(
+        Aliased {
+            color: "red".into(),
+        },
+        value!({"color": "red"}),
+    );
+    for key in ["colour", "farve"] {
+        let mut value = value!({});
+        value.as_map_mut().unwrap().insert(key, "red");
+        assert_eq!(
+            Aliased::from_value(value).unwrap(),
+            Aliased {
+                color: "red".into()
+            }
+        );
+    }
+
+    let err = Aliased::from_value(value!({"colors": "red"})).unwrap_err();
+    assert_eq!(err.to_string(), "color: missing field: color");
+}
+
+mod as_string {
+    use vaerdi::{ConvertError, Value};
+
+    pub fn into_value(value: u32) -> Value {
+        value.to_string().into()
+    }
+
+    pub fn from_value(value: Value) -> Result<u32, ConvertError> {
+        match value.as_string() {
+            Some(text) => text
+                .parse()
+                .map_err(|_| ConvertError::validation(format!("{text} is not a number"))),
+            None => Err(ConvertError::invalid_type(
+                vaerdi::Type::String,
+                value.get_type(),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+struct With {
+    #[vaerdi(with = "as_string")]
+    count: u32,
+}
+
+#[test]
+fn with() {
+    round_trip(With { count: 7 }, value!({"count": "7"}));
+    assert_eq!(Value::from(&With { count: 7 }), value!({"count": "7"}));
+
+    let err = With::from_value(value!({"count": "seven"})).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "count: invalid value: seven is not a number"
+    );
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+#[vaerdi(deny_unknown_fields)]
+struct Strict {
+    #[vaerdi(alias = "b")]
+    a: u8,
+}
+
+#[test]
+fn deny_unknown_fields() {
+    round_trip(Strict { a: 1 }, value!({"a": 1u8}));
+    assert_eq!(
+        Strict::from_value(value!({"b": 1u8})).unwrap(),
+        Strict { a: 1 }
+    );
+
+    let err = Strict::from_value(value!({"a": 1u8, "z": 2, "y": 3})).unwrap_err();
+    let mut errors = err
+        .errors()
+        .iter()
+        .map(|err| err.to_string())
+        .collect::<Vec<_>>();
+    errors.sort();
+    assert_eq!(errors, ["unknown field: y", "unknown field: z"]);
+}
+
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue)]
+struct Optional {
+    name: Option<String>,
+    age: Option<u8>,
+}
+
+#[test]
+fn optional_fields() {
+    round_trip(
+        Optional {
+            name: Some("a".into()),
+            age: Some(1),
+        },
+        value!({"name": "a", "age": 1u8}),
+    );
+    assert_eq!(
+        Value::from(Optional {
+            name: None,
+            age: None
+        }),
+        value!({"name": null, "age": null})
+    );
+
+    assert_eq!(
+        Optional::from_value(value!({})).unwrap(),
+        Optional {
+            name: None,
+            age: None
+        }
+    );
+    assert_eq!(
+        Optional::from_value(value!({"name": null, "age": 2u8})).unwrap(),
+        Optional {
+            name: None,
+            age: Some(2)
+        }
+    );
+
+    let err = Optional::from_value(value!({"age": "old"})).unwrap_err();
+    assert_eq!(err.to_string(), "age: expected: u8, found: string");
+}
+
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, PartialEq, FromValue, IntoValue, FromValueRef, vaerdi::ValueSchema)]
+struct Document {
+    id: u32,
+    body: vaerdi::json::RawJson,
+    meta: vaerdi::json::JsonValue,
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_fields() {
+    use vaerdi::{
+        date,
+        json::{parse_json_value, RawJson},
+        schema::{StructSchema, TypeSchema, ValueSchema as _},
+        Type,
+    };
+
+    let value = value!({
+        "id": 1u32,
+        "body": {"list": [1u8, true]},
+        "meta": {"date": date!(2024 - 01 - 02)},
+    });
+    let document = Document {
+        id: 1,
+        body: RawJson::new(r#"{"list":[1,true]}"#).unwrap(),
+        meta: parse_json_value(r#"{"date": "2024-01-02"}"#).unwrap(),
+    };
+
+    let converted = Document::from_value(value.clone()).unwrap();
+    assert_eq!(converted, document);
+    assert_eq!(converted.body.as_str(), r#"{"list":[1,true]}"#);
+    assert_eq!(value.borrow_as::<Document>().unwrap(), document);
+
+    // Types JSON lacks were written as strings
+    let meta = value!({"date": "2024-01-02"});
+    assert_eq!(
+        Value::from(document),
+        value!({"id": 1u32, "body": {"list": [1u8, true]}, "meta": meta})
+    );
+
+    assert_eq!(
+        Document::schema(),
+        StructSchema::new("Document")
+            .field("id", TypeSchema::Primitive(Type::U32), true)
+            .field("body", TypeSchema::Any, true)
+            .field("meta", TypeSchema::Any, true)
+            .into()
+    );
+}
@@ -0,0 +1,42 @@
+use std::{fs, path::Path};
+
+use vaerdi::json::parse;
+
+/// Parse the file as UTF-8 JSON, bytes that are not UTF-8 are rejected
+fn accepts(bytes: &[u8]) -> bool {
+    match core::str::from_utf8(bytes) {
+        Ok(input) => parse(input).is_ok(),
+        Err(_) => false,
+    }
+}
+
+#[test]
+fn json_test_suite() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/json_test_suite");
+
+    let mut failures = Vec::new();
+    let mut cases = 0;
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        let expected = match name.split_once('_') {
+            Some(("y", _)) => true,
+            Some(("n", _)) => false,
+            _ => continue,
+        };
+
+        cases += 1;
+        if accepts(&fs::read(&path).unwrap()) != expected {
+            failures.push(name);
+        }
+    }
+
+    failures.sort();
+    assert!(cases > 250, "found only {cases} cases");
+    assert!(failures.is_empty(), "failed: {failures:#?}");
+}
+
+#[test]
+fn opening_arrays() {
+    assert!(!accepts("[".repeat(100_000).as_bytes()));
+}
@@ -0,0 +1,110 @@
+use vaerdi::{date, datetime, time, uuid, value, Map, NaiveDate, Number, Value};
+
+#[test]
+fn empty() {
+    assert_eq!(value!({}), Value::Map(Map::default()));
+    assert_eq!(value!([]), Value::List(Default::default()));
+    assert_eq!(value!({"a": {}, "b": []}).as_map().unwrap().len(), 2);
+    assert_eq!(
+        value!([{}, []]),
+        Value::List(vec![value!({}), value!([])].into())
+    );
+}
+
+#[test]
+fn literals() {
+    assert_eq!(value!("text"), Value::String("text".into()));
+    assert_eq!(
+        value!(b"raw").as_bytes().map(|bytes| &bytes[..]),
+        Some(&b"raw"[..])
+    );
+    assert_eq!(value!('c'), Value::Char('c'));
+    assert_eq!(value!(true), Value::Bool(true));
+    assert_eq!(value!(null), Value::Null);
+    assert_eq!(value!(1), Value::Number(Number::I32(1)));
+    assert_eq!(value!(-1i8), Value::Number(Number::I8(-1)));
+    assert_eq!(value!(1u64), Value::Number(Number::U64(1)));
+    assert_eq!(value!(1isize), Value::Number(Number::I64(1)));
+    assert_eq!(value!(1.5), Value::Number(Number::F64(1.5)));
+    assert_eq!(value!(-1.5f32), Value::Number(Number::F32(-1.5)));
+}
+
+#[test]
+fn typed_literals() {
+    let id = uuid!("67e55044-10b1-426f-9247-bb680e5fe0c8");
+    assert_eq!(id, uuid!("67e5504410b1426f9247bb680e5fe0c8"));
+    assert_eq!(id.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+
+    assert_eq!(
+        date!(2024 - 02 - 29),
+        NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+    );
+    assert_eq!(date!("2024-02-29"), date!(2024 - 02 - 29));
+
+    let noon = time!(12:00);
+    assert_eq!(noon, time!("12:00:00"));
+    assert_eq!(time!(12:30:15.25).to_string(), "12:30:15.250");
+
+    let at = datetime!("2024-01-02T12:30:00");
+    assert_eq!(at, datetime!("2024-01-02 12:30:00"));
+    assert_eq!(at.date(), date!(2024 - 01 - 02));
+    assert_eq!(at.time(), time!(12:30));
+}
+
+#[test]
+fn typed_literals_in_value() {
+    let value = value!({
+        "id": uuid!("67e55044-10b1-426f-9247-bb680e5fe0c8"),
+        "days": [date!(2024 - 01 - 02), date!("2024-01-03")],
+        "at": datetime!("2024-01-02 12:30:00"),
+        "time": time!(08:15)
+    });
+
+    assert_eq!(
+        value.as_map().unwrap().get("id"),
+        Some(&Value::Uuid(uuid!("67e55044-10b1-426f-9247-bb680e5fe0c8")))
+    );
+    assert_eq!(
+        value.as_map().unwrap().get("days"),
+        Some(&value!([
+            Value::Date(date!(2024 - 01 - 02)),
+            Value::Date(date!(2024 - 01 - 03))
+        ]))
+    );
+    assert_eq!(
+        value.as_map().unwrap().get("at"),
+        Some(&Value::DateTime(datetime!("2024-01-02T12:30:00")))
+    );
+    assert_eq!(
+        value.as_map().unwrap().get("time"),
+        Some(&Value::Time(time!(08:15:00)))
+    );
+}
+
+#[test]
+fn other_macros_are_expressions() {
+    assert_eq!(value!(format!("{}-{}", 1, 2)), Value::String("1-2".into()));
+    assert_eq!(
+        value!({ "list": vec![1u8, 2u8] }),
+        value!({ "list": [1u8, 2u8] })
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_value() {
+    use vaerdi::json::{parse_json_value, JsonValue};
+
+    let value = vaerdi::json_value!({ "list": [1, "two", null], "ok": true });
+    assert!(value.is_object());
+    assert_eq!(
+        value.pointer("/list/1").and_then(JsonValue::as_str),
+        Some("two")
+    );
+    assert_eq!(value.pointer("/list/2"), Some(&JsonValue::Null));
+    assert_eq!(value.get("ok"), Some(&JsonValue::Bool(true)));
+
+    // Types JSON lacks become strings
+    let value = vaerdi::json_value!([date!(2024 - 01 - 02), 'c']);
+    assert_eq!(value, parse_json_value(r#"["2024-01-02", "c"]"#).unwrap());
+}
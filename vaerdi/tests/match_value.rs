@@ -0,0 +1,128 @@
+use vaerdi::{convert::ConvertError, match_value, value, Value};
+
+fn describe(value: &Value) -> Result<String, ConvertError> {
+    match_value!(value {
+        { "user": { "id": id @ u32, "name": name @ String, "tags": [first, ..] } } => {
+            format!("user {id} {name} tagged {first}")
+        }
+        { "error": message @ String } => format!("error: {message}"),
+        [_, .., last @ i32] => format!("list ending in {last}"),
+        null => String::from("nothing"),
+    })
+}
+
+#[test]
+fn nested() {
+    let user = value!({
+        "user": {"id": 1, "name": "vaerdi", "tags": ["a", "b"], "extra": true}
+    });
+    assert_eq!(describe(&user).unwrap(), "user 1 vaerdi tagged a");
+    assert_eq!(
+        describe(&value!({"error": "failed"})).unwrap(),
+        "error: failed"
+    );
+    assert_eq!(describe(&value!([1, 2, 3])).unwrap(), "list ending in 3");
+    assert_eq!(describe(&value!(null)).unwrap(), "nothing");
+}
+
+#[test]
+fn bindings() {
+    let value = value!({"point": {"x": 1, "y": 2}, "items": [1, 2, 3, 4]});
+    let ret = match_value!(value {
+        {
+            "point": point @ { "x": x @ i32, "y": _ @ i32 },
+            "items": [first @ i32, second, .., last @ i32]
+        } => (point.clone(), x, first, second.clone(), last),
+    })
+    .unwrap();
+
+    assert_eq!(ret, (value!({"x": 1, "y": 2}), 1, 1, value!(2), 4));
+}
+
+#[test]
+fn literals() {
+    let kind = |value: Value| {
+        match_value!(value {
+            { "kind": "circle", "r": r @ f64 } => r,
+            { "kind": "square", "side": side @ f64 } => side * side,
+            { "kind": true } => -1.0,
+            { "kind": -1 } => -2.0,
+        })
+    };
+
+    assert_eq!(kind(value!({"kind": "circle", "r": 2.0})).unwrap(), 2.0);
+    assert_eq!(kind(value!({"kind": "square", "side": 3.0})).unwrap(), 9.0);
+    assert_eq!(kind(value!({"kind": true})).unwrap(), -1.0);
+    assert_eq!(kind(value!({"kind": -1})).unwrap(), -2.0);
+}
+
+#[test]
+fn guards() {
+    let size = |value: Value| {
+        match_value!(value {
+            { "n": n @ i32 } if n > 100 => "large",
+            { "n": n @ i32 } if n > 10 => "medium",
+            { "n": _ } => "small",
+        })
+    };
+
+    assert_eq!(size(value!({"n": 1000})).unwrap(), "large");
+    assert_eq!(size(value!({"n": 50})).unwrap(), "medium");
+    assert_eq!(size(value!({"n": 5})).unwrap(), "small");
+    assert_eq!(size(value!({"n": "many"})).unwrap(), "small");
+}
+
+#[test]
+fn first_matching_arm_wins() {
+    let value = value!({"a": 1, "b": 2});
+    let ret = match_value!(value {
+        { "c": _ } => "c",
+        { "a": _ } => "a",
+        { "a": _, "b": _ } => "a and b",
+        _ => "anything",
+    });
+    assert_eq!(ret.unwrap(), "a");
+
+    let ret = match_value!(value!("text") {
+        _ @ i32 => "number",
+        _ => "anything",
+    });
+    assert_eq!(ret.unwrap(), "anything");
+}
+
+#[test]
+fn no_arm_matches() {
+    let value = value!({"user": {"id": "one"}, "items": [1]});
+    let err = match_value!(value {
+        { "user": { "id": id @ u32 } } => id,
+        { "items": [_, _] } => 0,
+        { "error": _ } => 0,
+        [] => 0,
+        { "items": [n @ u32] } if n > 1 => n,
+    })
+    .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "5 errors: user.id: expected: u32, found: string; \
+         items: expected length: 2, found: 1; \
+         missing field: error; \
+         expected: list, found: map; \
+         the guard did not match"
+    );
+}
+
+fn parse(value: &Value) -> Result<u32, ConvertError> {
+    let id = match_value!(value {
+        { "id": id @ u32 } => id,
+        { "missing": _ } => return Ok(0),
+    })?;
+    Ok(id + 1)
+}
+
+#[test]
+fn control_flow_in_bodies() {
+    assert_eq!(parse(&value!({"id": 1})).unwrap(), 2);
+    assert_eq!(parse(&value!({"missing": null})).unwrap(), 0);
+    assert!(parse(&value!({})).is_err());
+}
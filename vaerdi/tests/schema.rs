@@ -0,0 +1,241 @@
+// The types only exist for their schema, their fields are never read
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+
+use vaerdi::{
+    schema::{EnumRepr, EnumSchema, StructSchema, TypeSchema, ValueSchema},
+    Type,
+};
+
+fn primitive(ty: Type) -> TypeSchema {
+    TypeSchema::Primitive(ty)
+}
+
+#[derive(ValueSchema)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn point() -> TypeSchema {
+    StructSchema::new("Point")
+        .field("x", primitive(Type::I32), true)
+        .field("y", primitive(Type::I32), true)
+        .into()
+}
+
+#[derive(ValueSchema)]
+#[vaerdi(rename_all = "camelCase")]
+struct User {
+    user_name: String,
+    #[vaerdi(rename = "mail")]
+    email: Option<String>,
+    #[vaerdi(default)]
+    logins: u32,
+    #[vaerdi(skip)]
+    cache: Vec<u8>,
+    #[vaerdi(flatten)]
+    location: Point,
+    #[vaerdi(with = "module")]
+    raw: u8,
+    tags: BTreeMap<String, bool>,
+}
+
+#[test]
+fn structs() {
+    assert_eq!(
+        User::schema(),
+        StructSchema::new("User")
+            .field("userName", primitive(Type::String), true)
+            .field("mail", TypeSchema::optional(primitive(Type::String)), false)
+            .field("logins", primitive(Type::U32), false)
+            .field("x", primitive(Type::I32), true)
+            .field("y", primitive(Type::I32), true)
+            .field("raw", TypeSchema::Any, true)
+            .field("tags", TypeSchema::map(primitive(Type::Bool)), true)
+            .into()
+    );
+}
+
+#[derive(ValueSchema)]
+struct Unit;
+
+#[derive(ValueSchema)]
+struct Meters(f64);
+
+#[derive(ValueSchema)]
+struct Pair(i32, Option<String>);
+
+#[test]
+fn structs_without_named_fields() {
+    assert_eq!(Unit::schema(), TypeSchema::Null);
+    assert_eq!(Meters::schema(), primitive(Type::F64));
+    assert_eq!(
+        Pair::schema(),
+        TypeSchema::tuple([
+            primitive(Type::I32),
+            TypeSchema::optional(primitive(Type::String))
+        ])
+    );
+}
+
+#[derive(ValueSchema)]
+enum Color {
+    Red,
+    #[vaerdi(rename = "blue")]
+    Blue,
+}
+
+#[derive(ValueSchema)]
+enum Level {
+    Low = 1,
+    High = 10,
+}
+
+#[test]
+fn unit_enums() {
+    assert_eq!(
+        Color::schema(),
+        EnumSchema::new("Color", EnumRepr::Label)
+            .variant("Red", None, None)
+            .variant("blue", None, None)
+            .into()
+    );
+    assert_eq!(
+        Level::schema(),
+        EnumSchema::new("Level", EnumRepr::Number)
+            .variant("Low", Some(1), None)
+            .variant("High", Some(10), None)
+            .into()
+    );
+}
+
+macro_rules! shape {
+    ($name: ident, $($attr: tt)*) => {
+        #[derive(ValueSchema)]
+        $($attr)*
+        enum $name {
+            Empty,
+            Point(Point),
+            Pair(i32, bool),
+            Named { radius: f64, label: Option<String> },
+        }
+    };
+}
+
+shape!(Adjacent,);
+shape!(External, #[vaerdi(external)]);
+shape!(Untagged, #[vaerdi(untagged, rename_all = "snake_case")]);
+
+#[derive(ValueSchema)]
+#[vaerdi(tag = "kind")]
+enum Internal {
+    Empty,
+    Point(Point),
+    Named { radius: f64 },
+}
+
+fn named() -> TypeSchema {
+    StructSchema::new("Named")
+        .field("radius", primitive(Type::F64), true)
+        .field(
+            "label",
+            TypeSchema::optional(primitive(Type::String)),
+            false,
+        )
+        .into()
+}
+
+fn variants(schema: EnumSchema) -> EnumSchema {
+    schema
+        .variant("Empty", None, None)
+        .variant("Point", None, Some(point()))
+        .variant(
+            "Pair",
+            None,
+            Some(TypeSchema::tuple([
+                primitive(Type::I32),
+                primitive(Type::Bool),
+            ])),
+        )
+        .variant("Named", None, Some(named()))
+}
+
+#[test]
+fn enum_reprs() {
+    let adjacent = EnumRepr::Adjacent {
+        tag: "type".into(),
+        content: "content".into(),
+    };
+    assert_eq!(
+        Adjacent::schema(),
+        variants(EnumSchema::new("Adjacent", adjacent)).into()
+    );
+    assert_eq!(
+        External::schema(),
+        variants(EnumSchema::new("External", EnumRepr::External)).into()
+    );
+
+    assert_eq!(
+        Untagged::schema(),
+        EnumSchema::new("Untagged", EnumRepr::Untagged)
+            .variant("empty", None, None)
+            .variant("point", None, Some(point()))
+            .variant(
+                "pair",
+                None,
+                Some(TypeSchema::tuple([
+                    primitive(Type::I32),
+                    primitive(Type::Bool)
+                ]))
+            )
+            .variant(
+                "named",
+                None,
+                Some(
+                    StructSchema::new("named")
+                        .field("radius", primitive(Type::F64), true)
+                        .field(
+                            "label",
+                            TypeSchema::optional(primitive(Type::String)),
+                            false
+                        )
+                        .into()
+                )
+            )
+            .into()
+    );
+
+    assert_eq!(
+        Internal::schema(),
+        EnumSchema::new("Internal", EnumRepr::Internal { tag: "kind".into() })
+            .variant("Empty", None, None)
+            .variant("Point", None, Some(point()))
+            .variant(
+                "Named",
+                None,
+                Some(
+                    StructSchema::new("Named")
+                        .field("radius", primitive(Type::F64), true)
+                        .into()
+                )
+            )
+            .into()
+    );
+}
+
+#[test]
+fn generics() {
+    #[derive(ValueSchema)]
+    struct Wrap<T> {
+        inner: Vec<T>,
+    }
+
+    assert_eq!(
+        Wrap::<u8>::schema(),
+        StructSchema::new("Wrap")
+            .field("inner", TypeSchema::list(primitive(Type::U8)), true)
+            .into()
+    );
+}
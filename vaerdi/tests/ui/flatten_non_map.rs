@@ -0,0 +1,10 @@
+use vaerdi::{FromValue, IntoValue};
+
+#[derive(FromValue, IntoValue)]
+struct Flattened {
+    id: u32,
+    #[vaerdi(flatten)]
+    tags: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use vaerdi::{FromValue, IntoValue};
+
+#[derive(FromValue, IntoValue)]
+#[vaerdi(tag = "kind")]
+enum Message {
+    Text(String),
+}
+
+fn main() {}
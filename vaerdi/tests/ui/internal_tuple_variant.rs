@@ -0,0 +1,9 @@
+use vaerdi::{FromValue, IntoValue};
+
+#[derive(FromValue, IntoValue)]
+#[vaerdi(tag = "kind")]
+enum Shape {
+    Point(i32, i32),
+}
+
+fn main() {}
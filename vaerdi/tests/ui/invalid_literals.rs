@@ -0,0 +1,10 @@
+use vaerdi::{date, datetime, time, uuid, value};
+
+fn main() {
+    let _ = uuid!("67e55044-10b1-426f-9247");
+    let _ = date!(2023 - 02 - 29);
+    let _ = time!(24:00);
+    let _ = datetime!("2024-01-02 12:30:60");
+    let _ = value!({ "id": uuid!("not a uuid") });
+    let _ = value!([1u128]);
+}
@@ -0,0 +1,127 @@
+use vaerdi::{convert::FromValue as _, validate::Validate, value, FromValue};
+
+#[derive(Debug, FromValue, Validate)]
+struct Account {
+    #[vaerdi(validate(len(min = 3, max = 8)))]
+    name: String,
+    #[vaerdi(validate(min = 18, max = 130))]
+    age: u8,
+    #[vaerdi(validate(email))]
+    email: Option<String>,
+}
+
+#[test]
+fn valid() {
+    let account = Account::from_value(value!({"name": "vaerdi", "age": 30})).unwrap();
+    assert!(account.validate().is_ok());
+}
+
+#[test]
+fn every_failing_rule_is_reported() {
+    let err = Account::from_value(value!({"name": "v", "age": 12, "email": "nope"})).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "3 errors: name: invalid value: length must be at least 3, found 1; \
+         age: invalid value: must be at least 18, found 12; \
+         email: invalid value: not a valid email address"
+    );
+}
+
+#[test]
+fn validate_existing_value() {
+    let account = Account {
+        name: "vaerdi".into(),
+        age: 200,
+        email: None,
+    };
+    assert_eq!(
+        account.validate().unwrap_err().to_string(),
+        "age: invalid value: must be at most 130, found 200"
+    );
+}
+
+#[derive(Debug, FromValue)]
+struct Id {
+    #[vaerdi(validate(min = 9_007_199_254_740_993))]
+    id: u64,
+}
+
+#[test]
+fn large_integers_compare_exactly() {
+    // Both are 2^53 as a float
+    assert!(Id::from_value(value!({"id": 9_007_199_254_740_992u64})).is_err());
+    let id = Id::from_value(value!({"id": 9_007_199_254_740_993u64})).unwrap();
+    assert_eq!(id.id, 9_007_199_254_740_993);
+}
+
+#[derive(Debug, FromValue, Validate)]
+struct Measure {
+    #[vaerdi(validate(min = 1, max = 10))]
+    ratio: f64,
+    #[vaerdi(validate(min = -5, max = 5))]
+    offset: Option<f32>,
+    #[vaerdi(validate(min = 0))]
+    amount: vaerdi::Number,
+    #[vaerdi(validate(min = 0.5))]
+    scale: f64,
+}
+
+#[test]
+fn integer_bounds_on_floats() {
+    let measure =
+        Measure::from_value(value!({"ratio": 2.5, "offset": -4.5f32, "amount": 3, "scale": 0.5}))
+            .unwrap();
+    assert_eq!(measure.ratio, 2.5);
+
+    let err = Measure::from_value(
+        value!({"ratio": 10.5, "offset": -5.5f32, "amount": -1, "scale": 0.25}),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "4 errors: ratio: invalid value: must be at most 10, found 10.5; \
+         offset: invalid value: must be at least -5, found -5.5; \
+         amount: invalid value: must be at least 0, found -1; \
+         scale: invalid value: must be at least 0.5, found 0.25"
+    );
+}
+
+#[cfg(feature = "regex")]
+mod pattern {
+    use super::*;
+
+    #[derive(Debug, FromValue)]
+    struct Slug {
+        #[vaerdi(validate(pattern = "^[a-z-]+$"))]
+        slug: String,
+    }
+
+    #[derive(Debug, Validate)]
+    struct Invalid {
+        #[vaerdi(validate(pattern = "("))]
+        text: String,
+    }
+
+    #[test]
+    fn pattern() {
+        let slug = Slug::from_value(value!({"slug": "a-slug"})).unwrap();
+        assert_eq!(slug.slug, "a-slug");
+        assert_eq!(
+            Slug::from_value(value!({"slug": "A slug"}))
+                .unwrap_err()
+                .to_string(),
+            "slug: invalid value: does not match pattern \"^[a-z-]+$\""
+        );
+    }
+
+    #[test]
+    fn invalid_pattern_is_an_error() {
+        let invalid = Invalid {
+            text: "text".into(),
+        };
+        let err = invalid.validate().unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("text: invalid value: invalid pattern \"(\""));
+    }
+}